@@ -0,0 +1,120 @@
+use async_trait::async_trait;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{PoisonError, RwLock};
+
+/// An error raised by a cache backend.
+#[derive(Debug)]
+pub enum CacheError {
+    /// The backend could not be reached.
+    Io(std::io::Error),
+    /// The backend answered with an error or an unexpected reply.
+    Backend(String),
+    /// A cached value could not be encoded or decoded.
+    Serde(serde_json::Error),
+}
+
+impl fmt::Display for CacheError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CacheError::Io(error) => write!(f, "cache backend I/O error: {}", error),
+            CacheError::Backend(message) => write!(f, "cache backend error: {}", message),
+            CacheError::Serde(error) => write!(f, "cache value error: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for CacheError {}
+
+impl From<std::io::Error> for CacheError {
+    fn from(error: std::io::Error) -> Self {
+        CacheError::Io(error)
+    }
+}
+
+impl From<serde_json::Error> for CacheError {
+    fn from(error: serde_json::Error) -> Self {
+        CacheError::Serde(error)
+    }
+}
+
+/// Storage for the [`Cache`](super::Cache).
+///
+/// A backend stores JSON values in named collections, each a map from a field
+/// (usually a snowflake) to a value. The cache decides which collections exist,
+/// for example `"guilds"` or `"members:<guild_id>"`. Implement this trait to
+/// keep the cache somewhere other than process memory.
+#[async_trait]
+pub trait CacheBackend: Send + Sync + fmt::Debug {
+    /// Returns the value stored under `field` in `collection`.
+    async fn get(&self, collection: &str, field: &str) -> Result<Option<Value>, CacheError>;
+
+    /// Stores `value` under `field` in `collection`, replacing any previous value.
+    async fn insert(&self, collection: &str, field: &str, value: Value) -> Result<(), CacheError>;
+
+    /// Removes `field` from `collection`.
+    async fn remove(&self, collection: &str, field: &str) -> Result<(), CacheError>;
+
+    /// Returns the fields of `collection`, in no particular order.
+    async fn fields(&self, collection: &str) -> Result<Vec<String>, CacheError>;
+
+    /// Returns the values of `collection`, in no particular order.
+    async fn values(&self, collection: &str) -> Result<Vec<Value>, CacheError>;
+
+    /// Removes a whole collection.
+    async fn clear(&self, collection: &str) -> Result<(), CacheError>;
+}
+
+/// The default backend, which keeps everything in process memory.
+#[derive(Debug, Default)]
+pub struct InMemoryBackend {
+    collections: RwLock<HashMap<String, HashMap<String, Value>>>,
+}
+
+impl InMemoryBackend {
+    /// Creates an empty backend.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl CacheBackend for InMemoryBackend {
+    async fn get(&self, collection: &str, field: &str) -> Result<Option<Value>, CacheError> {
+        let collections = self.collections.read().unwrap_or_else(PoisonError::into_inner);
+        Ok(collections.get(collection).and_then(|values| values.get(field)).cloned())
+    }
+
+    async fn insert(&self, collection: &str, field: &str, value: Value) -> Result<(), CacheError> {
+        let mut collections = self.collections.write().unwrap_or_else(PoisonError::into_inner);
+        collections.entry(collection.to_string()).or_default().insert(field.to_string(), value);
+        Ok(())
+    }
+
+    async fn remove(&self, collection: &str, field: &str) -> Result<(), CacheError> {
+        let mut collections = self.collections.write().unwrap_or_else(PoisonError::into_inner);
+        if let Some(values) = collections.get_mut(collection) {
+            values.remove(field);
+            if values.is_empty() {
+                collections.remove(collection);
+            }
+        }
+        Ok(())
+    }
+
+    async fn fields(&self, collection: &str) -> Result<Vec<String>, CacheError> {
+        let collections = self.collections.read().unwrap_or_else(PoisonError::into_inner);
+        Ok(collections.get(collection).map(|values| values.keys().cloned().collect()).unwrap_or_default())
+    }
+
+    async fn values(&self, collection: &str) -> Result<Vec<Value>, CacheError> {
+        let collections = self.collections.read().unwrap_or_else(PoisonError::into_inner);
+        Ok(collections.get(collection).map(|values| values.values().cloned().collect()).unwrap_or_default())
+    }
+
+    async fn clear(&self, collection: &str) -> Result<(), CacheError> {
+        self.collections.write().unwrap_or_else(PoisonError::into_inner).remove(collection);
+        Ok(())
+    }
+}
@@ -0,0 +1,406 @@
+use reqwest::Client;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::error::Error;
+use std::sync::Arc;
+
+use crate::event::Event;
+use crate::model::{Channel, Guild, Member, Message, Role};
+use crate::utils::{channel, guild};
+
+mod backend;
+#[cfg(feature = "redis")]
+mod redis;
+
+pub use backend::{CacheBackend, CacheError, InMemoryBackend};
+#[cfg(feature = "redis")]
+pub use redis::{encode_command, RedisBackend, RedisReply};
+
+/// Chooses which resources the [`Cache`] keeps.
+///
+/// # Examples
+///
+/// ```
+/// use Rust_Discord_API::cache::{Cache, CacheConfig};
+///
+/// let cache = Cache::new(CacheConfig::new().members(false).message_capacity(50));
+/// ```
+#[derive(Clone, Debug)]
+pub struct CacheConfig {
+    guilds: bool,
+    channels: bool,
+    members: bool,
+    roles: bool,
+    messages: bool,
+    message_capacity: usize,
+}
+
+impl CacheConfig {
+    /// Creates a config that caches everything and keeps the last 100 messages per channel.
+    pub fn new() -> Self {
+        Self { guilds: true, channels: true, members: true, roles: true, messages: true, message_capacity: 100 }
+    }
+
+    /// Sets whether guilds are cached.
+    pub fn guilds(mut self, enabled: bool) -> Self {
+        self.guilds = enabled;
+        self
+    }
+
+    /// Sets whether channels and threads are cached.
+    pub fn channels(mut self, enabled: bool) -> Self {
+        self.channels = enabled;
+        self
+    }
+
+    /// Sets whether guild members are cached.
+    pub fn members(mut self, enabled: bool) -> Self {
+        self.members = enabled;
+        self
+    }
+
+    /// Sets whether roles are cached.
+    pub fn roles(mut self, enabled: bool) -> Self {
+        self.roles = enabled;
+        self
+    }
+
+    /// Sets whether messages are cached.
+    pub fn messages(mut self, enabled: bool) -> Self {
+        self.messages = enabled;
+        self
+    }
+
+    /// Sets how many of the most recent messages are kept per channel.
+    pub fn message_capacity(mut self, capacity: usize) -> Self {
+        self.message_capacity = capacity;
+        self
+    }
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A cache of guilds, channels, members, roles and messages, kept up to date
+/// from gateway events.
+///
+/// The data lives in a [`CacheBackend`]: process memory by default, or a
+/// shared store such as [`RedisBackend`] when several bot processes should see
+/// the same state. Share it between tasks with an `Arc<Cache>` and feed it
+/// every dispatch event with [`Cache::update`]. The `*_or_fetch` methods read
+/// from the cache and fall back to the REST API, caching what they fetch.
+#[derive(Debug)]
+pub struct Cache {
+    config: CacheConfig,
+    backend: Arc<dyn CacheBackend>,
+}
+
+const GUILDS: &str = "guilds";
+const CHANNELS: &str = "channels";
+
+fn members_of(guild_id: &str) -> String {
+    format!("members:{}", guild_id)
+}
+
+fn roles_of(guild_id: &str) -> String {
+    format!("roles:{}", guild_id)
+}
+
+fn messages_of(channel_id: &str) -> String {
+    format!("messages:{}", channel_id)
+}
+
+/// Orders snowflakes by age, falling back to string order for malformed IDs.
+fn snowflake_key(id: &str) -> (u64, &str) {
+    (id.parse().unwrap_or(u64::MAX), id)
+}
+
+fn encode<T: Serialize>(value: &T) -> Result<Value, CacheError> {
+    Ok(serde_json::to_value(value)?)
+}
+
+fn decode<T: DeserializeOwned>(value: Option<Value>) -> Result<Option<T>, CacheError> {
+    Ok(value.map(serde_json::from_value).transpose()?)
+}
+
+fn decode_all<T: DeserializeOwned>(values: Vec<Value>) -> Result<Vec<T>, CacheError> {
+    Ok(values.into_iter().map(serde_json::from_value).collect::<Result<_, _>>()?)
+}
+
+impl Cache {
+    /// Creates an empty cache kept in process memory.
+    pub fn new(config: CacheConfig) -> Self {
+        Self::with_backend(config, Arc::new(InMemoryBackend::new()))
+    }
+
+    /// Creates a cache that stores its data in `backend`.
+    pub fn with_backend(config: CacheConfig, backend: Arc<dyn CacheBackend>) -> Self {
+        Self { config, backend }
+    }
+
+    /// Returns the backend the cache stores its data in.
+    pub fn backend(&self) -> &Arc<dyn CacheBackend> {
+        &self.backend
+    }
+
+    /// Applies a gateway event to the cache.
+    pub async fn update(&self, event: &Event) -> Result<(), CacheError> {
+        match event {
+            Event::GuildCreate(guild) | Event::GuildUpdate(guild) => self.insert_guild(guild.clone()).await,
+            Event::GuildDelete(deleted) => {
+                if !deleted.unavailable {
+                    return self.remove_guild(&deleted.id).await;
+                }
+                match self.guild(&deleted.id).await? {
+                    Some(mut guild) => {
+                        guild.unavailable = true;
+                        self.backend.insert(GUILDS, &guild.id, encode(&guild)?).await
+                    }
+                    None => Ok(()),
+                }
+            }
+            Event::ChannelCreate(channel) | Event::ChannelUpdate(channel)
+            | Event::ThreadCreate(channel) | Event::ThreadUpdate(channel) => self.insert_channel(channel.clone()).await,
+            Event::ChannelDelete(channel) => self.remove_channel(&channel.id).await,
+            Event::ThreadDelete(thread) => self.remove_channel(&thread.id).await,
+            Event::GuildMemberAdd(added) => self.insert_member(&added.guild_id, added.member.clone()).await,
+            Event::GuildMemberUpdate(updated) => {
+                let mut member = updated.member.clone();
+                // Member updates leave out the voice state flags.
+                if let Some(user) = &member.user {
+                    if let Some(previous) = self.member(&updated.guild_id, &user.id).await? {
+                        member.deaf = previous.deaf;
+                        member.mute = previous.mute;
+                    }
+                }
+                self.insert_member(&updated.guild_id, member).await
+            }
+            Event::GuildMembersChunk(chunk) => {
+                for member in &chunk.members {
+                    self.insert_member(&chunk.guild_id, member.clone()).await?;
+                }
+                Ok(())
+            }
+            Event::GuildMemberRemove(removed) => self.backend.remove(&members_of(&removed.guild_id), &removed.user.id).await,
+            Event::GuildRoleCreate(created) | Event::GuildRoleUpdate(created) => self.insert_role(&created.guild_id, created.role.clone()).await,
+            Event::GuildRoleDelete(deleted) => self.backend.remove(&roles_of(&deleted.guild_id), &deleted.role_id).await,
+            Event::MessageCreate(message) => self.insert_message(message.clone()).await,
+            Event::MessageUpdate(update) => {
+                let collection = messages_of(&update.channel_id);
+                let Some(Value::Object(mut merged)) = self.backend.get(&collection, &update.id).await? else {
+                    return Ok(());
+                };
+                // Merge the changed fields into the cached message.
+                merged.extend(update.fields.clone());
+                if let Ok(updated) = serde_json::from_value::<Message>(Value::Object(merged)) {
+                    self.backend.insert(&collection, &update.id, encode(&updated)?).await?;
+                }
+                Ok(())
+            }
+            Event::MessageDelete(deleted) => self.backend.remove(&messages_of(&deleted.channel_id), &deleted.id).await,
+            Event::MessageDeleteBulk(deleted) => {
+                let collection = messages_of(&deleted.channel_id);
+                for id in &deleted.ids {
+                    self.backend.remove(&collection, id).await?;
+                }
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Caches a guild along with the channels, threads, roles and members it carries.
+    pub async fn insert_guild(&self, mut guild: Guild) -> Result<(), CacheError> {
+        for mut channel in guild.channels.drain(..).chain(guild.threads.drain(..)) {
+            channel.guild_id = Some(guild.id.clone());
+            self.insert_channel(channel).await?;
+        }
+        for role in guild.roles.drain(..) {
+            self.insert_role(&guild.id, role).await?;
+        }
+        for member in guild.members.drain(..) {
+            self.insert_member(&guild.id, member).await?;
+        }
+        if self.config.guilds {
+            self.backend.insert(GUILDS, &guild.id, encode(&guild)?).await?;
+        }
+        Ok(())
+    }
+
+    /// Caches a channel or thread.
+    pub async fn insert_channel(&self, channel: Channel) -> Result<(), CacheError> {
+        if self.config.channels {
+            self.backend.insert(CHANNELS, &channel.id, encode(&channel)?).await?;
+        }
+        Ok(())
+    }
+
+    /// Caches a guild member. Members without a user are ignored.
+    pub async fn insert_member(&self, guild_id: &str, member: Member) -> Result<(), CacheError> {
+        if !self.config.members {
+            return Ok(());
+        }
+        if let Some(user) = &member.user {
+            self.backend.insert(&members_of(guild_id), &user.id, encode(&member)?).await?;
+        }
+        Ok(())
+    }
+
+    /// Caches a role.
+    pub async fn insert_role(&self, guild_id: &str, role: Role) -> Result<(), CacheError> {
+        if self.config.roles {
+            self.backend.insert(&roles_of(guild_id), &role.id, encode(&role)?).await?;
+        }
+        Ok(())
+    }
+
+    /// Caches a message, evicting the oldest messages of the channel when it is full.
+    pub async fn insert_message(&self, message: Message) -> Result<(), CacheError> {
+        if !self.config.messages || self.config.message_capacity == 0 {
+            return Ok(());
+        }
+        let collection = messages_of(&message.channel_id);
+        self.backend.insert(&collection, &message.id, encode(&message)?).await?;
+
+        let mut ids = self.backend.fields(&collection).await?;
+        if ids.len() > self.config.message_capacity {
+            ids.sort_by(|a, b| snowflake_key(a).cmp(&snowflake_key(b)));
+            for id in &ids[..ids.len() - self.config.message_capacity] {
+                self.backend.remove(&collection, id).await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn remove_guild(&self, guild_id: &str) -> Result<(), CacheError> {
+        self.backend.remove(GUILDS, guild_id).await?;
+        self.backend.clear(&members_of(guild_id)).await?;
+        self.backend.clear(&roles_of(guild_id)).await?;
+
+        for channel in self.guild_channels(guild_id).await? {
+            self.remove_channel(&channel.id).await?;
+        }
+        Ok(())
+    }
+
+    async fn remove_channel(&self, channel_id: &str) -> Result<(), CacheError> {
+        self.backend.remove(CHANNELS, channel_id).await?;
+        self.backend.clear(&messages_of(channel_id)).await
+    }
+
+    /// Returns a cached guild. Its channels, roles and members are cached separately.
+    pub async fn guild(&self, guild_id: &str) -> Result<Option<Guild>, CacheError> {
+        decode(self.backend.get(GUILDS, guild_id).await?)
+    }
+
+    /// Returns the IDs of all cached guilds.
+    pub async fn guild_ids(&self) -> Result<Vec<String>, CacheError> {
+        self.backend.fields(GUILDS).await
+    }
+
+    /// Returns a cached channel or thread.
+    pub async fn channel(&self, channel_id: &str) -> Result<Option<Channel>, CacheError> {
+        decode(self.backend.get(CHANNELS, channel_id).await?)
+    }
+
+    /// Returns the cached channels and threads of a guild.
+    pub async fn guild_channels(&self, guild_id: &str) -> Result<Vec<Channel>, CacheError> {
+        let channels: Vec<Channel> = decode_all(self.backend.values(CHANNELS).await?)?;
+        Ok(channels.into_iter().filter(|channel| channel.guild_id.as_deref() == Some(guild_id)).collect())
+    }
+
+    /// Returns a cached guild member.
+    pub async fn member(&self, guild_id: &str, user_id: &str) -> Result<Option<Member>, CacheError> {
+        decode(self.backend.get(&members_of(guild_id), user_id).await?)
+    }
+
+    /// Returns the cached members of a guild.
+    pub async fn guild_members(&self, guild_id: &str) -> Result<Vec<Member>, CacheError> {
+        decode_all(self.backend.values(&members_of(guild_id)).await?)
+    }
+
+    /// Returns a cached role.
+    pub async fn role(&self, guild_id: &str, role_id: &str) -> Result<Option<Role>, CacheError> {
+        decode(self.backend.get(&roles_of(guild_id), role_id).await?)
+    }
+
+    /// Returns the cached roles of a guild.
+    pub async fn guild_roles(&self, guild_id: &str) -> Result<Vec<Role>, CacheError> {
+        decode_all(self.backend.values(&roles_of(guild_id)).await?)
+    }
+
+    /// Returns a cached message.
+    pub async fn message(&self, channel_id: &str, message_id: &str) -> Result<Option<Message>, CacheError> {
+        decode(self.backend.get(&messages_of(channel_id), message_id).await?)
+    }
+
+    /// Returns the cached messages of a channel, oldest first.
+    pub async fn channel_messages(&self, channel_id: &str) -> Result<Vec<Message>, CacheError> {
+        let mut messages: Vec<Message> = decode_all(self.backend.values(&messages_of(channel_id)).await?)?;
+        messages.sort_by(|a, b| snowflake_key(&a.id).cmp(&snowflake_key(&b.id)));
+        Ok(messages)
+    }
+
+    /// Returns a guild from the cache, or fetches and caches it.
+    pub async fn guild_or_fetch(&self, client: &Client, token: &str, guild_id: &str) -> Result<Guild, Box<dyn Error>> {
+        if let Some(guild) = self.guild(guild_id).await? {
+            return Ok(guild);
+        }
+        let guild: Guild = serde_json::from_value(guild::get_guild(client, token, guild_id).await?)?;
+        self.insert_guild(guild.clone()).await?;
+        Ok(guild)
+    }
+
+    /// Returns a channel from the cache, or fetches and caches it.
+    pub async fn channel_or_fetch(&self, client: &Client, token: &str, channel_id: &str) -> Result<Channel, Box<dyn Error>> {
+        if let Some(channel) = self.channel(channel_id).await? {
+            return Ok(channel);
+        }
+        let channel: Channel = serde_json::from_value(channel::fetch_channel_info(client, token, channel_id).await?)?;
+        self.insert_channel(channel.clone()).await?;
+        Ok(channel)
+    }
+
+    /// Returns a guild member from the cache, or fetches and caches it.
+    pub async fn member_or_fetch(&self, client: &Client, token: &str, guild_id: &str, user_id: &str) -> Result<Member, Box<dyn Error>> {
+        if let Some(member) = self.member(guild_id, user_id).await? {
+            return Ok(member);
+        }
+        let member: Member = serde_json::from_value(guild::get_guild_member(client, token, guild_id, user_id).await?)?;
+        self.insert_member(guild_id, member.clone()).await?;
+        Ok(member)
+    }
+
+    /// Returns the roles of a guild from the cache, or fetches and caches them.
+    pub async fn guild_roles_or_fetch(&self, client: &Client, token: &str, guild_id: &str) -> Result<Vec<Role>, Box<dyn Error>> {
+        let cached = self.guild_roles(guild_id).await?;
+        if !cached.is_empty() {
+            return Ok(cached);
+        }
+        let roles: Vec<Role> = serde_json::from_value(guild::get_guild_roles(client, token, guild_id).await?)?;
+        for role in &roles {
+            self.insert_role(guild_id, role.clone()).await?;
+        }
+        Ok(roles)
+    }
+
+    /// Returns a message from the cache, or fetches and caches it.
+    pub async fn message_or_fetch(&self, client: &Client, token: &str, channel_id: &str, message_id: &str) -> Result<Message, Box<dyn Error>> {
+        if let Some(message) = self.message(channel_id, message_id).await? {
+            return Ok(message);
+        }
+        let message: Message = serde_json::from_value(channel::get_channel_message(client, token, channel_id, message_id).await?)?;
+        self.insert_message(message.clone()).await?;
+        Ok(message)
+    }
+}
+
+impl Default for Cache {
+    fn default() -> Self {
+        Self::new(CacheConfig::default())
+    }
+}
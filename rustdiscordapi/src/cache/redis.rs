@@ -0,0 +1,233 @@
+use async_trait::async_trait;
+use serde_json::Value;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio::sync::Mutex;
+
+use super::backend::{CacheBackend, CacheError};
+
+/// A reply to a Redis command.
+#[derive(Clone, Debug, PartialEq)]
+pub enum RedisReply {
+    Status(String),
+    Integer(i64),
+    Bulk(Option<Vec<u8>>),
+    Array(Option<Vec<RedisReply>>),
+}
+
+/// A cache backend that stores collections as Redis hashes, so several bot
+/// processes can share one cache.
+///
+/// It speaks the Redis serialization protocol (RESP) directly and works with
+/// Redis and compatible servers such as Valkey, KeyDB or Dragonfly. Each
+/// collection becomes a hash named `<prefix><collection>`.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::sync::Arc;
+/// use Rust_Discord_API::cache::{Cache, CacheConfig, RedisBackend};
+///
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let backend = RedisBackend::connect("redis://127.0.0.1:6379/0").await?.prefix("mybot:");
+/// let cache = Cache::with_backend(CacheConfig::new(), Arc::new(backend));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct RedisBackend {
+    address: String,
+    username: Option<String>,
+    password: Option<String>,
+    database: Option<u32>,
+    prefix: String,
+    connection: Mutex<Option<BufReader<TcpStream>>>,
+}
+
+impl RedisBackend {
+    /// Connects to a server given a `redis://[[user]:password@]host[:port][/database]` URL
+    /// or a plain `host:port` address.
+    pub async fn connect(url: &str) -> Result<Self, CacheError> {
+        let rest = url.strip_prefix("redis://").unwrap_or(url);
+        let (credentials, rest) = match rest.rsplit_once('@') {
+            Some((credentials, rest)) => (Some(credentials), rest),
+            None => (None, rest),
+        };
+        let (address, database) = match rest.split_once('/') {
+            Some((address, database)) if !database.is_empty() => {
+                let database = database.parse().map_err(|_| CacheError::Backend(format!("invalid database number: {}", database)))?;
+                (address, Some(database))
+            }
+            Some((address, _)) => (address, None),
+            None => (rest, None),
+        };
+        // Credentials without a colon are a password for the default user.
+        let (username, password) = match credentials.map(|credentials| credentials.split_once(':').unwrap_or(("", credentials))) {
+            Some((username, password)) => (Some(username), Some(password)),
+            None => (None, None),
+        };
+        let username = username.filter(|username| !username.is_empty()).map(str::to_string);
+        let password = password.filter(|password| !password.is_empty()).map(str::to_string);
+        let address = if address.contains(':') { address.to_string() } else { format!("{}:6379", address) };
+
+        let backend = Self { address, username, password, database, prefix: String::new(), connection: Mutex::new(None) };
+        backend.command(&["PING"]).await?;
+        Ok(backend)
+    }
+
+    /// Sets the prefix prepended to every hash name, to keep several bots apart on one server.
+    pub fn prefix(mut self, prefix: &str) -> Self {
+        self.prefix = prefix.to_string();
+        self
+    }
+
+    async fn open(&self) -> Result<BufReader<TcpStream>, CacheError> {
+        let mut connection = BufReader::new(TcpStream::connect(&self.address).await?);
+        match (&self.username, &self.password) {
+            (Some(username), Some(password)) => expect_ok(round_trip(&mut connection, &["AUTH", username, password]).await?)?,
+            (None, Some(password)) => expect_ok(round_trip(&mut connection, &["AUTH", password]).await?)?,
+            _ => {}
+        }
+        if let Some(database) = self.database {
+            expect_ok(round_trip(&mut connection, &["SELECT", &database.to_string()]).await?)?;
+        }
+        Ok(connection)
+    }
+
+    /// Sends a command and returns the reply. The connection is reopened on
+    /// the next command if the command failed or was cancelled, since the
+    /// rest of its reply may still be waiting to be read.
+    pub async fn command(&self, args: &[&str]) -> Result<RedisReply, CacheError> {
+        let mut connection = self.connection.lock().await;
+        let mut stream = match connection.take() {
+            Some(stream) => stream,
+            None => self.open().await?,
+        };
+
+        let reply = round_trip(&mut stream, args).await?;
+        *connection = Some(stream);
+        Ok(reply)
+    }
+
+    fn key(&self, collection: &str) -> String {
+        format!("{}{}", self.prefix, collection)
+    }
+}
+
+fn expect_ok(reply: RedisReply) -> Result<(), CacheError> {
+    match reply {
+        RedisReply::Status(_) => Ok(()),
+        other => Err(CacheError::Backend(format!("unexpected reply: {:?}", other))),
+    }
+}
+
+/// Encodes a command as a RESP array of bulk strings.
+pub fn encode_command(args: &[&str]) -> Vec<u8> {
+    let mut buffer = format!("*{}\r\n", args.len()).into_bytes();
+    for arg in args {
+        buffer.extend_from_slice(format!("${}\r\n", arg.len()).as_bytes());
+        buffer.extend_from_slice(arg.as_bytes());
+        buffer.extend_from_slice(b"\r\n");
+    }
+    buffer
+}
+
+async fn round_trip(stream: &mut BufReader<TcpStream>, args: &[&str]) -> Result<RedisReply, CacheError> {
+    stream.get_mut().write_all(&encode_command(args)).await?;
+    read_reply(stream).await
+}
+
+async fn read_line(stream: &mut BufReader<TcpStream>) -> Result<String, CacheError> {
+    let mut line = String::new();
+    if stream.read_line(&mut line).await? == 0 {
+        return Err(CacheError::Io(std::io::ErrorKind::UnexpectedEof.into()));
+    }
+    Ok(line.trim_end_matches("\r\n").to_string())
+}
+
+fn parse_length(value: &str) -> Result<i64, CacheError> {
+    value.parse().map_err(|_| CacheError::Backend(format!("invalid length: {}", value)))
+}
+
+async fn read_reply(stream: &mut BufReader<TcpStream>) -> Result<RedisReply, CacheError> {
+    let line = read_line(stream).await?;
+    let (kind, value) = line.split_at(line.len().min(1));
+
+    match kind {
+        "+" => Ok(RedisReply::Status(value.to_string())),
+        "-" => Err(CacheError::Backend(value.to_string())),
+        ":" => Ok(RedisReply::Integer(parse_length(value)?)),
+        "$" => {
+            let length = parse_length(value)?;
+            if length < 0 {
+                return Ok(RedisReply::Bulk(None));
+            }
+            let mut data = vec![0; length as usize + 2];
+            stream.read_exact(&mut data).await?;
+            data.truncate(length as usize);
+            Ok(RedisReply::Bulk(Some(data)))
+        }
+        "*" => {
+            let length = parse_length(value)?;
+            if length < 0 {
+                return Ok(RedisReply::Array(None));
+            }
+            let mut items = Vec::with_capacity(length as usize);
+            for _ in 0..length {
+                items.push(Box::pin(read_reply(stream)).await?);
+            }
+            Ok(RedisReply::Array(Some(items)))
+        }
+        _ => Err(CacheError::Backend(format!("unexpected reply: {}", line))),
+    }
+}
+
+fn bulk_strings(reply: RedisReply) -> Result<Vec<String>, CacheError> {
+    match reply {
+        RedisReply::Array(items) => items.unwrap_or_default()
+            .into_iter()
+            .map(|item| match item {
+                RedisReply::Bulk(Some(data)) => String::from_utf8(data).map_err(|error| CacheError::Backend(error.to_string())),
+                other => Err(CacheError::Backend(format!("unexpected reply: {:?}", other))),
+            })
+            .collect(),
+        other => Err(CacheError::Backend(format!("unexpected reply: {:?}", other))),
+    }
+}
+
+#[async_trait]
+impl CacheBackend for RedisBackend {
+    async fn get(&self, collection: &str, field: &str) -> Result<Option<Value>, CacheError> {
+        match self.command(&["HGET", &self.key(collection), field]).await? {
+            RedisReply::Bulk(Some(data)) => Ok(Some(serde_json::from_slice(&data)?)),
+            RedisReply::Bulk(None) => Ok(None),
+            other => Err(CacheError::Backend(format!("unexpected reply: {:?}", other))),
+        }
+    }
+
+    async fn insert(&self, collection: &str, field: &str, value: Value) -> Result<(), CacheError> {
+        self.command(&["HSET", &self.key(collection), field, &value.to_string()]).await?;
+        Ok(())
+    }
+
+    async fn remove(&self, collection: &str, field: &str) -> Result<(), CacheError> {
+        self.command(&["HDEL", &self.key(collection), field]).await?;
+        Ok(())
+    }
+
+    async fn fields(&self, collection: &str) -> Result<Vec<String>, CacheError> {
+        bulk_strings(self.command(&["HKEYS", &self.key(collection)]).await?)
+    }
+
+    async fn values(&self, collection: &str) -> Result<Vec<Value>, CacheError> {
+        bulk_strings(self.command(&["HVALS", &self.key(collection)]).await?)?
+            .iter()
+            .map(|value| serde_json::from_str(value).map_err(CacheError::from))
+            .collect()
+    }
+
+    async fn clear(&self, collection: &str) -> Result<(), CacheError> {
+        self.command(&["DEL", &self.key(collection)]).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,209 @@
+use futures::stream::{self, BoxStream, StreamExt};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::time::Instant;
+
+use crate::event::{Event, EventBus, ReactionAdd};
+use crate::model::Message;
+use crate::utils::interaction::{Interaction, InteractionType};
+
+type Filter<T> = Box<dyn Fn(&T) -> bool + Send + Sync>;
+
+/// Waits for gateway events that match a set of filters.
+///
+/// A collector subscribes to an [`EventBus`] when it is created, so events
+/// published after that point are seen even if the collector is awaited later.
+/// Collection stops when the timeout elapses, when `max` items have been
+/// collected, or when the bus is dropped.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use Rust_Discord_API::collector::Collector;
+/// use Rust_Discord_API::event::EventBus;
+///
+/// # async fn example(bus: EventBus, user_id: &str, channel_id: &str) {
+/// let reason = Collector::messages(&bus)
+///     .author(user_id)
+///     .channel(channel_id)
+///     .timeout(Duration::from_secs(60))
+///     .first()
+///     .await;
+/// # }
+/// ```
+pub struct Collector<T> {
+    receiver: broadcast::Receiver<Arc<Event>>,
+    extract: fn(&Event) -> Option<T>,
+    filters: Vec<Filter<T>>,
+    timeout: Option<Duration>,
+    max: Option<usize>,
+}
+
+impl<T: Send + 'static> Collector<T> {
+    /// Creates a collector for any kind of event. `extract` picks the events of
+    /// interest out of the stream and returns `None` for everything else.
+    pub fn new(bus: &EventBus, extract: fn(&Event) -> Option<T>) -> Self {
+        Self { receiver: bus.subscribe(), extract, filters: Vec::new(), timeout: None, max: None }
+    }
+
+    /// Only collects items for which `filter` returns `true`.
+    pub fn filter(mut self, filter: impl Fn(&T) -> bool + Send + Sync + 'static) -> Self {
+        self.filters.push(Box::new(filter));
+        self
+    }
+
+    /// Stops collecting once `timeout` has elapsed since collection started.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Stops collecting after `max` items.
+    pub fn max(mut self, max: usize) -> Self {
+        self.max = Some(max);
+        self
+    }
+
+    /// Returns the matching items as a stream.
+    pub fn stream(self) -> BoxStream<'static, T> {
+        let deadline = self.timeout.map(|timeout| Instant::now() + timeout);
+        let state = (self.receiver, self.extract, self.filters, self.max);
+
+        stream::unfold(state, move |(mut receiver, extract, filters, remaining)| async move {
+            if remaining == Some(0) {
+                return None;
+            }
+
+            loop {
+                let received = match deadline {
+                    Some(deadline) => tokio::time::timeout_at(deadline, receiver.recv()).await.ok()?,
+                    None => receiver.recv().await,
+                };
+
+                let event = match received {
+                    Ok(event) => event,
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => return None,
+                };
+
+                if let Some(item) = extract(&event) {
+                    if filters.iter().all(|filter| filter(&item)) {
+                        let remaining = remaining.map(|remaining| remaining - 1);
+                        return Some((item, (receiver, extract, filters, remaining)));
+                    }
+                }
+            }
+        })
+        .boxed()
+    }
+
+    /// Waits for the first matching item, or `None` on timeout.
+    pub async fn first(self) -> Option<T> {
+        self.max(1).stream().next().await
+    }
+
+    /// Collects matching items until the timeout elapses or `max` is reached.
+    pub async fn collect(self) -> Vec<T> {
+        self.stream().collect().await
+    }
+}
+
+impl Collector<Message> {
+    /// Creates a collector for new messages.
+    pub fn messages(bus: &EventBus) -> Self {
+        Self::new(bus, |event| match event {
+            Event::MessageCreate(message) => Some(message.clone()),
+            _ => None,
+        })
+    }
+
+    /// Only collects messages sent by the given user.
+    pub fn author(self, user_id: &str) -> Self {
+        let user_id = user_id.to_string();
+        self.filter(move |message| message.author.id == user_id)
+    }
+
+    /// Only collects messages sent in the given channel.
+    pub fn channel(self, channel_id: &str) -> Self {
+        let channel_id = channel_id.to_string();
+        self.filter(move |message| message.channel_id == channel_id)
+    }
+}
+
+impl Collector<ReactionAdd> {
+    /// Creates a collector for added reactions.
+    pub fn reactions(bus: &EventBus) -> Self {
+        Self::new(bus, |event| match event {
+            Event::MessageReactionAdd(reaction) => Some(reaction.clone()),
+            _ => None,
+        })
+    }
+
+    /// Only collects reactions added by the given user.
+    pub fn author(self, user_id: &str) -> Self {
+        let user_id = user_id.to_string();
+        self.filter(move |reaction| reaction.user_id == user_id)
+    }
+
+    /// Only collects reactions in the given channel.
+    pub fn channel(self, channel_id: &str) -> Self {
+        let channel_id = channel_id.to_string();
+        self.filter(move |reaction| reaction.channel_id == channel_id)
+    }
+
+    /// Only collects reactions on the given message.
+    pub fn message(self, message_id: &str) -> Self {
+        let message_id = message_id.to_string();
+        self.filter(move |reaction| reaction.message_id == message_id)
+    }
+
+    /// Only collects reactions with the given emoji, matched against a Unicode
+    /// emoji such as `"✅"` or the ID of a custom emoji.
+    pub fn emoji(self, emoji: &str) -> Self {
+        let emoji = emoji.to_string();
+        self.filter(move |reaction| match &reaction.emoji.id {
+            Some(id) => *id == emoji,
+            None => reaction.emoji.name.as_deref() == Some(emoji.as_str()),
+        })
+    }
+}
+
+impl Collector<Interaction> {
+    /// Creates a collector for button clicks and select menu choices.
+    pub fn components(bus: &EventBus) -> Self {
+        Self::new(bus, |event| match event {
+            Event::InteractionCreate(interaction) if interaction.kind == InteractionType::MessageComponent => Some(interaction.clone()),
+            _ => None,
+        })
+    }
+
+    /// Only collects interactions triggered by the given user.
+    pub fn author(self, user_id: &str) -> Self {
+        let user_id = user_id.to_string();
+        self.filter(move |interaction| interaction.user_id() == Some(user_id.as_str()))
+    }
+
+    /// Only collects interactions in the given channel.
+    pub fn channel(self, channel_id: &str) -> Self {
+        let channel_id = channel_id.to_string();
+        self.filter(move |interaction| interaction.channel_id.as_deref() == Some(channel_id.as_str()))
+    }
+
+    /// Only collects interactions with components on the given message.
+    pub fn message(self, message_id: &str) -> Self {
+        let message_id = message_id.to_string();
+        self.filter(move |interaction| {
+            interaction.message.as_ref().and_then(|message| message["id"].as_str()) == Some(message_id.as_str())
+        })
+    }
+
+    /// Only collects interactions with the given `custom_id`.
+    pub fn custom_id(self, custom_id: &str) -> Self {
+        let custom_id = custom_id.to_string();
+        self.filter(move |interaction| {
+            interaction.component_data().is_some_and(|data| data.custom_id == custom_id)
+        })
+    }
+}
@@ -0,0 +1,578 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Arc;
+use tokio::sync::broadcast;
+
+use crate::gateway::{Activity, Status};
+use crate::model::{Channel, Guild, Member, Message, Role, User, VoiceState};
+use crate::utils::audit_log::AuditLogEntry;
+use crate::utils::auto_moderation::{AutoModerationAction, AutoModerationRule, TriggerType};
+use crate::utils::component::PartialEmoji;
+use crate::utils::emoji::Emoji;
+use crate::utils::guild_scheduled_event::ScheduledEvent;
+use crate::utils::interaction::Interaction;
+use crate::utils::invite::Invite;
+use crate::utils::stage_instance::StageInstance;
+
+/// A gateway dispatch event.
+#[derive(Clone, Debug)]
+pub enum Event {
+    Ready(Ready),
+    Resumed,
+    ApplicationCommandPermissionsUpdate(Value),
+    AutoModerationRuleCreate(AutoModerationRule),
+    AutoModerationRuleUpdate(AutoModerationRule),
+    AutoModerationRuleDelete(AutoModerationRule),
+    AutoModerationActionExecution(AutoModerationActionExecution),
+    GuildCreate(Guild),
+    GuildUpdate(Guild),
+    GuildDelete(UnavailableGuild),
+    ChannelCreate(Channel),
+    ChannelUpdate(Channel),
+    ChannelDelete(Channel),
+    ThreadCreate(Channel),
+    ThreadUpdate(Channel),
+    ThreadDelete(ThreadDelete),
+    ThreadListSync(ThreadListSync),
+    ThreadMemberUpdate(ThreadMemberUpdate),
+    ThreadMembersUpdate(ThreadMembersUpdate),
+    ChannelPinsUpdate(ChannelPinsUpdate),
+    EntitlementCreate(Value),
+    EntitlementUpdate(Value),
+    EntitlementDelete(Value),
+    GuildAuditLogEntryCreate(AuditLogEntry),
+    GuildBanAdd(GuildBan),
+    GuildBanRemove(GuildBan),
+    GuildEmojisUpdate(GuildEmojisUpdate),
+    GuildStickersUpdate(GuildStickersUpdate),
+    GuildIntegrationsUpdate(GuildIntegrationsUpdate),
+    GuildMemberAdd(MemberAdd),
+    GuildMemberUpdate(MemberUpdate),
+    GuildMemberRemove(MemberRemove),
+    GuildRoleCreate(RoleEvent),
+    GuildRoleUpdate(RoleEvent),
+    GuildRoleDelete(RoleDelete),
+    GuildMembersChunk(GuildMembersChunk),
+    GuildScheduledEventCreate(Box<ScheduledEvent>),
+    GuildScheduledEventUpdate(Box<ScheduledEvent>),
+    GuildScheduledEventDelete(Box<ScheduledEvent>),
+    GuildScheduledEventUserAdd(ScheduledEventUser),
+    GuildScheduledEventUserRemove(ScheduledEventUser),
+    GuildSoundboardSoundCreate(Value),
+    GuildSoundboardSoundUpdate(Value),
+    GuildSoundboardSoundDelete(Value),
+    GuildSoundboardSoundsUpdate(Value),
+    SoundboardSounds(Value),
+    IntegrationCreate(Value),
+    IntegrationUpdate(Value),
+    IntegrationDelete(IntegrationDelete),
+    InviteCreate(Box<Invite>),
+    InviteDelete(InviteDelete),
+    MessageCreate(Message),
+    MessageUpdate(MessageUpdate),
+    MessageDelete(MessageDelete),
+    MessageDeleteBulk(MessageDeleteBulk),
+    MessageReactionAdd(ReactionAdd),
+    MessageReactionRemove(ReactionRemove),
+    MessageReactionRemoveAll(ReactionRemoveAll),
+    MessageReactionRemoveEmoji(ReactionRemoveEmoji),
+    MessagePollVoteAdd(PollVote),
+    MessagePollVoteRemove(PollVote),
+    PresenceUpdate(PresenceUpdate),
+    StageInstanceCreate(StageInstance),
+    StageInstanceUpdate(StageInstance),
+    StageInstanceDelete(StageInstance),
+    SubscriptionCreate(Value),
+    SubscriptionUpdate(Value),
+    SubscriptionDelete(Value),
+    TypingStart(TypingStart),
+    UserUpdate(User),
+    VoiceChannelEffectSend(Value),
+    VoiceStateUpdate(VoiceState),
+    VoiceServerUpdate(VoiceServerUpdate),
+    WebhooksUpdate(WebhooksUpdate),
+    InteractionCreate(Interaction),
+    /// An event this crate does not model yet, with its raw payload.
+    Unknown { name: String, data: Value },
+}
+
+impl Event {
+    /// Parses a dispatch event from its name (the `t` field) and payload (the `d` field).
+    pub fn from_dispatch(name: &str, data: Value) -> Result<Self, serde_json::Error> {
+        let event = match name {
+            "READY" => Event::Ready(serde_json::from_value(data)?),
+            "RESUMED" => Event::Resumed,
+            "APPLICATION_COMMAND_PERMISSIONS_UPDATE" => Event::ApplicationCommandPermissionsUpdate(data),
+            "AUTO_MODERATION_RULE_CREATE" => Event::AutoModerationRuleCreate(serde_json::from_value(data)?),
+            "AUTO_MODERATION_RULE_UPDATE" => Event::AutoModerationRuleUpdate(serde_json::from_value(data)?),
+            "AUTO_MODERATION_RULE_DELETE" => Event::AutoModerationRuleDelete(serde_json::from_value(data)?),
+            "AUTO_MODERATION_ACTION_EXECUTION" => Event::AutoModerationActionExecution(serde_json::from_value(data)?),
+            "GUILD_CREATE" => Event::GuildCreate(serde_json::from_value(data)?),
+            "GUILD_UPDATE" => Event::GuildUpdate(serde_json::from_value(data)?),
+            "GUILD_DELETE" => Event::GuildDelete(serde_json::from_value(data)?),
+            "CHANNEL_CREATE" => Event::ChannelCreate(serde_json::from_value(data)?),
+            "CHANNEL_UPDATE" => Event::ChannelUpdate(serde_json::from_value(data)?),
+            "CHANNEL_DELETE" => Event::ChannelDelete(serde_json::from_value(data)?),
+            "THREAD_CREATE" => Event::ThreadCreate(serde_json::from_value(data)?),
+            "THREAD_UPDATE" => Event::ThreadUpdate(serde_json::from_value(data)?),
+            "THREAD_DELETE" => Event::ThreadDelete(serde_json::from_value(data)?),
+            "THREAD_LIST_SYNC" => Event::ThreadListSync(serde_json::from_value(data)?),
+            "THREAD_MEMBER_UPDATE" => Event::ThreadMemberUpdate(serde_json::from_value(data)?),
+            "THREAD_MEMBERS_UPDATE" => Event::ThreadMembersUpdate(serde_json::from_value(data)?),
+            "CHANNEL_PINS_UPDATE" => Event::ChannelPinsUpdate(serde_json::from_value(data)?),
+            "ENTITLEMENT_CREATE" => Event::EntitlementCreate(data),
+            "ENTITLEMENT_UPDATE" => Event::EntitlementUpdate(data),
+            "ENTITLEMENT_DELETE" => Event::EntitlementDelete(data),
+            "GUILD_AUDIT_LOG_ENTRY_CREATE" => Event::GuildAuditLogEntryCreate(serde_json::from_value(data)?),
+            "GUILD_BAN_ADD" => Event::GuildBanAdd(serde_json::from_value(data)?),
+            "GUILD_BAN_REMOVE" => Event::GuildBanRemove(serde_json::from_value(data)?),
+            "GUILD_EMOJIS_UPDATE" => Event::GuildEmojisUpdate(serde_json::from_value(data)?),
+            "GUILD_STICKERS_UPDATE" => Event::GuildStickersUpdate(serde_json::from_value(data)?),
+            "GUILD_INTEGRATIONS_UPDATE" => Event::GuildIntegrationsUpdate(serde_json::from_value(data)?),
+            "GUILD_MEMBER_ADD" => Event::GuildMemberAdd(serde_json::from_value(data)?),
+            "GUILD_MEMBER_UPDATE" => Event::GuildMemberUpdate(serde_json::from_value(data)?),
+            "GUILD_MEMBER_REMOVE" => Event::GuildMemberRemove(serde_json::from_value(data)?),
+            "GUILD_ROLE_CREATE" => Event::GuildRoleCreate(serde_json::from_value(data)?),
+            "GUILD_ROLE_UPDATE" => Event::GuildRoleUpdate(serde_json::from_value(data)?),
+            "GUILD_ROLE_DELETE" => Event::GuildRoleDelete(serde_json::from_value(data)?),
+            "GUILD_MEMBERS_CHUNK" => Event::GuildMembersChunk(serde_json::from_value(data)?),
+            "GUILD_SCHEDULED_EVENT_CREATE" => Event::GuildScheduledEventCreate(serde_json::from_value(data)?),
+            "GUILD_SCHEDULED_EVENT_UPDATE" => Event::GuildScheduledEventUpdate(serde_json::from_value(data)?),
+            "GUILD_SCHEDULED_EVENT_DELETE" => Event::GuildScheduledEventDelete(serde_json::from_value(data)?),
+            "GUILD_SCHEDULED_EVENT_USER_ADD" => Event::GuildScheduledEventUserAdd(serde_json::from_value(data)?),
+            "GUILD_SCHEDULED_EVENT_USER_REMOVE" => Event::GuildScheduledEventUserRemove(serde_json::from_value(data)?),
+            "GUILD_SOUNDBOARD_SOUND_CREATE" => Event::GuildSoundboardSoundCreate(data),
+            "GUILD_SOUNDBOARD_SOUND_UPDATE" => Event::GuildSoundboardSoundUpdate(data),
+            "GUILD_SOUNDBOARD_SOUND_DELETE" => Event::GuildSoundboardSoundDelete(data),
+            "GUILD_SOUNDBOARD_SOUNDS_UPDATE" => Event::GuildSoundboardSoundsUpdate(data),
+            "SOUNDBOARD_SOUNDS" => Event::SoundboardSounds(data),
+            "INTEGRATION_CREATE" => Event::IntegrationCreate(data),
+            "INTEGRATION_UPDATE" => Event::IntegrationUpdate(data),
+            "INTEGRATION_DELETE" => Event::IntegrationDelete(serde_json::from_value(data)?),
+            "INVITE_CREATE" => Event::InviteCreate(serde_json::from_value(data)?),
+            "INVITE_DELETE" => Event::InviteDelete(serde_json::from_value(data)?),
+            "MESSAGE_CREATE" => Event::MessageCreate(serde_json::from_value(data)?),
+            "MESSAGE_UPDATE" => Event::MessageUpdate(serde_json::from_value(data)?),
+            "MESSAGE_DELETE" => Event::MessageDelete(serde_json::from_value(data)?),
+            "MESSAGE_DELETE_BULK" => Event::MessageDeleteBulk(serde_json::from_value(data)?),
+            "MESSAGE_REACTION_ADD" => Event::MessageReactionAdd(serde_json::from_value(data)?),
+            "MESSAGE_REACTION_REMOVE" => Event::MessageReactionRemove(serde_json::from_value(data)?),
+            "MESSAGE_REACTION_REMOVE_ALL" => Event::MessageReactionRemoveAll(serde_json::from_value(data)?),
+            "MESSAGE_REACTION_REMOVE_EMOJI" => Event::MessageReactionRemoveEmoji(serde_json::from_value(data)?),
+            "MESSAGE_POLL_VOTE_ADD" => Event::MessagePollVoteAdd(serde_json::from_value(data)?),
+            "MESSAGE_POLL_VOTE_REMOVE" => Event::MessagePollVoteRemove(serde_json::from_value(data)?),
+            "PRESENCE_UPDATE" => Event::PresenceUpdate(serde_json::from_value(data)?),
+            "STAGE_INSTANCE_CREATE" => Event::StageInstanceCreate(serde_json::from_value(data)?),
+            "STAGE_INSTANCE_UPDATE" => Event::StageInstanceUpdate(serde_json::from_value(data)?),
+            "STAGE_INSTANCE_DELETE" => Event::StageInstanceDelete(serde_json::from_value(data)?),
+            "SUBSCRIPTION_CREATE" => Event::SubscriptionCreate(data),
+            "SUBSCRIPTION_UPDATE" => Event::SubscriptionUpdate(data),
+            "SUBSCRIPTION_DELETE" => Event::SubscriptionDelete(data),
+            "TYPING_START" => Event::TypingStart(serde_json::from_value(data)?),
+            "USER_UPDATE" => Event::UserUpdate(serde_json::from_value(data)?),
+            "VOICE_CHANNEL_EFFECT_SEND" => Event::VoiceChannelEffectSend(data),
+            "VOICE_STATE_UPDATE" => Event::VoiceStateUpdate(serde_json::from_value(data)?),
+            "VOICE_SERVER_UPDATE" => Event::VoiceServerUpdate(serde_json::from_value(data)?),
+            "WEBHOOKS_UPDATE" => Event::WebhooksUpdate(serde_json::from_value(data)?),
+            "INTERACTION_CREATE" => Event::InteractionCreate(serde_json::from_value(data)?),
+            _ => Event::Unknown { name: name.to_string(), data },
+        };
+
+        Ok(event)
+    }
+}
+
+/// Sent once the connection is identified.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Ready {
+    /// The gateway API version.
+    pub v: u8,
+    pub user: User,
+    /// The guilds the bot is in. Each arrives in full later as a GUILD_CREATE.
+    #[serde(default)]
+    pub guilds: Vec<UnavailableGuild>,
+    pub session_id: String,
+    #[serde(default)]
+    pub resume_gateway_url: Option<String>,
+    /// The shard ID and shard count of this connection.
+    #[serde(default)]
+    pub shard: Option<[u32; 2]>,
+    #[serde(default)]
+    pub application: Option<Value>,
+}
+
+/// Sent when the bot leaves a guild or the guild becomes unavailable.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct UnavailableGuild {
+    pub id: String,
+    /// `true` during an outage; missing when the bot was removed from the guild.
+    #[serde(default)]
+    pub unavailable: bool,
+}
+
+/// Sent when an auto moderation rule is triggered and an action executed.
+/// Needs the `AUTO_MODERATION_EXECUTION` intent, and `MESSAGE_CONTENT` for
+/// the content fields.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct AutoModerationActionExecution {
+    pub guild_id: String,
+    pub action: AutoModerationAction,
+    pub rule_id: String,
+    pub rule_trigger_type: TriggerType,
+    /// The member who triggered the rule.
+    pub user_id: String,
+    #[serde(default)]
+    pub channel_id: Option<String>,
+    /// The blocked or flagged message, unless it was blocked before being sent.
+    #[serde(default)]
+    pub message_id: Option<String>,
+    /// The alert posted by a send alert message action.
+    #[serde(default)]
+    pub alert_system_message_id: Option<String>,
+    #[serde(default)]
+    pub content: String,
+    /// The keyword or pattern that matched.
+    #[serde(default)]
+    pub matched_keyword: Option<String>,
+    /// The part of the content that matched.
+    #[serde(default)]
+    pub matched_content: Option<String>,
+}
+
+/// Sent when a thread is deleted.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ThreadDelete {
+    pub id: String,
+    #[serde(default)]
+    pub guild_id: Option<String>,
+    #[serde(default)]
+    pub parent_id: Option<String>,
+    #[serde(rename = "type")]
+    pub kind: u8,
+}
+
+/// Sent when the bot gains access to a channel, with all active threads in it.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ThreadListSync {
+    pub guild_id: String,
+    /// The parent channels being synced. Missing when the whole guild is synced.
+    #[serde(default)]
+    pub channel_ids: Option<Vec<String>>,
+    #[serde(default)]
+    pub threads: Vec<Channel>,
+    #[serde(default)]
+    pub members: Vec<Value>,
+}
+
+/// Sent when the bot's own thread member is updated.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ThreadMemberUpdate {
+    pub guild_id: String,
+    /// The thread ID.
+    #[serde(default)]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub user_id: Option<String>,
+    #[serde(default)]
+    pub join_timestamp: Option<String>,
+    #[serde(default)]
+    pub flags: u64,
+}
+
+/// Sent when users are added to or removed from a thread.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ThreadMembersUpdate {
+    /// The thread ID.
+    pub id: String,
+    pub guild_id: String,
+    pub member_count: u32,
+    #[serde(default)]
+    pub added_members: Vec<Value>,
+    #[serde(default)]
+    pub removed_member_ids: Vec<String>,
+}
+
+/// Sent when a message is pinned or unpinned.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ChannelPinsUpdate {
+    #[serde(default)]
+    pub guild_id: Option<String>,
+    pub channel_id: String,
+    #[serde(default)]
+    pub last_pin_timestamp: Option<String>,
+}
+
+/// Sent when a user is banned or unbanned.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct GuildBan {
+    pub guild_id: String,
+    pub user: User,
+}
+
+/// Sent when the emojis of a guild change. Carries the full new list.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct GuildEmojisUpdate {
+    pub guild_id: String,
+    pub emojis: Vec<Emoji>,
+}
+
+/// Sent when the stickers of a guild change. Carries the full new list.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct GuildStickersUpdate {
+    pub guild_id: String,
+    pub stickers: Vec<Value>,
+}
+
+/// Sent when the integrations of a guild change.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct GuildIntegrationsUpdate {
+    pub guild_id: String,
+}
+
+/// Sent when a user joins a guild.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct MemberAdd {
+    pub guild_id: String,
+    #[serde(flatten)]
+    pub member: Member,
+}
+
+/// Sent when a guild member is updated. Carries the member's full new state.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct MemberUpdate {
+    pub guild_id: String,
+    #[serde(flatten)]
+    pub member: Member,
+}
+
+/// Sent when a user leaves or is removed from a guild.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct MemberRemove {
+    pub guild_id: String,
+    pub user: User,
+}
+
+/// Sent when a guild role is created or updated.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct RoleEvent {
+    pub guild_id: String,
+    pub role: Role,
+}
+
+/// Sent when a guild role is deleted.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct RoleDelete {
+    pub guild_id: String,
+    pub role_id: String,
+}
+
+/// Sent in response to a Request Guild Members command.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct GuildMembersChunk {
+    pub guild_id: String,
+    pub members: Vec<Member>,
+    pub chunk_index: u32,
+    pub chunk_count: u32,
+    /// Requested user IDs that were not found.
+    #[serde(default)]
+    pub not_found: Vec<String>,
+    /// The members' presences, when requested.
+    #[serde(default)]
+    pub presences: Vec<PresenceUpdate>,
+    #[serde(default)]
+    pub nonce: Option<String>,
+}
+
+/// Sent when a user subscribes to or unsubscribes from a scheduled event.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ScheduledEventUser {
+    pub guild_scheduled_event_id: String,
+    pub user_id: String,
+    pub guild_id: String,
+}
+
+/// Sent when an integration is removed from a guild.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct IntegrationDelete {
+    pub id: String,
+    pub guild_id: String,
+    #[serde(default)]
+    pub application_id: Option<String>,
+}
+
+/// Sent when an invite is deleted.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct InviteDelete {
+    pub channel_id: String,
+    #[serde(default)]
+    pub guild_id: Option<String>,
+    pub code: String,
+}
+
+/// Sent when a message is edited. Only `id` and `channel_id` are guaranteed;
+/// the other changed fields are kept as raw JSON.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct MessageUpdate {
+    pub id: String,
+    pub channel_id: String,
+    #[serde(default)]
+    pub guild_id: Option<String>,
+    #[serde(flatten)]
+    pub fields: serde_json::Map<String, Value>,
+}
+
+/// Sent when a message is deleted.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct MessageDelete {
+    pub id: String,
+    pub channel_id: String,
+    #[serde(default)]
+    pub guild_id: Option<String>,
+}
+
+/// Sent when several messages are deleted at once.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct MessageDeleteBulk {
+    pub ids: Vec<String>,
+    pub channel_id: String,
+    #[serde(default)]
+    pub guild_id: Option<String>,
+}
+
+/// Sent when a user adds a reaction to a message.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ReactionAdd {
+    pub user_id: String,
+    pub channel_id: String,
+    pub message_id: String,
+    #[serde(default)]
+    pub guild_id: Option<String>,
+    #[serde(default)]
+    pub member: Option<Value>,
+    pub emoji: PartialEmoji,
+    #[serde(default)]
+    pub message_author_id: Option<String>,
+    #[serde(default)]
+    pub burst: bool,
+}
+
+/// Sent when a user removes a reaction from a message.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ReactionRemove {
+    pub user_id: String,
+    pub channel_id: String,
+    pub message_id: String,
+    #[serde(default)]
+    pub guild_id: Option<String>,
+    pub emoji: PartialEmoji,
+    #[serde(default)]
+    pub burst: bool,
+}
+
+/// Sent when all reactions are removed from a message.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ReactionRemoveAll {
+    pub channel_id: String,
+    pub message_id: String,
+    #[serde(default)]
+    pub guild_id: Option<String>,
+}
+
+/// Sent when all reactions of one emoji are removed from a message.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ReactionRemoveEmoji {
+    pub channel_id: String,
+    pub message_id: String,
+    #[serde(default)]
+    pub guild_id: Option<String>,
+    pub emoji: PartialEmoji,
+}
+
+/// Sent when a user votes for or retracts a vote on a poll answer.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PollVote {
+    pub user_id: String,
+    pub channel_id: String,
+    pub message_id: String,
+    #[serde(default)]
+    pub guild_id: Option<String>,
+    pub answer_id: u32,
+}
+
+/// Sent when a user starts typing in a channel.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TypingStart {
+    pub channel_id: String,
+    #[serde(default)]
+    pub guild_id: Option<String>,
+    pub user_id: String,
+    /// Unix time in seconds.
+    pub timestamp: u64,
+    #[serde(default)]
+    pub member: Option<Member>,
+}
+
+/// Sent when the voice server of a guild is assigned or changes.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct VoiceServerUpdate {
+    pub token: String,
+    pub guild_id: String,
+    /// Missing while the voice server is being reallocated.
+    #[serde(default)]
+    pub endpoint: Option<String>,
+}
+
+/// Sent when a user's presence changes in a guild.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PresenceUpdate {
+    /// The user. Only `id` is always present.
+    pub user: Value,
+    #[serde(default)]
+    pub guild_id: Option<String>,
+    pub status: Status,
+    #[serde(default)]
+    pub activities: Vec<Activity>,
+    /// The status on each platform: `desktop`, `mobile` and `web`.
+    #[serde(default)]
+    pub client_status: Value,
+}
+
+/// Sent when the webhooks of a channel change.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct WebhooksUpdate {
+    pub guild_id: String,
+    pub channel_id: String,
+}
+
+/// Fans gateway events out to every subscriber, such as collectors.
+///
+/// Cloning an `EventBus` yields another handle to the same channel.
+#[derive(Clone, Debug)]
+pub struct EventBus {
+    sender: broadcast::Sender<Arc<Event>>,
+}
+
+impl EventBus {
+    /// Creates a bus that buffers up to `capacity` events for slow subscribers.
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        Self { sender }
+    }
+
+    /// Publishes an event to every current subscriber.
+    pub fn publish(&self, event: impl Into<Arc<Event>>) {
+        // Sending only fails when nobody is subscribed, which is fine.
+        let _ = self.sender.send(event.into());
+    }
+
+    /// Subscribes to events published from now on.
+    pub fn subscribe(&self) -> broadcast::Receiver<Arc<Event>> {
+        self.sender.subscribe()
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new(256)
+    }
+}
@@ -0,0 +1,154 @@
+use std::io;
+
+/// Transport compression for the gateway connection.
+///
+/// With transport compression every message on the connection is part of one
+/// compressed stream, which saves a lot of bandwidth on large payloads such
+/// as GUILD_CREATE. Each variant needs its cargo feature.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Compression {
+    /// Uncompressed payloads.
+    #[default]
+    None,
+    /// `zlib-stream`, needs the `zlib` feature.
+    #[cfg(feature = "zlib")]
+    Zlib,
+    /// `zstd-stream`, needs the `zstd` feature.
+    #[cfg(feature = "zstd")]
+    Zstd,
+}
+
+impl Compression {
+    /// Returns the query parameter requesting this compression, if any.
+    pub(crate) fn query(self) -> &'static str {
+        match self {
+            Compression::None => "",
+            #[cfg(feature = "zlib")]
+            Compression::Zlib => "&compress=zlib-stream",
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => "&compress=zstd-stream",
+        }
+    }
+}
+
+/// Every zlib-stream payload ends with the marker of a Z_SYNC_FLUSH.
+#[cfg(feature = "zlib")]
+const ZLIB_SUFFIX: [u8; 4] = [0x00, 0x00, 0xff, 0xff];
+
+/// Decompresses the binary messages of one gateway connection.
+///
+/// The compression context spans the whole connection, so use one
+/// `Decompressor` per connection and feed it every binary message in order.
+#[cfg(any(feature = "zlib", feature = "zstd"))]
+pub struct Decompressor {
+    inner: Inner,
+}
+
+#[cfg(any(feature = "zlib", feature = "zstd"))]
+enum Inner {
+    #[cfg(feature = "zlib")]
+    Zlib { decompress: flate2::Decompress, buffer: Vec<u8> },
+    #[cfg(feature = "zstd")]
+    Zstd { decoder: zstd::stream::raw::Decoder<'static>, chunk: Vec<u8> },
+}
+
+#[cfg(any(feature = "zlib", feature = "zstd"))]
+impl Decompressor {
+    /// Creates a decompressor, or `None` for [`Compression::None`].
+    pub fn new(compression: Compression) -> io::Result<Option<Self>> {
+        let inner = match compression {
+            Compression::None => return Ok(None),
+            #[cfg(feature = "zlib")]
+            Compression::Zlib => Inner::Zlib { decompress: flate2::Decompress::new(true), buffer: Vec::new() },
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => Inner::Zstd { decoder: zstd::stream::raw::Decoder::new()?, chunk: vec![0; 32 * 1024] },
+        };
+        Ok(Some(Self { inner }))
+    }
+
+    /// Feeds one binary message and returns the bytes of the payload it
+    /// completes, if any.
+    ///
+    /// zlib-stream payloads can span several messages; only the message ending
+    /// with the Z_SYNC_FLUSH marker completes one. Every zstd-stream message
+    /// holds exactly one payload.
+    pub fn push(&mut self, data: &[u8]) -> io::Result<Option<Vec<u8>>> {
+        let output = match &mut self.inner {
+            #[cfg(feature = "zlib")]
+            Inner::Zlib { decompress, buffer } => {
+                buffer.extend_from_slice(data);
+                if !buffer.ends_with(&ZLIB_SUFFIX) {
+                    return Ok(None);
+                }
+                let output = inflate(decompress, buffer);
+                buffer.clear();
+                output?
+            }
+            #[cfg(feature = "zstd")]
+            Inner::Zstd { decoder, chunk } => decode_zstd(decoder, chunk, data)?,
+        };
+        Ok(Some(output))
+    }
+}
+
+/// Without a compression feature there is nothing to decompress.
+#[cfg(not(any(feature = "zlib", feature = "zstd")))]
+pub struct Decompressor(std::convert::Infallible);
+
+#[cfg(not(any(feature = "zlib", feature = "zstd")))]
+impl Decompressor {
+    /// Returns `None`, as only [`Compression::None`] is available.
+    pub fn new(_compression: Compression) -> io::Result<Option<Self>> {
+        Ok(None)
+    }
+
+    /// Never called, as no `Decompressor` can be created.
+    pub fn push(&mut self, _data: &[u8]) -> io::Result<Option<Vec<u8>>> {
+        match self.0 {}
+    }
+}
+
+#[cfg(feature = "zlib")]
+fn inflate(decompress: &mut flate2::Decompress, input: &[u8]) -> io::Result<Vec<u8>> {
+    let mut output = Vec::with_capacity(input.len() * 4);
+    let mut offset = 0;
+
+    loop {
+        let (before_in, before_out) = (decompress.total_in(), decompress.total_out());
+        decompress
+            .decompress_vec(&input[offset..], &mut output, flate2::FlushDecompress::Sync)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+        offset += (decompress.total_in() - before_in) as usize;
+
+        // There is always room in the output here, so a call that neither reads
+        // nor writes anything is stuck, for example after the end of the stream.
+        if decompress.total_in() == before_in && decompress.total_out() == before_out {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "zlib stream made no progress"));
+        }
+
+        // Done once all input is read and the output buffer wasn't filled up.
+        if offset >= input.len() && output.len() < output.capacity() {
+            return Ok(output);
+        }
+        output.reserve(output.capacity().max(1024));
+    }
+}
+
+#[cfg(feature = "zstd")]
+fn decode_zstd(decoder: &mut zstd::stream::raw::Decoder<'static>, chunk: &mut [u8], data: &[u8]) -> io::Result<Vec<u8>> {
+    use zstd::stream::raw::{InBuffer, Operation, OutBuffer};
+
+    let mut input = InBuffer::around(data);
+    let mut output = Vec::new();
+
+    loop {
+        let mut out = OutBuffer::around(&mut *chunk);
+        decoder.run(&mut input, &mut out)?;
+        let written = out.pos();
+        output.extend_from_slice(&chunk[..written]);
+
+        if input.pos == data.len() && written < chunk.len() {
+            return Ok(output);
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use serde_json::Value;
+use tokio_tungstenite::tungstenite::Message as WsMessage;
+
+use super::etf;
+
+/// The payload encoding of the gateway connection.
+///
+/// ETF payloads are smaller than JSON and arrive as binary messages. Both
+/// encodings decode to the same `serde_json::Value` shapes, with snowflakes
+/// as strings, so events parse the same way either way.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Encoding {
+    /// JSON text payloads.
+    #[default]
+    Json,
+    /// Erlang External Term Format payloads.
+    Etf,
+}
+
+impl Encoding {
+    /// Returns the query parameter requesting this encoding.
+    pub(crate) fn query(self) -> &'static str {
+        match self {
+            Encoding::Json => "encoding=json",
+            Encoding::Etf => "encoding=etf",
+        }
+    }
+
+    /// Decodes one complete payload.
+    pub(crate) fn decode(self, data: &[u8]) -> Result<Value, String> {
+        match self {
+            Encoding::Json => serde_json::from_slice(data).map_err(|error| error.to_string()),
+            Encoding::Etf => etf::decode(data).map_err(|error| error.to_string()),
+        }
+    }
+
+    /// Encodes a payload as a message to send.
+    pub(crate) fn encode(self, payload: &Value) -> WsMessage {
+        match self {
+            Encoding::Json => WsMessage::text(payload.to_string()),
+            Encoding::Etf => WsMessage::binary(etf::encode(payload)),
+        }
+    }
+}
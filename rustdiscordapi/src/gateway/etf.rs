@@ -0,0 +1,259 @@
+//! Erlang External Term Format, the binary alternative to JSON on the gateway.
+//!
+//! Terms are converted to and from the same `serde_json::Value` shapes the
+//! JSON encoding produces, so payloads deserialize into the same types either
+//! way. ETF sends snowflakes as integers while JSON sends them as strings, so
+//! integers under snowflake keys (`id`, `*_id`, `*_ids`, `roles`, ...) decode
+//! to decimal strings, and digit strings under those keys encode as integers.
+
+use serde_json::{Map, Number, Value};
+use std::fmt;
+
+const VERSION: u8 = 131;
+const NEW_FLOAT_EXT: u8 = 70;
+const SMALL_INTEGER_EXT: u8 = 97;
+const INTEGER_EXT: u8 = 98;
+const FLOAT_EXT: u8 = 99;
+const ATOM_EXT: u8 = 100;
+const SMALL_TUPLE_EXT: u8 = 104;
+const LARGE_TUPLE_EXT: u8 = 105;
+const NIL_EXT: u8 = 106;
+const STRING_EXT: u8 = 107;
+const LIST_EXT: u8 = 108;
+const BINARY_EXT: u8 = 109;
+const SMALL_BIG_EXT: u8 = 110;
+const LARGE_BIG_EXT: u8 = 111;
+const SMALL_ATOM_EXT: u8 = 115;
+const MAP_EXT: u8 = 116;
+const ATOM_UTF8_EXT: u8 = 118;
+const SMALL_ATOM_UTF8_EXT: u8 = 119;
+
+/// An error raised while decoding an ETF payload.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EtfError(String);
+
+impl fmt::Display for EtfError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid ETF payload: {}", self.0)
+    }
+}
+
+impl std::error::Error for EtfError {}
+
+/// Whether values under `key` are snowflakes, which JSON sends as strings.
+fn is_snowflake_key(key: &str) -> bool {
+    key == "id"
+        || key.ends_with("_id")
+        || key.ends_with("_ids")
+        || matches!(key, "roles" | "mention_roles" | "not_found" | "exempt_roles" | "exempt_channels" | "applied_tags" | "permissions" | "allow" | "deny")
+}
+
+/// Decodes an ETF payload.
+pub fn decode(data: &[u8]) -> Result<Value, EtfError> {
+    let mut decoder = Decoder { data, position: 0 };
+    if decoder.byte()? != VERSION {
+        return Err(EtfError("unsupported format version".to_string()));
+    }
+    let value = decoder.term(false)?;
+    if decoder.position != data.len() {
+        return Err(EtfError("trailing bytes".to_string()));
+    }
+    Ok(value)
+}
+
+struct Decoder<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Decoder<'a> {
+    fn take(&mut self, length: usize) -> Result<&'a [u8], EtfError> {
+        let end = self.position.checked_add(length).filter(|end| *end <= self.data.len());
+        let end = end.ok_or_else(|| EtfError("unexpected end of data".to_string()))?;
+        let bytes = &self.data[self.position..end];
+        self.position = end;
+        Ok(bytes)
+    }
+
+    fn byte(&mut self) -> Result<u8, EtfError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<usize, EtfError> {
+        Ok(u16::from_be_bytes(self.take(2)?.try_into().unwrap()) as usize)
+    }
+
+    fn u32(&mut self) -> Result<usize, EtfError> {
+        Ok(u32::from_be_bytes(self.take(4)?.try_into().unwrap()) as usize)
+    }
+
+    fn text(&mut self, length: usize) -> Result<String, EtfError> {
+        String::from_utf8(self.take(length)?.to_vec()).map_err(|error| EtfError(error.to_string()))
+    }
+
+    fn latin1(&mut self, length: usize) -> Result<String, EtfError> {
+        Ok(self.take(length)?.iter().map(|&byte| byte as char).collect())
+    }
+
+    /// Decodes one term. `snowflake` is set when the term sits under a snowflake key.
+    fn term(&mut self, snowflake: bool) -> Result<Value, EtfError> {
+        let tag = self.byte()?;
+        let value = match tag {
+            SMALL_INTEGER_EXT => integer(self.byte()? as i128, snowflake),
+            INTEGER_EXT => integer(i32::from_be_bytes(self.take(4)?.try_into().unwrap()) as i128, snowflake),
+            SMALL_BIG_EXT | LARGE_BIG_EXT => {
+                let length = if tag == SMALL_BIG_EXT { self.byte()? as usize } else { self.u32()? };
+                let negative = self.byte()? != 0;
+                let digits = self.take(length)?;
+                if digits.len() > 16 {
+                    return Err(EtfError("integer too large".to_string()));
+                }
+                let magnitude = digits.iter().rev().fold(0u128, |total, &digit| (total << 8) | digit as u128);
+                let magnitude = i128::try_from(magnitude).map_err(|_| EtfError("integer too large".to_string()))?;
+                integer(if negative { -magnitude } else { magnitude }, snowflake)
+            }
+            NEW_FLOAT_EXT => float(f64::from_be_bytes(self.take(8)?.try_into().unwrap())),
+            FLOAT_EXT => {
+                let text = self.latin1(31)?;
+                float(text.trim_end_matches('\0').trim().parse().map_err(|_| EtfError("invalid float".to_string()))?)
+            }
+            ATOM_EXT => { let length = self.u16()?; atom(self.latin1(length)?) }
+            SMALL_ATOM_EXT => { let length = self.byte()? as usize; atom(self.latin1(length)?) }
+            ATOM_UTF8_EXT => { let length = self.u16()?; atom(self.text(length)?) }
+            SMALL_ATOM_UTF8_EXT => { let length = self.byte()? as usize; atom(self.text(length)?) }
+            BINARY_EXT => { let length = self.u32()?; Value::String(self.text(length)?) }
+            STRING_EXT => { let length = self.u16()?; Value::String(self.latin1(length)?) }
+            NIL_EXT => Value::Array(Vec::new()),
+            LIST_EXT => {
+                let length = self.u32()?;
+                let items = (0..length).map(|_| self.term(snowflake)).collect::<Result<Vec<_>, _>>()?;
+                // Proper lists end with an empty list as their tail.
+                if self.byte()? != NIL_EXT {
+                    return Err(EtfError("improper list".to_string()));
+                }
+                Value::Array(items)
+            }
+            SMALL_TUPLE_EXT | LARGE_TUPLE_EXT => {
+                let length = if tag == SMALL_TUPLE_EXT { self.byte()? as usize } else { self.u32()? };
+                Value::Array((0..length).map(|_| self.term(snowflake)).collect::<Result<_, _>>()?)
+            }
+            MAP_EXT => {
+                let length = self.u32()?;
+                let mut map = Map::new();
+                for _ in 0..length {
+                    let key = match self.term(false)? {
+                        Value::String(key) => key,
+                        Value::Number(key) => key.to_string(),
+                        other => return Err(EtfError(format!("unsupported map key {}", other))),
+                    };
+                    let value = self.term(is_snowflake_key(&key))?;
+                    map.insert(key, value);
+                }
+                Value::Object(map)
+            }
+            other => return Err(EtfError(format!("unsupported tag {}", other))),
+        };
+        Ok(value)
+    }
+}
+
+fn integer(value: i128, snowflake: bool) -> Value {
+    if snowflake {
+        return Value::String(value.to_string());
+    }
+    if let Ok(value) = i64::try_from(value) {
+        Value::from(value)
+    } else if let Ok(value) = u64::try_from(value) {
+        Value::from(value)
+    } else {
+        Value::String(value.to_string())
+    }
+}
+
+fn float(value: f64) -> Value {
+    Number::from_f64(value).map_or(Value::Null, Value::Number)
+}
+
+fn atom(name: String) -> Value {
+    match name.as_str() {
+        "nil" | "null" => Value::Null,
+        "true" => Value::Bool(true),
+        "false" => Value::Bool(false),
+        _ => Value::String(name),
+    }
+}
+
+/// Encodes a payload as ETF. Map keys and strings become binaries, `null`
+/// becomes the `nil` atom, and digit strings under snowflake keys become integers.
+pub fn encode(value: &Value) -> Vec<u8> {
+    let mut buffer = vec![VERSION];
+    encode_term(&mut buffer, value, false);
+    buffer
+}
+
+fn encode_term(buffer: &mut Vec<u8>, value: &Value, snowflake: bool) {
+    match value {
+        Value::Null => encode_atom(buffer, "nil"),
+        Value::Bool(value) => encode_atom(buffer, if *value { "true" } else { "false" }),
+        Value::Number(number) => {
+            if let Some(value) = number.as_i64() {
+                encode_integer(buffer, value as i128);
+            } else if let Some(value) = number.as_u64() {
+                encode_integer(buffer, value as i128);
+            } else {
+                buffer.push(NEW_FLOAT_EXT);
+                buffer.extend_from_slice(&number.as_f64().unwrap_or_default().to_be_bytes());
+            }
+        }
+        Value::String(text) => match text.parse::<u64>() {
+            Ok(id) if snowflake && !text.starts_with('+') => encode_integer(buffer, id as i128),
+            _ => {
+                buffer.push(BINARY_EXT);
+                buffer.extend_from_slice(&(text.len() as u32).to_be_bytes());
+                buffer.extend_from_slice(text.as_bytes());
+            }
+        },
+        Value::Array(items) => {
+            if !items.is_empty() {
+                buffer.push(LIST_EXT);
+                buffer.extend_from_slice(&(items.len() as u32).to_be_bytes());
+                for item in items {
+                    encode_term(buffer, item, snowflake);
+                }
+            }
+            buffer.push(NIL_EXT);
+        }
+        Value::Object(map) => {
+            buffer.push(MAP_EXT);
+            buffer.extend_from_slice(&(map.len() as u32).to_be_bytes());
+            for (key, value) in map {
+                encode_term(buffer, &Value::String(key.clone()), false);
+                encode_term(buffer, value, is_snowflake_key(key));
+            }
+        }
+    }
+}
+
+fn encode_atom(buffer: &mut Vec<u8>, name: &str) {
+    buffer.push(SMALL_ATOM_UTF8_EXT);
+    buffer.push(name.len() as u8);
+    buffer.extend_from_slice(name.as_bytes());
+}
+
+fn encode_integer(buffer: &mut Vec<u8>, value: i128) {
+    if (0..=255).contains(&value) {
+        buffer.extend_from_slice(&[SMALL_INTEGER_EXT, value as u8]);
+    } else if let Ok(value) = i32::try_from(value) {
+        buffer.push(INTEGER_EXT);
+        buffer.extend_from_slice(&value.to_be_bytes());
+    } else {
+        let mut magnitude = value.unsigned_abs();
+        let mut digits = Vec::new();
+        while magnitude > 0 {
+            digits.push(magnitude as u8);
+            magnitude >>= 8;
+        }
+        buffer.extend_from_slice(&[SMALL_BIG_EXT, digits.len() as u8, (value < 0) as u8]);
+        buffer.extend_from_slice(&digits);
+    }
+}
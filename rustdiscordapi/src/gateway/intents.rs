@@ -0,0 +1,74 @@
+use bitflags::bitflags;
+
+bitflags! {
+    /// Gateway intents, selecting which groups of events the gateway sends.
+    ///
+    /// `GUILD_MEMBERS`, `GUILD_PRESENCES` and `MESSAGE_CONTENT` are privileged
+    /// and must also be enabled for the application in the Developer Portal.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use Rust_Discord_API::gateway::Intents;
+    ///
+    /// let intents = Intents::non_privileged() | Intents::MESSAGE_CONTENT;
+    /// assert!(intents.contains(Intents::GUILD_MESSAGES));
+    /// ```
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    pub struct Intents: u64 {
+        /// Guild, channel, thread, role and stage instance events.
+        const GUILDS = 1 << 0;
+        /// Member joins, updates and removals. Privileged.
+        const GUILD_MEMBERS = 1 << 1;
+        /// Bans and audit log entries.
+        const GUILD_MODERATION = 1 << 2;
+        /// Emoji, sticker and soundboard updates.
+        const GUILD_EXPRESSIONS = 1 << 3;
+        const GUILD_INTEGRATIONS = 1 << 4;
+        const GUILD_WEBHOOKS = 1 << 5;
+        const GUILD_INVITES = 1 << 6;
+        const GUILD_VOICE_STATES = 1 << 7;
+        /// Presence updates. Privileged.
+        const GUILD_PRESENCES = 1 << 8;
+        const GUILD_MESSAGES = 1 << 9;
+        const GUILD_MESSAGE_REACTIONS = 1 << 10;
+        const GUILD_MESSAGE_TYPING = 1 << 11;
+        const DIRECT_MESSAGES = 1 << 12;
+        const DIRECT_MESSAGE_REACTIONS = 1 << 13;
+        const DIRECT_MESSAGE_TYPING = 1 << 14;
+        /// The content, embeds, attachments and components of messages that
+        /// don't mention the bot and aren't DMs. Privileged.
+        const MESSAGE_CONTENT = 1 << 15;
+        const GUILD_SCHEDULED_EVENTS = 1 << 16;
+        const AUTO_MODERATION_CONFIGURATION = 1 << 20;
+        const AUTO_MODERATION_EXECUTION = 1 << 21;
+        const GUILD_MESSAGE_POLLS = 1 << 24;
+        const DIRECT_MESSAGE_POLLS = 1 << 25;
+    }
+}
+
+impl Intents {
+    /// The intents that must be enabled in the Developer Portal before use.
+    pub const PRIVILEGED: Intents = Intents::GUILD_MEMBERS.union(Intents::GUILD_PRESENCES).union(Intents::MESSAGE_CONTENT);
+
+    /// Returns every intent except the privileged ones.
+    pub fn non_privileged() -> Self {
+        Intents::all().difference(Intents::PRIVILEGED)
+    }
+
+    /// Returns the privileged intents in this set.
+    pub fn privileged(self) -> Self {
+        self.intersection(Intents::PRIVILEGED)
+    }
+
+    /// Returns the intent names, separated by commas.
+    pub fn names(self) -> String {
+        self.iter_names().map(|(name, _)| name).collect::<Vec<_>>().join(", ")
+    }
+}
+
+impl Default for Intents {
+    fn default() -> Self {
+        Intents::non_privileged()
+    }
+}
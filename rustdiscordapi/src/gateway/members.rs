@@ -0,0 +1,152 @@
+use futures::Stream;
+use serde_json::{json, Value};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+use tokio::time::timeout;
+
+use super::GatewayError;
+use crate::event::{GuildMembersChunk, PresenceUpdate};
+use crate::model::Member;
+
+/// Discord accepts at most this many user IDs per request.
+pub const MAX_USER_IDS: usize = 100;
+
+/// How long [`MemberChunks::collect_members`] waits for the next chunk by default.
+pub const DEFAULT_CHUNK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A Request Guild Members command (opcode 8), sent with
+/// [`ShardHandle::request_guild_members`](super::ShardHandle::request_guild_members).
+///
+/// # Examples
+///
+/// ```
+/// use Rust_Discord_API::gateway::MemberRequest;
+///
+/// // Up to 10 members whose username or nickname starts with "ja".
+/// let request = MemberRequest::query("81384788765712384", "ja", 10).presences(true);
+/// assert_eq!(request.guild_id, "81384788765712384");
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct MemberRequest {
+    pub guild_id: String,
+    /// The username or nickname prefix to match, or `""` for all members.
+    pub query: Option<String>,
+    /// The maximum number of members to return, or 0 for no limit with an empty query.
+    pub limit: u32,
+    /// The members to fetch, instead of a query.
+    pub user_ids: Option<Vec<String>>,
+    /// Whether to include the members' presences. Needs the `GUILD_PRESENCES` intent.
+    pub presences: bool,
+    /// Identifies the response chunks. Generated when not set.
+    pub nonce: Option<String>,
+}
+
+impl MemberRequest {
+    /// Requests up to `limit` members whose username or nickname starts with `query`.
+    pub fn query(guild_id: &str, query: &str, limit: u32) -> Self {
+        Self { guild_id: guild_id.to_string(), query: Some(query.to_string()), limit, user_ids: None, presences: false, nonce: None }
+    }
+
+    /// Requests every member of the guild. Needs the `GUILD_MEMBERS` intent.
+    pub fn all(guild_id: &str) -> Self {
+        Self::query(guild_id, "", 0)
+    }
+
+    /// Requests specific members by user ID, at most [`MAX_USER_IDS`].
+    pub fn user_ids(guild_id: &str, user_ids: &[&str]) -> Self {
+        let user_ids = user_ids.iter().map(|id| id.to_string()).collect();
+        Self { guild_id: guild_id.to_string(), query: None, limit: 0, user_ids: Some(user_ids), presences: false, nonce: None }
+    }
+
+    /// Sets whether the members' presences are included.
+    pub fn presences(mut self, presences: bool) -> Self {
+        self.presences = presences;
+        self
+    }
+
+    /// Sets the nonce identifying the response chunks, at most 32 bytes.
+    pub fn nonce(mut self, nonce: &str) -> Self {
+        self.nonce = Some(nonce.to_string());
+        self
+    }
+
+    pub(crate) fn payload(&self) -> Value {
+        let mut payload = json!({ "guild_id": self.guild_id, "limit": self.limit, "presences": self.presences, "nonce": self.nonce });
+        match &self.user_ids {
+            Some(user_ids) => payload["user_ids"] = json!(user_ids),
+            None => payload["query"] = json!(self.query.as_deref().unwrap_or_default()),
+        }
+        payload
+    }
+}
+
+/// A request waiting for its chunks.
+pub(crate) struct PendingMembers {
+    /// Receives the chunks, or the error that ends the request.
+    pub(crate) sender: UnboundedSender<Result<GuildMembersChunk, GatewayError>>,
+    pub(crate) received: u32,
+}
+
+/// The GUILD_MEMBERS_CHUNK events answering one member request, as a stream.
+///
+/// The stream ends after the last chunk. It ends early when the connection
+/// closes before all chunks arrived or a chunk could not be parsed;
+/// [`collect_members`](Self::collect_members) reports that as an error.
+pub struct MemberChunks {
+    pub(crate) receiver: UnboundedReceiver<Result<GuildMembersChunk, GatewayError>>,
+    pub(crate) timeout: Duration,
+}
+
+impl MemberChunks {
+    /// Sets how long `collect_members` waits for the next chunk before giving
+    /// up. Defaults to [`DEFAULT_CHUNK_TIMEOUT`].
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Waits for every chunk and merges them into one result.
+    pub async fn collect_members(mut self) -> Result<GuildMembers, GatewayError> {
+        let mut result = GuildMembers::default();
+        let (mut received, mut expected) = (0, None);
+        loop {
+            let chunk = match timeout(self.timeout, self.receiver.recv()).await {
+                Ok(Some(chunk)) => chunk?,
+                Ok(None) => break,
+                Err(_) => return Err(GatewayError::Timeout("member chunks".to_string())),
+            };
+            received += 1;
+            expected = Some(chunk.chunk_count);
+            result.guild_id = chunk.guild_id;
+            result.members.extend(chunk.members);
+            result.presences.extend(chunk.presences);
+            result.not_found.extend(chunk.not_found);
+        }
+        match expected {
+            Some(expected) if received >= expected => Ok(result),
+            _ => Err(GatewayError::Protocol("the session ended before all member chunks arrived".to_string())),
+        }
+    }
+}
+
+impl Stream for MemberChunks {
+    type Item = GuildMembersChunk;
+
+    fn poll_next(mut self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<Option<GuildMembersChunk>> {
+        // An error is always the last item, so the stream just ends there.
+        self.receiver.poll_recv(context).map(|chunk| chunk.and_then(Result::ok))
+    }
+}
+
+/// The members returned for a member request.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GuildMembers {
+    pub guild_id: String,
+    pub members: Vec<Member>,
+    /// The members' presences, when requested.
+    pub presences: Vec<PresenceUpdate>,
+    /// Requested user IDs that are not members of the guild.
+    pub not_found: Vec<String>,
+}
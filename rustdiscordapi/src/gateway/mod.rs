@@ -0,0 +1,609 @@
+use futures::{FutureExt, SinkExt, StreamExt};
+use reqwest::Client;
+use serde_json::{json, Value};
+use std::error::Error;
+use std::fmt;
+use std::future::Future;
+use std::io;
+use std::panic::AssertUnwindSafe;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
+use tokio::time::{interval_at, sleep, Instant};
+use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+use tokio_tungstenite::tungstenite::protocol::CloseFrame;
+use tokio_tungstenite::tungstenite::Message as WsMessage;
+use tokio_tungstenite::connect_async;
+
+use crate::cache::Cache;
+use crate::event::{Event, EventBus};
+use crate::handler::{dispatch_event, EventHandler};
+use crate::invite_tracker::{InviteTracker, TrackerQueue};
+use crate::router::CommandRouter;
+use crate::utils::interaction::InteractionType;
+
+mod compression;
+mod encoding;
+pub mod etf;
+mod intents;
+mod members;
+mod presence;
+mod shard;
+
+pub use compression::{Compression, Decompressor};
+pub use encoding::Encoding;
+pub use intents::Intents;
+pub use members::{GuildMembers, MemberChunks, MemberRequest, DEFAULT_CHUNK_TIMEOUT, MAX_USER_IDS};
+pub use presence::{Activity, ActivityType, Presence, Status};
+pub use shard::{ShardHandle, VOICE_SERVER_TIMEOUT};
+use shard::{ShardCommand, ShardState};
+
+/// The gateway API version requested on connect.
+const GATEWAY_VERSION: &str = "v=10";
+
+/// Gateway opcodes.
+mod op {
+    pub const DISPATCH: u8 = 0;
+    pub const HEARTBEAT: u8 = 1;
+    pub const IDENTIFY: u8 = 2;
+    pub const PRESENCE_UPDATE: u8 = 3;
+    pub const VOICE_STATE_UPDATE: u8 = 4;
+    pub const RESUME: u8 = 6;
+    pub const RECONNECT: u8 = 7;
+    pub const REQUEST_GUILD_MEMBERS: u8 = 8;
+    pub const INVALID_SESSION: u8 = 9;
+    pub const HELLO: u8 = 10;
+    pub const HEARTBEAT_ACK: u8 = 11;
+}
+
+/// An error that stops the gateway client.
+#[derive(Debug)]
+pub enum GatewayError {
+    /// The WebSocket connection could not be opened.
+    WebSocket(tokio_tungstenite::tungstenite::Error),
+    /// Discord sent something other than the expected payload.
+    Protocol(String),
+    /// Close code 4013: the intents value is not a valid bit set.
+    InvalidIntents,
+    /// Close code 4014: a privileged intent was requested without being
+    /// enabled in the Developer Portal. Holds the privileged intents requested.
+    DisallowedIntents(Intents),
+    /// Discord closed the connection with another close code that must not be
+    /// retried, such as 4004 (authentication failed).
+    Closed { code: u16, reason: String },
+    /// A command was sent through a [`ShardHandle`] after its client was dropped.
+    Stopped,
+    /// A command sent through a [`ShardHandle`] is invalid, for example
+    /// because it needs an intent that is not enabled.
+    InvalidRequest(String),
+    /// A response to a command sent through a [`ShardHandle`] did not arrive in time.
+    Timeout(String),
+}
+
+impl fmt::Display for GatewayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GatewayError::WebSocket(error) => write!(f, "gateway connection failed: {}", error),
+            GatewayError::Protocol(message) => write!(f, "unexpected gateway payload: {}", message),
+            GatewayError::InvalidIntents => write!(f, "gateway rejected the intents as invalid (close code 4013)"),
+            GatewayError::DisallowedIntents(intents) => write!(
+                f,
+                "gateway rejected the privileged intents {} (close code 4014); enable them for the application in the Developer Portal or stop requesting them",
+                intents.names()
+            ),
+            GatewayError::Closed { code, reason } => write!(f, "gateway closed with code {}: {}", code, reason),
+            GatewayError::Stopped => write!(f, "the gateway client is no longer running"),
+            GatewayError::InvalidRequest(message) => write!(f, "invalid gateway command: {}", message),
+            GatewayError::Timeout(waiting_for) => write!(f, "timed out waiting for {}", waiting_for),
+        }
+    }
+}
+
+impl Error for GatewayError {}
+
+impl From<tokio_tungstenite::tungstenite::Error> for GatewayError {
+    fn from(error: tokio_tungstenite::tungstenite::Error) -> Self {
+        GatewayError::WebSocket(error)
+    }
+}
+
+/// A message read from the gateway socket.
+enum Frame {
+    Payload(Vec<u8>),
+    Close(Option<CloseFrame>),
+    /// A control message, or part of a compressed payload.
+    Incomplete,
+}
+
+fn read_frame(message: WsMessage, decompressor: &mut Option<Decompressor>) -> io::Result<Frame> {
+    match message {
+        WsMessage::Text(text) => Ok(Frame::Payload(text.as_bytes().to_vec())),
+        WsMessage::Binary(data) => match decompressor {
+            Some(decompressor) => Ok(decompressor.push(&data)?.map_or(Frame::Incomplete, Frame::Payload)),
+            // Uncompressed binary messages are ETF payloads.
+            None => Ok(Frame::Payload(data.to_vec())),
+        },
+        WsMessage::Close(frame) => Ok(Frame::Close(frame)),
+        _ => Ok(Frame::Incomplete),
+    }
+}
+
+/// What to do after a connection ends.
+enum Reconnect {
+    Resume,
+    Identify,
+}
+
+/// The state needed to resume a session on a new connection.
+#[derive(Default)]
+struct Session {
+    id: Option<String>,
+    resume_url: Option<String>,
+    sequence: Option<u64>,
+}
+
+/// A connection to the Discord gateway for one shard.
+///
+/// The client identifies, keeps the connection alive with heartbeats and
+/// resumes the session when the connection drops. Every dispatch event is
+/// applied to the [`Cache`], published on the [`EventBus`], fed to the
+/// [`InviteTracker`] and passed to the [`EventHandler`]. Messages are routed
+/// to the [`CommandRouter`], and component and modal interactions to its
+/// component and modal handlers.
+///
+/// # Examples
+///
+/// ```no_run
+/// use reqwest::Client;
+/// use std::sync::Arc;
+/// use Rust_Discord_API::{CommandRouter, EventHandler, GatewayClient, Intents};
+///
+/// struct Handler;
+///
+/// impl EventHandler for Handler {}
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let token = std::env::var("DISCORD_TOKEN")?;
+///     let router = CommandRouter::new();
+///
+///     let intents = Intents::GUILDS | Intents::GUILD_MESSAGES | Intents::MESSAGE_CONTENT;
+///     let gateway = GatewayClient::new(Client::new(), &token, intents)
+///         .event_handler(Arc::new(Handler))
+///         .router(Arc::new(router));
+///
+///     gateway.run().await?;
+///     Ok(())
+/// }
+/// ```
+pub struct GatewayClient {
+    client: Client,
+    token: String,
+    intents: Intents,
+    url: String,
+    shard: Option<[u32; 2]>,
+    compression: Compression,
+    encoding: Encoding,
+    handler: Option<Arc<dyn EventHandler>>,
+    router: Option<Arc<CommandRouter>>,
+    cache: Option<Arc<Cache>>,
+    invite_tracker: Option<TrackerQueue>,
+    bus: EventBus,
+    handle: ShardHandle,
+    commands: tokio::sync::Mutex<UnboundedReceiver<ShardCommand>>,
+}
+
+impl GatewayClient {
+    /// Creates a gateway client.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The HTTP client passed to handlers.
+    /// * `token` - The bot token for authentication.
+    /// * `intents` - The gateway intents, selecting which events are received.
+    pub fn new(client: Client, token: &str, intents: Intents) -> Self {
+        let (sender, commands) = unbounded_channel();
+        Self {
+            client,
+            token: token.to_string(),
+            intents,
+            url: "wss://gateway.discord.gg".to_string(),
+            shard: None,
+            compression: Compression::None,
+            encoding: Encoding::Json,
+            handler: None,
+            router: None,
+            cache: None,
+            invite_tracker: None,
+            bus: EventBus::default(),
+            handle: ShardHandle { shard: None, intents, state: Arc::new(ShardState::default()), commands: sender },
+            commands: tokio::sync::Mutex::new(commands),
+        }
+    }
+
+    /// Sets the handler called for every dispatch event.
+    pub fn event_handler(mut self, handler: Arc<dyn EventHandler>) -> Self {
+        self.handler = Some(handler);
+        self
+    }
+
+    /// Sets the router that receives messages and component and modal interactions.
+    pub fn router(mut self, router: Arc<CommandRouter>) -> Self {
+        self.router = Some(router);
+        self
+    }
+
+    /// Sets the cache kept up to date from dispatch events.
+    pub fn cache(mut self, cache: Arc<Cache>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Sets the tracker that attributes member joins to invites. Events are
+    /// applied to it in order for each guild.
+    pub fn invite_tracker(mut self, tracker: Arc<InviteTracker>) -> Self {
+        self.invite_tracker = Some(TrackerQueue::new(tracker, self.client.clone(), &self.token));
+        self
+    }
+
+    /// Sets the bus dispatch events are published to, for collectors and paginators.
+    pub fn event_bus(mut self, bus: EventBus) -> Self {
+        self.bus = bus;
+        self
+    }
+
+    /// Connects as shard `id` of `total`.
+    pub fn shard(mut self, id: u32, total: u32) -> Self {
+        self.shard = Some([id, total]);
+        self.handle.shard = self.shard;
+        self
+    }
+
+    /// Sets the presence sent at Identify.
+    pub fn presence(self, presence: Presence) -> Self {
+        *self.handle.state.presence.lock().unwrap() = Some(presence);
+        self
+    }
+
+    /// Returns a handle for updating this shard while it runs.
+    pub fn handle(&self) -> ShardHandle {
+        self.handle.clone()
+    }
+
+    /// Sets the transport compression of the connection.
+    pub fn compression(mut self, compression: Compression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Sets the payload encoding of the connection.
+    pub fn encoding(mut self, encoding: Encoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// Overrides the gateway URL, for example the one returned by `GET /gateway/bot`.
+    pub fn url(mut self, url: &str) -> Self {
+        self.url = url.to_string();
+        self
+    }
+
+    /// Returns a warning for each intent the handler or router needs but the
+    /// client does not request. `run` prints these before connecting.
+    pub fn intent_warnings(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+
+        if let Some(handler) = &self.handler {
+            let missing = handler.intents().difference(self.intents);
+            if !missing.is_empty() {
+                warnings.push(format!("The event handler needs intents that are not enabled: {}", missing.names()));
+            }
+        }
+
+        if self.router.as_ref().is_some_and(|router| router.has_commands()) {
+            if !self.intents.intersects(Intents::GUILD_MESSAGES | Intents::DIRECT_MESSAGES) {
+                warnings.push("The router has prefix commands, but neither GUILD_MESSAGES nor DIRECT_MESSAGES is enabled, so no messages will arrive".to_string());
+            } else if !self.intents.contains(Intents::MESSAGE_CONTENT) {
+                warnings.push("The router has prefix commands, but MESSAGE_CONTENT is not enabled, so guild messages that don't mention the bot arrive with empty content".to_string());
+            }
+        }
+
+        warnings
+    }
+
+    /// Connects and processes events until Discord closes the connection with
+    /// a close code that must not be retried. Dropped connections are resumed,
+    /// and failed connection attempts are retried with backoff.
+    ///
+    /// Only one `run` of a client can be active at a time; further calls wait for it to end.
+    pub async fn run(&self) -> Result<(), GatewayError> {
+        let mut commands = self.commands.lock().await;
+        for warning in self.intent_warnings() {
+            eprintln!("Warning: {}", warning);
+        }
+
+        let mut session = Session::default();
+        let mut backoff = Duration::from_secs(1);
+
+        loop {
+            let url = session.resume_url.as_deref().unwrap_or(&self.url);
+            let url = format!("{}/?{}&{}{}", url.trim_end_matches('/'), GATEWAY_VERSION, self.encoding.query(), self.compression.query());
+
+            match self.connect(&url, &mut session, &mut commands).await {
+                Ok(Reconnect::Resume) => backoff = Duration::from_secs(1),
+                Ok(Reconnect::Identify) => {
+                    backoff = Duration::from_secs(1);
+                    session = Session::default();
+                    self.handle.state.session_ended();
+                }
+                Err(error @ (GatewayError::Closed { .. } | GatewayError::InvalidIntents | GatewayError::DisallowedIntents(_))) => {
+                    self.handle.state.session_ended();
+                    return Err(error);
+                }
+                Err(error) => {
+                    eprintln!("{}, retrying in {:?}", error, backoff);
+                    sleep(backoff).await;
+                    backoff = (backoff * 2).min(Duration::from_secs(60));
+                }
+            }
+        }
+    }
+
+    /// Runs one connection until it ends.
+    async fn connect(&self, url: &str, session: &mut Session, commands: &mut UnboundedReceiver<ShardCommand>) -> Result<Reconnect, GatewayError> {
+        let (mut socket, _) = connect_async(url).await?;
+        // The compression context lives as long as the connection.
+        let mut decompressor = Decompressor::new(self.compression).map_err(|error| GatewayError::Protocol(error.to_string()))?;
+
+        let hello = loop {
+            let message = match socket.next().await {
+                Some(Ok(message)) => message,
+                Some(Err(error)) => return Err(error.into()),
+                None => return Err(GatewayError::Protocol("expected Hello".to_string())),
+            };
+            match read_frame(message, &mut decompressor).map_err(|error| GatewayError::Protocol(error.to_string()))? {
+                Frame::Payload(data) => break self.encoding.decode(&data).map_err(GatewayError::Protocol)?,
+                Frame::Close(frame) => return close_action(frame, self.intents),
+                Frame::Incomplete => {}
+            }
+        };
+        let interval = match (hello["op"].as_u64(), hello["d"]["heartbeat_interval"].as_u64()) {
+            (Some(code), Some(interval)) if code == op::HELLO as u64 => Duration::from_millis(interval),
+            _ => return Err(GatewayError::Protocol(hello.to_string())),
+        };
+
+        let handshake = match (&session.id, session.sequence) {
+            (Some(session_id), Some(sequence)) => self.payload(op::RESUME, json!({ "token": self.token, "session_id": session_id, "seq": sequence })),
+            _ => self.payload(op::IDENTIFY, self.identify()),
+        };
+        socket.send(handshake).await?;
+
+        // The first heartbeat is jittered so that many clients don't send at once.
+        let mut heartbeat = interval_at(Instant::now() + interval.mul_f64(jitter()), interval);
+        let mut acknowledged = true;
+
+        loop {
+            tokio::select! {
+                _ = heartbeat.tick() => {
+                    if !acknowledged {
+                        // The connection is dead without being closed.
+                        let _ = socket.close(Some(resume_close())).await;
+                        return Ok(Reconnect::Resume);
+                    }
+                    acknowledged = false;
+                    if socket.send(self.payload(op::HEARTBEAT, json!(session.sequence))).await.is_err() {
+                        return Ok(Reconnect::Resume);
+                    }
+                }
+                Some(command) = commands.recv() => {
+                    let payload = match command {
+                        ShardCommand::UpdatePresence(presence) => self.payload(op::PRESENCE_UPDATE, json!(presence)),
+                        ShardCommand::RequestGuildMembers(request) => self.payload(op::REQUEST_GUILD_MEMBERS, request),
+                        ShardCommand::UpdateVoiceState(state) => self.payload(op::VOICE_STATE_UPDATE, state),
+                    };
+                    if socket.send(payload).await.is_err() {
+                        return Ok(Reconnect::Resume);
+                    }
+                }
+                message = socket.next() => {
+                    let message = match message {
+                        Some(Ok(message)) => message,
+                        Some(Err(error)) => {
+                            eprintln!("Gateway connection lost: {}", error);
+                            return Ok(Reconnect::Resume);
+                        }
+                        None => return Ok(Reconnect::Resume),
+                    };
+                    let data = match read_frame(message, &mut decompressor) {
+                        Ok(Frame::Payload(data)) => data,
+                        Ok(Frame::Close(frame)) => return close_action(frame, self.intents),
+                        Ok(Frame::Incomplete) => continue,
+                        Err(error) => {
+                            // The stream can't be recovered, but a new connection can resume the session.
+                            eprintln!("Failed to decompress gateway payload: {}", error);
+                            let _ = socket.close(Some(resume_close())).await;
+                            return Ok(Reconnect::Resume);
+                        }
+                    };
+                    let payload = match self.encoding.decode(&data) {
+                        Ok(payload) => payload,
+                        Err(error) => {
+                            eprintln!("Failed to parse gateway payload: {}", error);
+                            continue;
+                        }
+                    };
+
+                    match payload["op"].as_u64().and_then(|code| u8::try_from(code).ok()) {
+                        Some(op::DISPATCH) => {
+                            if let Some(sequence) = payload["s"].as_u64() {
+                                session.sequence = Some(sequence);
+                            }
+                            let name = payload["t"].as_str().unwrap_or_default().to_string();
+                            let data = payload.get("d").cloned().unwrap_or(Value::Null);
+                            if name == "READY" {
+                                session.id = data["session_id"].as_str().map(str::to_string);
+                                session.resume_url = data["resume_gateway_url"].as_str().map(str::to_string);
+                            }
+                            self.handle_dispatch(&name, data).await;
+                        }
+                        Some(op::HEARTBEAT) => {
+                            // Discord asked for a heartbeat outside the regular interval.
+                            let sent = socket.send(self.payload(op::HEARTBEAT, json!(session.sequence))).await;
+                            if sent.is_err() {
+                                return Ok(Reconnect::Resume);
+                            }
+                        }
+                        Some(op::HEARTBEAT_ACK) => acknowledged = true,
+                        Some(op::RECONNECT) => {
+                            let _ = socket.close(Some(resume_close())).await;
+                            return Ok(Reconnect::Resume);
+                        }
+                        Some(op::INVALID_SESSION) => {
+                            let resumable = payload["d"].as_bool().unwrap_or(false);
+                            let _ = socket.close(None).await;
+                            // Discord asks for a random wait of 1 to 5 seconds before identifying again.
+                            sleep(Duration::from_secs(1).mul_f64(1.0 + 4.0 * jitter())).await;
+                            return Ok(if resumable { Reconnect::Resume } else { Reconnect::Identify });
+                        }
+                        _ => eprintln!("Ignoring gateway payload with unknown opcode {}", payload["op"]),
+                    }
+                }
+            }
+        }
+    }
+
+    fn payload(&self, op: u8, data: Value) -> WsMessage {
+        self.encoding.encode(&json!({ "op": op, "d": data }))
+    }
+
+    fn identify(&self) -> Value {
+        let mut identify = json!({
+            "token": self.token,
+            "intents": self.intents.bits(),
+            "properties": {
+                "os": std::env::consts::OS,
+                "browser": "Rust-Discord-API",
+                "device": "Rust-Discord-API",
+            },
+        });
+        if let Some(shard) = self.shard {
+            identify["shard"] = json!(shard);
+        }
+        if let Some(presence) = &*self.handle.state.presence.lock().unwrap() {
+            identify["presence"] = json!(presence);
+        }
+        identify
+    }
+
+    /// Applies a dispatch event to the cache and bus, then runs the handler and
+    /// router in their own tasks.
+    async fn handle_dispatch(&self, name: &str, data: Value) {
+        // Kept to fail the member request a chunk answers if it can't be parsed.
+        let nonce = match name {
+            "GUILD_MEMBERS_CHUNK" => data["nonce"].as_str().map(str::to_string),
+            _ => None,
+        };
+        let event = match Event::from_dispatch(name, data) {
+            Ok(event) => Arc::new(event),
+            Err(error) => {
+                eprintln!("Failed to parse {} event: {}", name, error);
+                if let Some(nonce) = nonce {
+                    self.handle.state.members_chunk_failed(&nonce, &error);
+                }
+                return;
+            }
+        };
+
+        if let Some(cache) = &self.cache {
+            if let Err(error) = cache.update(&event).await {
+                eprintln!("Failed to cache {} event: {}", name, error);
+            }
+        }
+        match &*event {
+            Event::Ready(ready) => *self.handle.state.user_id.lock().unwrap() = Some(ready.user.id.clone()),
+            Event::GuildMembersChunk(chunk) => self.handle.state.members_chunk(chunk),
+            Event::VoiceStateUpdate(state) => self.handle.state.voice_state(state),
+            Event::VoiceServerUpdate(server) => self.handle.state.voice_server(server),
+            _ => {}
+        }
+        self.bus.publish(event.clone());
+
+        if let Some(tracker) = &self.invite_tracker {
+            tracker.push(event.clone());
+        }
+
+        if let Some(handler) = &self.handler {
+            let (handler, client, token, event) = (handler.clone(), self.client.clone(), self.token.clone(), event.clone());
+            spawn_isolated(format!("{} handler", name), async move {
+                dispatch_event(&*handler, &client, &token, &event).await
+            });
+        }
+
+        if let Some(router) = &self.router {
+            let (router, client, token) = (router.clone(), self.client.clone(), self.token.clone());
+            match &*event {
+                Event::MessageCreate(message) if !message.author.bot && router.is_command(&message.content) => {
+                    let (channel_id, content) = (message.channel_id.clone(), message.content.clone());
+                    spawn_isolated(format!("command {}", content), async move {
+                        router.dispatch(&client, &token, &channel_id, &content).await
+                    });
+                }
+                Event::InteractionCreate(interaction) if interaction.kind == InteractionType::MessageComponent => {
+                    let interaction = interaction.clone();
+                    spawn_isolated("component handler".to_string(), async move {
+                        router.dispatch_component(&client, &token, &interaction).await
+                    });
+                }
+                Event::InteractionCreate(interaction) if interaction.kind == InteractionType::ModalSubmit => {
+                    let interaction = interaction.clone();
+                    spawn_isolated("modal handler".to_string(), async move {
+                        router.dispatch_modal(&client, &token, &interaction).await
+                    });
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Runs a handler in its own task, reporting errors and panics instead of
+/// letting them reach the connection.
+fn spawn_isolated<F>(label: String, future: F)
+where
+    F: Future<Output = Result<(), Box<dyn Error>>> + Send + 'static,
+{
+    tokio::spawn(async move {
+        match AssertUnwindSafe(future).catch_unwind().await {
+            Ok(Ok(())) => {}
+            Ok(Err(error)) => eprintln!("Error in {}: {}", label, error),
+            Err(_) => eprintln!("Panic in {}", label),
+        }
+    });
+}
+
+/// A close frame with a non-1000 code, which keeps the session resumable.
+fn resume_close() -> CloseFrame {
+    CloseFrame { code: CloseCode::from(4000), reason: "reconnecting".into() }
+}
+
+/// Decides how to continue after Discord closes the connection.
+fn close_action(frame: Option<CloseFrame>, intents: Intents) -> Result<Reconnect, GatewayError> {
+    let Some(frame) = frame else {
+        return Ok(Reconnect::Resume);
+    };
+    match u16::from(frame.code) {
+        // Invalid sequence or session timed out.
+        4007 | 4009 => Ok(Reconnect::Identify),
+        4013 => Err(GatewayError::InvalidIntents),
+        4014 => Err(GatewayError::DisallowedIntents(intents.privileged())),
+        // Authentication failed, invalid shard, sharding required or invalid API version.
+        code @ (4004 | 4010 | 4011 | 4012) => Err(GatewayError::Closed { code, reason: frame.reason.to_string() }),
+        _ => Ok(Reconnect::Resume),
+    }
+}
+
+/// A pseudo-random number in `0.0..1.0`, good enough to spread out timers.
+fn jitter() -> f64 {
+    let nanos = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).map(|time| time.subsec_nanos()).unwrap_or(0);
+    nanos as f64 / 1_000_000_000.0
+}
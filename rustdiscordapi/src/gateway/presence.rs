@@ -0,0 +1,164 @@
+use serde::{Deserialize, Serialize};
+
+use crate::utils::component::PartialEmoji;
+
+/// The online status of a user.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    #[default]
+    Online,
+    Idle,
+    /// Do not disturb.
+    Dnd,
+    /// Shown as offline to others.
+    Invisible,
+    /// Only received, never sent.
+    Offline,
+}
+
+/// The kind of an activity, which decides how its name is shown.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(from = "u8", into = "u8")]
+pub enum ActivityType {
+    /// "Playing {name}"
+    Playing,
+    /// "Streaming {details}", needs a Twitch or YouTube URL.
+    Streaming,
+    /// "Listening to {name}"
+    Listening,
+    /// "Watching {name}"
+    Watching,
+    /// "{emoji} {state}"
+    Custom,
+    /// "Competing in {name}"
+    Competing,
+    /// An activity type this crate does not model yet, such as those set by
+    /// user clients.
+    Unknown(u8),
+}
+
+impl From<u8> for ActivityType {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => ActivityType::Playing,
+            1 => ActivityType::Streaming,
+            2 => ActivityType::Listening,
+            3 => ActivityType::Watching,
+            4 => ActivityType::Custom,
+            5 => ActivityType::Competing,
+            other => ActivityType::Unknown(other),
+        }
+    }
+}
+
+impl From<ActivityType> for u8 {
+    fn from(kind: ActivityType) -> Self {
+        match kind {
+            ActivityType::Playing => 0,
+            ActivityType::Streaming => 1,
+            ActivityType::Listening => 2,
+            ActivityType::Watching => 3,
+            ActivityType::Custom => 4,
+            ActivityType::Competing => 5,
+            ActivityType::Unknown(value) => value,
+        }
+    }
+}
+
+/// An activity shown in a user's presence.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Activity {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: ActivityType,
+    /// The stream URL of a streaming activity.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// The text of a custom status, or the party status of other activities.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<String>,
+    /// The emoji of a custom status. Only received.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub emoji: Option<PartialEmoji>,
+    /// Unix time in milliseconds when the activity was added. Only received.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<u64>,
+}
+
+impl Activity {
+    fn new(kind: ActivityType, name: &str) -> Self {
+        Self { name: name.to_string(), kind, url: None, state: None, details: None, emoji: None, created_at: None }
+    }
+
+    /// Creates a "Playing {name}" activity.
+    pub fn playing(name: &str) -> Self {
+        Self::new(ActivityType::Playing, name)
+    }
+
+    /// Creates a streaming activity. `url` must be a Twitch or YouTube URL.
+    pub fn streaming(name: &str, url: &str) -> Self {
+        Self { url: Some(url.to_string()), ..Self::new(ActivityType::Streaming, name) }
+    }
+
+    /// Creates a "Listening to {name}" activity.
+    pub fn listening(name: &str) -> Self {
+        Self::new(ActivityType::Listening, name)
+    }
+
+    /// Creates a "Watching {name}" activity.
+    pub fn watching(name: &str) -> Self {
+        Self::new(ActivityType::Watching, name)
+    }
+
+    /// Creates a custom status showing `state`.
+    pub fn custom(state: &str) -> Self {
+        Self { state: Some(state.to_string()), ..Self::new(ActivityType::Custom, "Custom Status") }
+    }
+
+    /// Creates a "Competing in {name}" activity.
+    pub fn competing(name: &str) -> Self {
+        Self::new(ActivityType::Competing, name)
+    }
+}
+
+/// The presence the bot shows, sent at Identify and with presence updates (opcode 3).
+///
+/// # Examples
+///
+/// ```
+/// use Rust_Discord_API::gateway::{Activity, Presence, Status};
+///
+/// let presence = Presence::new(Status::Dnd).activity(Activity::watching("the logs"));
+/// assert_eq!(presence.activities.len(), 1);
+/// ```
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct Presence {
+    /// Unix time in milliseconds since the client went idle.
+    pub since: Option<u64>,
+    pub activities: Vec<Activity>,
+    pub status: Status,
+    pub afk: bool,
+}
+
+impl Presence {
+    /// Creates a presence with a status and no activities.
+    pub fn new(status: Status) -> Self {
+        Self { status, ..Self::default() }
+    }
+
+    /// Adds an activity. Bots can only show one activity.
+    pub fn activity(mut self, activity: Activity) -> Self {
+        self.activities.push(activity);
+        self
+    }
+
+    /// Marks the client as AFK since `since`, in Unix milliseconds.
+    pub fn afk(mut self, since: u64) -> Self {
+        self.afk = true;
+        self.since = Some(since);
+        self
+    }
+}
@@ -0,0 +1,236 @@
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+use tokio::sync::oneshot;
+
+use super::members::{MemberChunks, MemberRequest, PendingMembers, DEFAULT_CHUNK_TIMEOUT, MAX_USER_IDS};
+use super::{GatewayError, Intents, Presence};
+use crate::event::{GuildMembersChunk, VoiceServerUpdate};
+use crate::model::VoiceState;
+use crate::voice::VoiceServerInfo;
+
+/// How long [`ShardHandle::join_voice`] waits for the voice server to be assigned.
+pub const VOICE_SERVER_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A command for the running connection of a shard.
+pub(crate) enum ShardCommand {
+    UpdatePresence(Presence),
+    RequestGuildMembers(Value),
+    UpdateVoiceState(Value),
+}
+
+/// A voice channel join waiting for its voice state and voice server.
+struct PendingVoice {
+    state: Option<VoiceState>,
+    server: Option<VoiceServerUpdate>,
+    sender: oneshot::Sender<VoiceServerInfo>,
+}
+
+/// State shared between a [`GatewayClient`](super::GatewayClient) and its handles.
+#[derive(Default)]
+pub(crate) struct ShardState {
+    /// The presence sent at Identify.
+    pub(crate) presence: Mutex<Option<Presence>>,
+    /// Member requests waiting for chunks, by nonce.
+    members: Mutex<HashMap<String, PendingMembers>>,
+    next_nonce: AtomicU64,
+    /// The bot's user ID, from READY.
+    pub(crate) user_id: Mutex<Option<String>>,
+    /// Voice channel joins waiting for the voice server, by guild ID.
+    voice: Mutex<HashMap<String, PendingVoice>>,
+}
+
+impl ShardState {
+    /// Passes a chunk to the request it answers, if it is still waiting.
+    pub(crate) fn members_chunk(&self, chunk: &GuildMembersChunk) {
+        let Some(nonce) = &chunk.nonce else { return };
+        let mut pending = self.members.lock().unwrap();
+        let Some(request) = pending.get_mut(nonce) else { return };
+        request.received += 1;
+        let _ = request.sender.send(Ok(chunk.clone()));
+        if request.received >= chunk.chunk_count {
+            pending.remove(nonce);
+        }
+    }
+
+    /// Fails the request a chunk answers when the chunk could not be parsed,
+    /// as the request can no longer be completed.
+    pub(crate) fn members_chunk_failed(&self, nonce: &str, error: &serde_json::Error) {
+        if let Some(request) = self.members.lock().unwrap().remove(nonce) {
+            let _ = request.sender.send(Err(GatewayError::Protocol(format!("a member chunk could not be parsed: {}", error))));
+        }
+    }
+
+    /// Records the bot's voice state for a join waiting in its guild.
+    pub(crate) fn voice_state(&self, state: &VoiceState) {
+        if self.user_id.lock().unwrap().as_deref() != Some(state.user_id.as_str()) {
+            return;
+        }
+        let Some(guild_id) = &state.guild_id else { return };
+        if let Some(pending) = self.voice.lock().unwrap().get_mut(guild_id) {
+            pending.state = Some(state.clone());
+        }
+        self.complete_voice(guild_id);
+    }
+
+    /// Records the voice server for a join waiting in its guild.
+    pub(crate) fn voice_server(&self, server: &VoiceServerUpdate) {
+        // Without an endpoint the server is still being allocated; another update follows.
+        if server.endpoint.is_none() {
+            return;
+        }
+        if let Some(pending) = self.voice.lock().unwrap().get_mut(&server.guild_id) {
+            pending.server = Some(server.clone());
+        }
+        self.complete_voice(&server.guild_id);
+    }
+
+    /// Finishes a join once both its voice state and voice server arrived.
+    fn complete_voice(&self, guild_id: &str) {
+        let mut voice = self.voice.lock().unwrap();
+        if !voice.get(guild_id).is_some_and(|pending| pending.state.is_some() && pending.server.is_some()) {
+            return;
+        }
+        let Some(PendingVoice { state: Some(state), server: Some(server), sender }) = voice.remove(guild_id) else { return };
+        let _ = sender.send(VoiceServerInfo {
+            guild_id: server.guild_id,
+            channel_id: state.channel_id,
+            user_id: state.user_id,
+            session_id: state.session_id,
+            token: server.token,
+            endpoint: server.endpoint.unwrap_or_default(),
+        });
+    }
+
+    /// Ends the member requests and voice joins of a discarded session, as
+    /// their events won't arrive. A resumed session replays them instead.
+    pub(crate) fn session_ended(&self) {
+        self.members.lock().unwrap().clear();
+        self.voice.lock().unwrap().clear();
+    }
+}
+
+/// A handle for sending commands to a running [`GatewayClient`](super::GatewayClient),
+/// obtained with [`GatewayClient::handle`](super::GatewayClient::handle).
+///
+/// Commands sent while the shard is reconnecting are sent once it is connected again.
+#[derive(Clone)]
+pub struct ShardHandle {
+    pub(crate) shard: Option<[u32; 2]>,
+    pub(crate) intents: Intents,
+    pub(crate) state: Arc<ShardState>,
+    pub(crate) commands: UnboundedSender<ShardCommand>,
+}
+
+impl ShardHandle {
+    /// Returns the shard ID and shard count, if the client is sharded.
+    pub fn shard(&self) -> Option<[u32; 2]> {
+        self.shard
+    }
+
+    /// Updates the bot's presence on this shard. The presence is also kept
+    /// for later identifies, so it survives reconnects.
+    pub fn update_presence(&self, presence: Presence) -> Result<(), GatewayError> {
+        *self.state.presence.lock().unwrap() = Some(presence.clone());
+        self.commands.send(ShardCommand::UpdatePresence(presence)).map_err(|_| GatewayError::Stopped)
+    }
+
+    /// Requests guild members over the gateway (opcode 8), which is much
+    /// faster than paging through the REST API for large guilds. The guild
+    /// must be on this shard.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The members to request.
+    ///
+    /// # Returns
+    ///
+    /// A result containing the response chunks, to read as a stream or collect with `collect_members`.
+    pub fn request_guild_members(&self, mut request: MemberRequest) -> Result<MemberChunks, GatewayError> {
+        if request.presences && !self.intents.contains(Intents::GUILD_PRESENCES) {
+            return Err(GatewayError::InvalidRequest("requesting presences needs the GUILD_PRESENCES intent".to_string()));
+        }
+        if request.query.as_deref() == Some("") && !self.intents.contains(Intents::GUILD_MEMBERS) {
+            return Err(GatewayError::InvalidRequest("requesting all members needs the GUILD_MEMBERS intent".to_string()));
+        }
+        if request.user_ids.as_ref().is_some_and(|user_ids| user_ids.len() > MAX_USER_IDS) {
+            return Err(GatewayError::InvalidRequest(format!("at most {} user IDs can be requested at once", MAX_USER_IDS)));
+        }
+
+        let nonce = match &request.nonce {
+            Some(nonce) if nonce.len() > 32 => return Err(GatewayError::InvalidRequest("the nonce is longer than 32 bytes".to_string())),
+            Some(nonce) => nonce.clone(),
+            None => format!("members-{}", self.state.next_nonce.fetch_add(1, Ordering::Relaxed)),
+        };
+        request.nonce = Some(nonce.clone());
+
+        let (sender, receiver) = unbounded_channel();
+        {
+            let mut pending = self.state.members.lock().unwrap();
+            if pending.contains_key(&nonce) {
+                return Err(GatewayError::InvalidRequest(format!("a member request with nonce {} is already waiting", nonce)));
+            }
+            pending.insert(nonce.clone(), PendingMembers { sender, received: 0 });
+        }
+
+        if self.commands.send(ShardCommand::RequestGuildMembers(request.payload())).is_err() {
+            self.state.members.lock().unwrap().remove(&nonce);
+            return Err(GatewayError::Stopped);
+        }
+        Ok(MemberChunks { receiver, timeout: DEFAULT_CHUNK_TIMEOUT })
+    }
+
+    /// Joins a voice channel, or moves to another one in the same guild
+    /// (opcode 4), and waits for the voice server to be assigned. The guild
+    /// must be on this shard and the `GUILD_VOICE_STATES` intent enabled.
+    /// Gives up after [`VOICE_SERVER_TIMEOUT`].
+    ///
+    /// # Arguments
+    ///
+    /// * `guild_id` - The ID of the guild.
+    /// * `channel_id` - The ID of the voice channel to join.
+    /// * `self_mute` - Whether the bot is muted.
+    /// * `self_deaf` - Whether the bot is deafened.
+    ///
+    /// # Returns
+    ///
+    /// A result containing the voice server and session that `VoiceGateway::connect` needs.
+    pub async fn join_voice(&self, guild_id: &str, channel_id: &str, self_mute: bool, self_deaf: bool) -> Result<VoiceServerInfo, GatewayError> {
+        if !self.intents.contains(Intents::GUILD_VOICE_STATES) {
+            return Err(GatewayError::InvalidRequest("joining voice channels needs the GUILD_VOICE_STATES intent".to_string()));
+        }
+        let (sender, receiver) = oneshot::channel();
+        self.state.voice.lock().unwrap().insert(guild_id.to_string(), PendingVoice { state: None, server: None, sender });
+
+        if let Err(error) = self.update_voice_state(guild_id, Some(channel_id), self_mute, self_deaf) {
+            self.state.voice.lock().unwrap().remove(guild_id);
+            return Err(error);
+        }
+        match tokio::time::timeout(VOICE_SERVER_TIMEOUT, receiver).await {
+            Ok(Ok(info)) => Ok(info),
+            Ok(Err(_)) => Err(GatewayError::Protocol("the session ended before the voice server was assigned".to_string())),
+            Err(_) => {
+                // Only drop this join's entry, not one a later join put in its place.
+                let mut voice = self.state.voice.lock().unwrap();
+                if voice.get(guild_id).is_some_and(|pending| pending.sender.is_closed()) {
+                    voice.remove(guild_id);
+                }
+                Err(GatewayError::Timeout("the voice server".to_string()))
+            }
+        }
+    }
+
+    /// Leaves the voice channel the bot is in within a guild.
+    pub fn leave_voice(&self, guild_id: &str) -> Result<(), GatewayError> {
+        self.state.voice.lock().unwrap().remove(guild_id);
+        self.update_voice_state(guild_id, None, false, false)
+    }
+
+    fn update_voice_state(&self, guild_id: &str, channel_id: Option<&str>, self_mute: bool, self_deaf: bool) -> Result<(), GatewayError> {
+        let payload = json!({ "guild_id": guild_id, "channel_id": channel_id, "self_mute": self_mute, "self_deaf": self_deaf });
+        self.commands.send(ShardCommand::UpdateVoiceState(payload)).map_err(|_| GatewayError::Stopped)
+    }
+}
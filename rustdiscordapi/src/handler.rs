@@ -0,0 +1,604 @@
+use async_trait::async_trait;
+use reqwest::Client;
+use serde_json::Value;
+use std::error::Error;
+
+use crate::event::{
+    AutoModerationActionExecution, ChannelPinsUpdate, Event, GuildBan, GuildEmojisUpdate,
+    GuildIntegrationsUpdate, GuildMembersChunk, GuildStickersUpdate, IntegrationDelete,
+    InviteDelete, MemberAdd, MemberRemove, MemberUpdate, MessageDelete, MessageDeleteBulk,
+    MessageUpdate, PollVote, PresenceUpdate, ReactionAdd, ReactionRemove, ReactionRemoveAll,
+    ReactionRemoveEmoji, Ready, RoleDelete, RoleEvent, ScheduledEventUser, ThreadDelete,
+    ThreadListSync, ThreadMemberUpdate, ThreadMembersUpdate, TypingStart, UnavailableGuild,
+    VoiceServerUpdate, WebhooksUpdate,
+};
+use crate::gateway::Intents;
+use crate::model::{Channel, Guild, Message, User, VoiceState};
+use crate::utils::audit_log::AuditLogEntry;
+use crate::utils::auto_moderation::AutoModerationRule;
+use crate::utils::guild_scheduled_event::ScheduledEvent;
+use crate::utils::interaction::Interaction;
+use crate::utils::invite::Invite;
+use crate::utils::stage_instance::StageInstance;
+
+#[async_trait]
+/// The `EventHandler` trait reacts to gateway dispatch events.
+///
+/// Every method has a no-op default, so implement only the events you need.
+///
+/// Discord only sends the events of the intents the gateway client enables,
+/// and each method notes the intent its event needs. Override
+/// [`intents`](EventHandler::intents) to return the intents of the methods
+/// you implement, so the client warns at startup when one is missing;
+/// otherwise the events just never arrive.
+/// The gateway calls each event in its own task, so handlers run concurrently
+/// and may see events out of order. A handler that returns an error or panics
+/// is reported and does not affect the connection or other handlers.
+///
+/// # Examples
+///
+/// ```
+/// use async_trait::async_trait;
+/// use reqwest::Client;
+/// use std::error::Error;
+/// use Rust_Discord_API::{EventHandler, Intents};
+/// use Rust_Discord_API::event::MemberAdd;
+///
+/// struct Welcome;
+///
+/// #[async_trait]
+/// impl EventHandler for Welcome {
+///     fn intents(&self) -> Intents {
+///         Intents::GUILD_MEMBERS
+///     }
+///
+///     async fn guild_member_add(&self, _client: &Client, _token: &str, event: &MemberAdd) -> Result<(), Box<dyn Error>> {
+///         println!("Someone joined {}", event.guild_id);
+///         Ok(())
+///     }
+/// }
+/// ```
+pub trait EventHandler: Send + Sync {
+    /// The intents the events this handler reacts to need. The gateway client
+    /// warns when any of them are not enabled.
+    ///
+    /// This can't be worked out from the methods a handler implements, so it
+    /// defaults to none and nothing is checked until it is overridden.
+    fn intents(&self) -> Intents {
+        Intents::empty()
+    }
+
+    /// Called when the connection is identified.
+    async fn ready(&self, _client: &Client, _token: &str, _event: &Ready) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when a dropped connection has been resumed.
+    async fn resumed(&self, _client: &Client, _token: &str) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when the permissions of an application command change.
+    async fn application_command_permissions_update(&self, _client: &Client, _token: &str, _event: &Value) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when an auto moderation rule is created.
+    /// Needs the `AUTO_MODERATION_CONFIGURATION` intent.
+    async fn auto_moderation_rule_create(&self, _client: &Client, _token: &str, _event: &AutoModerationRule) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when an auto moderation rule is updated.
+    /// Needs the `AUTO_MODERATION_CONFIGURATION` intent.
+    async fn auto_moderation_rule_update(&self, _client: &Client, _token: &str, _event: &AutoModerationRule) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when an auto moderation rule is deleted.
+    /// Needs the `AUTO_MODERATION_CONFIGURATION` intent.
+    async fn auto_moderation_rule_delete(&self, _client: &Client, _token: &str, _event: &AutoModerationRule) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when auto moderation takes an action.
+    /// Needs the `AUTO_MODERATION_EXECUTION` intent.
+    async fn auto_moderation_action_execution(&self, _client: &Client, _token: &str, _event: &AutoModerationActionExecution) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when a guild becomes available or the bot joins one.
+    /// Needs the `GUILDS` intent.
+    async fn guild_create(&self, _client: &Client, _token: &str, _event: &Guild) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when a guild is updated.
+    /// Needs the `GUILDS` intent.
+    async fn guild_update(&self, _client: &Client, _token: &str, _event: &Guild) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when the bot leaves a guild or it becomes unavailable.
+    /// Needs the `GUILDS` intent.
+    async fn guild_delete(&self, _client: &Client, _token: &str, _event: &UnavailableGuild) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when a channel is created.
+    /// Needs the `GUILDS` intent.
+    async fn channel_create(&self, _client: &Client, _token: &str, _event: &Channel) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when a channel is updated.
+    /// Needs the `GUILDS` intent.
+    async fn channel_update(&self, _client: &Client, _token: &str, _event: &Channel) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when a channel is deleted.
+    /// Needs the `GUILDS` intent.
+    async fn channel_delete(&self, _client: &Client, _token: &str, _event: &Channel) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when a thread is created or the bot is added to a private thread.
+    /// Needs the `GUILDS` intent.
+    async fn thread_create(&self, _client: &Client, _token: &str, _event: &Channel) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when a thread is updated.
+    /// Needs the `GUILDS` intent.
+    async fn thread_update(&self, _client: &Client, _token: &str, _event: &Channel) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when a thread is deleted.
+    /// Needs the `GUILDS` intent.
+    async fn thread_delete(&self, _client: &Client, _token: &str, _event: &ThreadDelete) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when the bot gains access to a channel, with its active threads.
+    /// Needs the `GUILDS` intent.
+    async fn thread_list_sync(&self, _client: &Client, _token: &str, _event: &ThreadListSync) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when the bot's thread member is updated.
+    /// Needs the `GUILDS` intent.
+    async fn thread_member_update(&self, _client: &Client, _token: &str, _event: &ThreadMemberUpdate) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when users are added to or removed from a thread.
+    /// Needs the `GUILDS` intent, and `GUILD_MEMBERS` for users other than the bot.
+    async fn thread_members_update(&self, _client: &Client, _token: &str, _event: &ThreadMembersUpdate) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when a message is pinned or unpinned.
+    /// Needs the `GUILDS` intent, or `DIRECT_MESSAGES` for DMs.
+    async fn channel_pins_update(&self, _client: &Client, _token: &str, _event: &ChannelPinsUpdate) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when a user subscribes to a SKU.
+    async fn entitlement_create(&self, _client: &Client, _token: &str, _event: &Value) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when an entitlement is updated.
+    async fn entitlement_update(&self, _client: &Client, _token: &str, _event: &Value) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when an entitlement is deleted.
+    async fn entitlement_delete(&self, _client: &Client, _token: &str, _event: &Value) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when an audit log entry is created.
+    /// Needs the `GUILD_MODERATION` intent.
+    async fn guild_audit_log_entry_create(&self, _client: &Client, _token: &str, _event: &AuditLogEntry) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when a user is banned.
+    /// Needs the `GUILD_MODERATION` intent.
+    async fn guild_ban_add(&self, _client: &Client, _token: &str, _event: &GuildBan) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when a user is unbanned.
+    /// Needs the `GUILD_MODERATION` intent.
+    async fn guild_ban_remove(&self, _client: &Client, _token: &str, _event: &GuildBan) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when the emojis of a guild change.
+    /// Needs the `GUILD_EXPRESSIONS` intent.
+    async fn guild_emojis_update(&self, _client: &Client, _token: &str, _event: &GuildEmojisUpdate) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when the stickers of a guild change.
+    /// Needs the `GUILD_EXPRESSIONS` intent.
+    async fn guild_stickers_update(&self, _client: &Client, _token: &str, _event: &GuildStickersUpdate) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when the integrations of a guild change.
+    /// Needs the `GUILD_INTEGRATIONS` intent.
+    async fn guild_integrations_update(&self, _client: &Client, _token: &str, _event: &GuildIntegrationsUpdate) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when a user joins a guild.
+    /// Needs the `GUILD_MEMBERS` intent.
+    async fn guild_member_add(&self, _client: &Client, _token: &str, _event: &MemberAdd) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when a guild member is updated.
+    /// Needs the `GUILD_MEMBERS` intent.
+    async fn guild_member_update(&self, _client: &Client, _token: &str, _event: &MemberUpdate) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when a user leaves or is removed from a guild.
+    /// Needs the `GUILD_MEMBERS` intent.
+    async fn guild_member_remove(&self, _client: &Client, _token: &str, _event: &MemberRemove) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when a role is created.
+    /// Needs the `GUILDS` intent.
+    async fn guild_role_create(&self, _client: &Client, _token: &str, _event: &RoleEvent) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when a role is updated.
+    /// Needs the `GUILDS` intent.
+    async fn guild_role_update(&self, _client: &Client, _token: &str, _event: &RoleEvent) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when a role is deleted.
+    /// Needs the `GUILDS` intent.
+    async fn guild_role_delete(&self, _client: &Client, _token: &str, _event: &RoleDelete) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called for each chunk of members sent in response to a Request Guild Members command.
+    async fn guild_members_chunk(&self, _client: &Client, _token: &str, _event: &GuildMembersChunk) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when a scheduled event is created.
+    /// Needs the `GUILD_SCHEDULED_EVENTS` intent.
+    async fn guild_scheduled_event_create(&self, _client: &Client, _token: &str, _event: &ScheduledEvent) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when a scheduled event is updated.
+    /// Needs the `GUILD_SCHEDULED_EVENTS` intent.
+    async fn guild_scheduled_event_update(&self, _client: &Client, _token: &str, _event: &ScheduledEvent) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when a scheduled event is deleted.
+    /// Needs the `GUILD_SCHEDULED_EVENTS` intent.
+    async fn guild_scheduled_event_delete(&self, _client: &Client, _token: &str, _event: &ScheduledEvent) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when a user subscribes to a scheduled event.
+    /// Needs the `GUILD_SCHEDULED_EVENTS` intent.
+    async fn guild_scheduled_event_user_add(&self, _client: &Client, _token: &str, _event: &ScheduledEventUser) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when a user unsubscribes from a scheduled event.
+    /// Needs the `GUILD_SCHEDULED_EVENTS` intent.
+    async fn guild_scheduled_event_user_remove(&self, _client: &Client, _token: &str, _event: &ScheduledEventUser) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when a soundboard sound is created.
+    /// Needs the `GUILD_EXPRESSIONS` intent.
+    async fn guild_soundboard_sound_create(&self, _client: &Client, _token: &str, _event: &Value) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when a soundboard sound is updated.
+    /// Needs the `GUILD_EXPRESSIONS` intent.
+    async fn guild_soundboard_sound_update(&self, _client: &Client, _token: &str, _event: &Value) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when a soundboard sound is deleted.
+    /// Needs the `GUILD_EXPRESSIONS` intent.
+    async fn guild_soundboard_sound_delete(&self, _client: &Client, _token: &str, _event: &Value) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when several soundboard sounds are updated.
+    /// Needs the `GUILD_EXPRESSIONS` intent.
+    async fn guild_soundboard_sounds_update(&self, _client: &Client, _token: &str, _event: &Value) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called with the soundboard sounds of a guild after requesting them.
+    async fn soundboard_sounds(&self, _client: &Client, _token: &str, _event: &Value) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when an integration is created.
+    /// Needs the `GUILD_INTEGRATIONS` intent.
+    async fn integration_create(&self, _client: &Client, _token: &str, _event: &Value) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when an integration is updated.
+    /// Needs the `GUILD_INTEGRATIONS` intent.
+    async fn integration_update(&self, _client: &Client, _token: &str, _event: &Value) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when an integration is deleted.
+    /// Needs the `GUILD_INTEGRATIONS` intent.
+    async fn integration_delete(&self, _client: &Client, _token: &str, _event: &IntegrationDelete) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when an invite is created.
+    /// Needs the `GUILD_INVITES` intent.
+    async fn invite_create(&self, _client: &Client, _token: &str, _event: &Invite) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when an invite is deleted.
+    /// Needs the `GUILD_INVITES` intent.
+    async fn invite_delete(&self, _client: &Client, _token: &str, _event: &InviteDelete) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when a message is sent.
+    /// Needs the `GUILD_MESSAGES` intent, or `DIRECT_MESSAGES` for DMs.
+    async fn message_create(&self, _client: &Client, _token: &str, _event: &Message) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when a message is edited.
+    /// Needs the `GUILD_MESSAGES` intent, or `DIRECT_MESSAGES` for DMs.
+    async fn message_update(&self, _client: &Client, _token: &str, _event: &MessageUpdate) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when a message is deleted.
+    /// Needs the `GUILD_MESSAGES` intent, or `DIRECT_MESSAGES` for DMs.
+    async fn message_delete(&self, _client: &Client, _token: &str, _event: &MessageDelete) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when several messages are deleted at once.
+    /// Needs the `GUILD_MESSAGES` intent.
+    async fn message_delete_bulk(&self, _client: &Client, _token: &str, _event: &MessageDeleteBulk) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when a user adds a reaction to a message.
+    /// Needs the `GUILD_MESSAGE_REACTIONS` intent, or `DIRECT_MESSAGE_REACTIONS` for DMs.
+    async fn message_reaction_add(&self, _client: &Client, _token: &str, _event: &ReactionAdd) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when a user removes a reaction from a message.
+    /// Needs the `GUILD_MESSAGE_REACTIONS` intent, or `DIRECT_MESSAGE_REACTIONS` for DMs.
+    async fn message_reaction_remove(&self, _client: &Client, _token: &str, _event: &ReactionRemove) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when all reactions are removed from a message.
+    /// Needs the `GUILD_MESSAGE_REACTIONS` intent, or `DIRECT_MESSAGE_REACTIONS` for DMs.
+    async fn message_reaction_remove_all(&self, _client: &Client, _token: &str, _event: &ReactionRemoveAll) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when all reactions of one emoji are removed from a message.
+    /// Needs the `GUILD_MESSAGE_REACTIONS` intent, or `DIRECT_MESSAGE_REACTIONS` for DMs.
+    async fn message_reaction_remove_emoji(&self, _client: &Client, _token: &str, _event: &ReactionRemoveEmoji) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when a user votes on a poll.
+    /// Needs the `GUILD_MESSAGE_POLLS` intent, or `DIRECT_MESSAGE_POLLS` for DMs.
+    async fn message_poll_vote_add(&self, _client: &Client, _token: &str, _event: &PollVote) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when a user removes a poll vote.
+    /// Needs the `GUILD_MESSAGE_POLLS` intent, or `DIRECT_MESSAGE_POLLS` for DMs.
+    async fn message_poll_vote_remove(&self, _client: &Client, _token: &str, _event: &PollVote) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when a user's presence changes.
+    /// Needs the `GUILD_PRESENCES` intent.
+    async fn presence_update(&self, _client: &Client, _token: &str, _event: &PresenceUpdate) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when a stage instance is created.
+    /// Needs the `GUILDS` intent.
+    async fn stage_instance_create(&self, _client: &Client, _token: &str, _event: &StageInstance) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when a stage instance is updated.
+    /// Needs the `GUILDS` intent.
+    async fn stage_instance_update(&self, _client: &Client, _token: &str, _event: &StageInstance) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when a stage instance is deleted.
+    /// Needs the `GUILDS` intent.
+    async fn stage_instance_delete(&self, _client: &Client, _token: &str, _event: &StageInstance) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when a premium subscription is created.
+    async fn subscription_create(&self, _client: &Client, _token: &str, _event: &Value) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when a premium subscription is updated.
+    async fn subscription_update(&self, _client: &Client, _token: &str, _event: &Value) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when a premium subscription is deleted.
+    async fn subscription_delete(&self, _client: &Client, _token: &str, _event: &Value) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when a user starts typing.
+    /// Needs the `GUILD_MESSAGE_TYPING` intent, or `DIRECT_MESSAGE_TYPING` for DMs.
+    async fn typing_start(&self, _client: &Client, _token: &str, _event: &TypingStart) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when the bot user is updated.
+    async fn user_update(&self, _client: &Client, _token: &str, _event: &User) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when someone sends an effect in a voice channel the bot is in.
+    /// Needs the `GUILD_VOICE_STATES` intent.
+    async fn voice_channel_effect_send(&self, _client: &Client, _token: &str, _event: &Value) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when a user joins, leaves or moves between voice channels.
+    /// Needs the `GUILD_VOICE_STATES` intent.
+    async fn voice_state_update(&self, _client: &Client, _token: &str, _event: &VoiceState) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when the voice server of a guild is assigned or changes.
+    async fn voice_server_update(&self, _client: &Client, _token: &str, _event: &VoiceServerUpdate) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when the webhooks of a channel change.
+    /// Needs the `GUILD_WEBHOOKS` intent.
+    async fn webhooks_update(&self, _client: &Client, _token: &str, _event: &WebhooksUpdate) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called when a user uses an interaction, such as a command or button.
+    async fn interaction_create(&self, _client: &Client, _token: &str, _event: &Interaction) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Called for dispatch events this crate does not model yet.
+    async fn unknown(&self, _client: &Client, _token: &str, _name: &str, _data: &Value) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+}
+
+/// Calls the `EventHandler` method matching an event.
+///
+/// # Arguments
+///
+/// * `handler` - The handler to call.
+/// * `client` - The HTTP client used to send requests.
+/// * `token` - The bot token for authentication.
+/// * `event` - The event to dispatch.
+///
+/// # Returns
+///
+/// The result of the handler method.
+pub async fn dispatch_event(handler: &dyn EventHandler, client: &Client, token: &str, event: &Event) -> Result<(), Box<dyn Error>> {
+    match event {
+        Event::Ready(event) => handler.ready(client, token, event).await,
+        Event::Resumed => handler.resumed(client, token).await,
+        Event::ApplicationCommandPermissionsUpdate(event) => handler.application_command_permissions_update(client, token, event).await,
+        Event::AutoModerationRuleCreate(event) => handler.auto_moderation_rule_create(client, token, event).await,
+        Event::AutoModerationRuleUpdate(event) => handler.auto_moderation_rule_update(client, token, event).await,
+        Event::AutoModerationRuleDelete(event) => handler.auto_moderation_rule_delete(client, token, event).await,
+        Event::AutoModerationActionExecution(event) => handler.auto_moderation_action_execution(client, token, event).await,
+        Event::GuildCreate(event) => handler.guild_create(client, token, event).await,
+        Event::GuildUpdate(event) => handler.guild_update(client, token, event).await,
+        Event::GuildDelete(event) => handler.guild_delete(client, token, event).await,
+        Event::ChannelCreate(event) => handler.channel_create(client, token, event).await,
+        Event::ChannelUpdate(event) => handler.channel_update(client, token, event).await,
+        Event::ChannelDelete(event) => handler.channel_delete(client, token, event).await,
+        Event::ThreadCreate(event) => handler.thread_create(client, token, event).await,
+        Event::ThreadUpdate(event) => handler.thread_update(client, token, event).await,
+        Event::ThreadDelete(event) => handler.thread_delete(client, token, event).await,
+        Event::ThreadListSync(event) => handler.thread_list_sync(client, token, event).await,
+        Event::ThreadMemberUpdate(event) => handler.thread_member_update(client, token, event).await,
+        Event::ThreadMembersUpdate(event) => handler.thread_members_update(client, token, event).await,
+        Event::ChannelPinsUpdate(event) => handler.channel_pins_update(client, token, event).await,
+        Event::EntitlementCreate(event) => handler.entitlement_create(client, token, event).await,
+        Event::EntitlementUpdate(event) => handler.entitlement_update(client, token, event).await,
+        Event::EntitlementDelete(event) => handler.entitlement_delete(client, token, event).await,
+        Event::GuildAuditLogEntryCreate(event) => handler.guild_audit_log_entry_create(client, token, event).await,
+        Event::GuildBanAdd(event) => handler.guild_ban_add(client, token, event).await,
+        Event::GuildBanRemove(event) => handler.guild_ban_remove(client, token, event).await,
+        Event::GuildEmojisUpdate(event) => handler.guild_emojis_update(client, token, event).await,
+        Event::GuildStickersUpdate(event) => handler.guild_stickers_update(client, token, event).await,
+        Event::GuildIntegrationsUpdate(event) => handler.guild_integrations_update(client, token, event).await,
+        Event::GuildMemberAdd(event) => handler.guild_member_add(client, token, event).await,
+        Event::GuildMemberUpdate(event) => handler.guild_member_update(client, token, event).await,
+        Event::GuildMemberRemove(event) => handler.guild_member_remove(client, token, event).await,
+        Event::GuildRoleCreate(event) => handler.guild_role_create(client, token, event).await,
+        Event::GuildRoleUpdate(event) => handler.guild_role_update(client, token, event).await,
+        Event::GuildRoleDelete(event) => handler.guild_role_delete(client, token, event).await,
+        Event::GuildMembersChunk(event) => handler.guild_members_chunk(client, token, event).await,
+        Event::GuildScheduledEventCreate(event) => handler.guild_scheduled_event_create(client, token, event).await,
+        Event::GuildScheduledEventUpdate(event) => handler.guild_scheduled_event_update(client, token, event).await,
+        Event::GuildScheduledEventDelete(event) => handler.guild_scheduled_event_delete(client, token, event).await,
+        Event::GuildScheduledEventUserAdd(event) => handler.guild_scheduled_event_user_add(client, token, event).await,
+        Event::GuildScheduledEventUserRemove(event) => handler.guild_scheduled_event_user_remove(client, token, event).await,
+        Event::GuildSoundboardSoundCreate(event) => handler.guild_soundboard_sound_create(client, token, event).await,
+        Event::GuildSoundboardSoundUpdate(event) => handler.guild_soundboard_sound_update(client, token, event).await,
+        Event::GuildSoundboardSoundDelete(event) => handler.guild_soundboard_sound_delete(client, token, event).await,
+        Event::GuildSoundboardSoundsUpdate(event) => handler.guild_soundboard_sounds_update(client, token, event).await,
+        Event::SoundboardSounds(event) => handler.soundboard_sounds(client, token, event).await,
+        Event::IntegrationCreate(event) => handler.integration_create(client, token, event).await,
+        Event::IntegrationUpdate(event) => handler.integration_update(client, token, event).await,
+        Event::IntegrationDelete(event) => handler.integration_delete(client, token, event).await,
+        Event::InviteCreate(event) => handler.invite_create(client, token, event).await,
+        Event::InviteDelete(event) => handler.invite_delete(client, token, event).await,
+        Event::MessageCreate(event) => handler.message_create(client, token, event).await,
+        Event::MessageUpdate(event) => handler.message_update(client, token, event).await,
+        Event::MessageDelete(event) => handler.message_delete(client, token, event).await,
+        Event::MessageDeleteBulk(event) => handler.message_delete_bulk(client, token, event).await,
+        Event::MessageReactionAdd(event) => handler.message_reaction_add(client, token, event).await,
+        Event::MessageReactionRemove(event) => handler.message_reaction_remove(client, token, event).await,
+        Event::MessageReactionRemoveAll(event) => handler.message_reaction_remove_all(client, token, event).await,
+        Event::MessageReactionRemoveEmoji(event) => handler.message_reaction_remove_emoji(client, token, event).await,
+        Event::MessagePollVoteAdd(event) => handler.message_poll_vote_add(client, token, event).await,
+        Event::MessagePollVoteRemove(event) => handler.message_poll_vote_remove(client, token, event).await,
+        Event::PresenceUpdate(event) => handler.presence_update(client, token, event).await,
+        Event::StageInstanceCreate(event) => handler.stage_instance_create(client, token, event).await,
+        Event::StageInstanceUpdate(event) => handler.stage_instance_update(client, token, event).await,
+        Event::StageInstanceDelete(event) => handler.stage_instance_delete(client, token, event).await,
+        Event::SubscriptionCreate(event) => handler.subscription_create(client, token, event).await,
+        Event::SubscriptionUpdate(event) => handler.subscription_update(client, token, event).await,
+        Event::SubscriptionDelete(event) => handler.subscription_delete(client, token, event).await,
+        Event::TypingStart(event) => handler.typing_start(client, token, event).await,
+        Event::UserUpdate(event) => handler.user_update(client, token, event).await,
+        Event::VoiceChannelEffectSend(event) => handler.voice_channel_effect_send(client, token, event).await,
+        Event::VoiceStateUpdate(event) => handler.voice_state_update(client, token, event).await,
+        Event::VoiceServerUpdate(event) => handler.voice_server_update(client, token, event).await,
+        Event::WebhooksUpdate(event) => handler.webhooks_update(client, token, event).await,
+        Event::InteractionCreate(event) => handler.interaction_create(client, token, event).await,
+        Event::Unknown { name, data } => handler.unknown(client, token, name, data).await,
+    }
+}
@@ -0,0 +1,325 @@
+use futures::FutureExt;
+use reqwest::Client;
+use std::collections::HashMap;
+use std::error::Error;
+use std::panic::AssertUnwindSafe;
+use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+
+use crate::event::Event;
+use crate::model::{Member, User};
+use crate::utils::guild;
+use crate::utils::invite::{Invite, VanityUrl};
+
+/// The use count of one invite when a snapshot was taken.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TrackedInvite {
+    pub code: String,
+    pub uses: u64,
+    /// Zero when unlimited.
+    pub max_uses: u64,
+    pub inviter: Option<User>,
+    pub channel_id: Option<String>,
+}
+
+impl TrackedInvite {
+    /// Copies the use count and inviter of an invite.
+    pub fn from_invite(invite: &Invite) -> Self {
+        Self {
+            code: invite.code.clone(),
+            uses: invite.uses.unwrap_or(0),
+            max_uses: invite.max_uses.unwrap_or(0),
+            inviter: invite.inviter.clone(),
+            channel_id: invite.channel.as_ref().map(|channel| channel.id.clone()).or_else(|| invite.channel_id.clone()),
+        }
+    }
+
+    /// Whether the next use exhausts the invite, which makes Discord delete it.
+    fn one_use_left(&self) -> bool {
+        self.max_uses > 0 && self.uses + 1 >= self.max_uses
+    }
+}
+
+/// The use counts of a guild's invites and vanity invite at one point in time.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InviteSnapshot {
+    pub invites: HashMap<String, TrackedInvite>,
+    /// The vanity invite code and its use count, if the guild has one.
+    pub vanity: Option<(String, u64)>,
+}
+
+impl InviteSnapshot {
+    /// Creates a snapshot from a guild's invites and vanity invite.
+    pub fn new(invites: &[Invite], vanity: Option<&VanityUrl>) -> Self {
+        Self {
+            invites: invites.iter().map(|invite| (invite.code.clone(), TrackedInvite::from_invite(invite))).collect(),
+            vanity: vanity.and_then(|vanity| Some((vanity.code.clone()?, vanity.uses))),
+        }
+    }
+
+    /// Fetches the current use counts of a guild's invites. Needs the
+    /// `MANAGE_GUILD` permission.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The HTTP client used to send the requests.
+    /// * `token` - The bot token for authentication.
+    /// * `guild_id` - The ID of the guild.
+    /// * `with_vanity` - Whether to fetch the vanity invite too.
+    pub async fn fetch(client: &Client, token: &str, guild_id: &str, with_vanity: bool) -> Result<Self, Box<dyn Error>> {
+        let invites = guild::get_guild_invites(client, token, guild_id).await?;
+        let vanity = match with_vanity {
+            true => Some(guild::get_guild_vanity_url(client, token, guild_id).await?),
+            false => None,
+        };
+        Ok(Self::new(&invites, vanity.as_ref()))
+    }
+}
+
+/// Which invite a member joined with.
+#[derive(Clone, Debug, PartialEq)]
+pub enum JoinSource {
+    /// Exactly one invite was used, as it was before the join.
+    Invite(TrackedInvite),
+    /// The guild's vanity invite was used.
+    Vanity(String),
+    /// Several invites were used since the last snapshot, such as when
+    /// members joined at nearly the same time.
+    Ambiguous { invites: Vec<TrackedInvite>, vanity: Option<String> },
+    /// No use was recorded, such as for bots added through OAuth2, members
+    /// added by an application, or when no earlier snapshot exists.
+    Unknown,
+}
+
+/// Works out which invite was used to join from the use counts before and
+/// after the join.
+///
+/// An invite counts as used when its count went up, or when it was created
+/// after `before` and has been used. An invite that had one use left and is
+/// missing from `after` counts as used as well, since Discord deletes
+/// invites when they reach their maximum uses.
+pub fn attribute_join(before: &InviteSnapshot, after: &InviteSnapshot) -> JoinSource {
+    let mut used: Vec<TrackedInvite> = after.invites.values()
+        .filter(|invite| invite.uses > before.invites.get(&invite.code).map_or(0, |previous| previous.uses))
+        .cloned()
+        .collect();
+    let vanity = match (&before.vanity, &after.vanity) {
+        (Some((_, previous)), Some((code, uses))) if uses > previous => Some(code.clone()),
+        _ => None,
+    };
+    if used.is_empty() && vanity.is_none() {
+        used = before.invites.values()
+            .filter(|invite| invite.one_use_left() && !after.invites.contains_key(&invite.code))
+            .cloned()
+            .collect();
+    }
+    used.sort_by(|first, second| first.code.cmp(&second.code));
+
+    match (used.len(), vanity) {
+        (0, None) => JoinSource::Unknown,
+        (0, Some(code)) => JoinSource::Vanity(code),
+        (1, None) => JoinSource::Invite(used.remove(0)),
+        (_, vanity) => JoinSource::Ambiguous { invites: used, vanity },
+    }
+}
+
+/// A member join together with the invite it came through.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AttributedJoin {
+    pub guild_id: String,
+    pub member: Member,
+    pub source: JoinSource,
+}
+
+/// Attributes member joins to the invites they came through.
+///
+/// The tracker keeps a snapshot of every guild's invite use counts, taken at
+/// `GUILD_CREATE` and kept current from `INVITE_CREATE` and `INVITE_DELETE`.
+/// The vanity invite is fetched again when `GUILD_UPDATE` changes its code.
+/// On `GUILD_MEMBER_ADD` it fetches the counts again and compares them with
+/// the snapshot, then sends an [`AttributedJoin`] to every subscriber. Feed it
+/// dispatch events with [`InviteTracker::update`], or hand it to
+/// `GatewayClient::invite_tracker`.
+///
+/// It needs the `GUILDS`, `GUILD_MEMBERS` and `GUILD_INVITES` intents and the
+/// `MANAGE_GUILD` permission in tracked guilds.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::sync::Arc;
+/// use Rust_Discord_API::invite_tracker::{InviteTracker, JoinSource};
+///
+/// # async fn example(tracker: Arc<InviteTracker>) {
+/// let mut joins = tracker.subscribe();
+/// while let Ok(join) = joins.recv().await {
+///     if let JoinSource::Invite(invite) = join.source {
+///         println!("joined with {} from {:?}", invite.code, invite.inviter.map(|user| user.username));
+///     }
+/// }
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct InviteTracker {
+    guilds: Mutex<HashMap<String, InviteSnapshot>>,
+    /// Held per guild while its snapshot is fetched, so a slow fetch cannot
+    /// overwrite a newer snapshot and joins are diffed one at a time.
+    fetches: Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>,
+    sender: broadcast::Sender<AttributedJoin>,
+}
+
+impl InviteTracker {
+    /// Creates a tracker that buffers up to 256 joins for slow subscribers.
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(256);
+        Self { guilds: Mutex::new(HashMap::new()), fetches: Mutex::new(HashMap::new()), sender }
+    }
+
+    /// Subscribes to joins attributed from now on.
+    pub fn subscribe(&self) -> broadcast::Receiver<AttributedJoin> {
+        self.sender.subscribe()
+    }
+
+    /// Returns the current snapshot of a guild, if it is tracked.
+    pub fn snapshot(&self, guild_id: &str) -> Option<InviteSnapshot> {
+        self.guilds.lock().unwrap().get(guild_id).cloned()
+    }
+
+    /// Returns the lock held while fetching the snapshot of a guild.
+    fn fetch_lock(&self, guild_id: &str) -> Arc<tokio::sync::Mutex<()>> {
+        self.fetches.lock().unwrap().entry(guild_id.to_string()).or_default().clone()
+    }
+
+    /// Starts tracking a guild, or refreshes its snapshot.
+    pub async fn track_guild(&self, client: &Client, token: &str, guild_id: &str) -> Result<(), Box<dyn Error>> {
+        let lock = self.fetch_lock(guild_id);
+        let _fetch = lock.lock().await;
+        let snapshot = InviteSnapshot::fetch(client, token, guild_id, true).await?;
+        self.guilds.lock().unwrap().insert(guild_id.to_string(), snapshot);
+        Ok(())
+    }
+
+    /// Applies a gateway event, attributing the join when a member joined.
+    /// The events of a guild must be applied in the order they were received.
+    ///
+    /// # Returns
+    ///
+    /// A result containing the attributed join for `GUILD_MEMBER_ADD`, which is also sent to subscribers.
+    pub async fn update(&self, client: &Client, token: &str, event: &Event) -> Result<Option<AttributedJoin>, Box<dyn Error>> {
+        match event {
+            Event::GuildCreate(guild) => self.track_guild(client, token, &guild.id).await?,
+            Event::GuildUpdate(guild) => {
+                let lock = self.fetch_lock(&guild.id);
+                let _fetch = lock.lock().await;
+                let tracked_code = self.snapshot(&guild.id).map(|snapshot| snapshot.vanity.map(|(code, _)| code));
+                if tracked_code.is_some_and(|code| code != guild.vanity_url_code) {
+                    let vanity = guild::get_guild_vanity_url(client, token, &guild.id).await?;
+                    if let Some(snapshot) = self.guilds.lock().unwrap().get_mut(&guild.id) {
+                        snapshot.vanity = vanity.code.map(|code| (code, vanity.uses));
+                    }
+                }
+            }
+            Event::GuildDelete(deleted) => {
+                self.guilds.lock().unwrap().remove(&deleted.id);
+                self.fetches.lock().unwrap().remove(&deleted.id);
+            }
+            Event::InviteCreate(invite) => {
+                let mut guilds = self.guilds.lock().unwrap();
+                if let Some(snapshot) = invite.guild_id().and_then(|guild_id| guilds.get_mut(guild_id)) {
+                    // A join may already have fetched the invite with a newer count.
+                    snapshot.invites.entry(invite.code.clone()).or_insert_with(|| TrackedInvite::from_invite(invite));
+                }
+            }
+            Event::InviteDelete(deleted) => {
+                let mut guilds = self.guilds.lock().unwrap();
+                if let Some(snapshot) = deleted.guild_id.as_deref().and_then(|guild_id| guilds.get_mut(guild_id)) {
+                    // Discord deletes exhausted invites before sending the join,
+                    // so keep those to recognise the join by.
+                    if snapshot.invites.get(&deleted.code).is_some_and(|invite| !invite.one_use_left()) {
+                        snapshot.invites.remove(&deleted.code);
+                    }
+                }
+            }
+            Event::GuildMemberAdd(added) => {
+                let lock = self.fetch_lock(&added.guild_id);
+                let _fetch = lock.lock().await;
+                let with_vanity = self.snapshot(&added.guild_id).is_none_or(|snapshot| snapshot.vanity.is_some());
+                let after = InviteSnapshot::fetch(client, token, &added.guild_id, with_vanity).await?;
+                let before = self.guilds.lock().unwrap().insert(added.guild_id.clone(), after.clone());
+                let source = match before {
+                    Some(before) => attribute_join(&before, &after),
+                    None => JoinSource::Unknown,
+                };
+                let join = AttributedJoin { guild_id: added.guild_id.clone(), member: added.member.clone(), source };
+                // Sending only fails when nobody is subscribed, which is fine.
+                let _ = self.sender.send(join.clone());
+                return Ok(Some(join));
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+}
+
+impl Default for InviteTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Applies gateway events to a tracker in the order they arrive. Each guild
+/// has its own queue, so a slow fetch in one guild does not hold up others.
+pub(crate) struct TrackerQueue {
+    tracker: Arc<InviteTracker>,
+    client: Client,
+    token: String,
+    guilds: Mutex<HashMap<String, UnboundedSender<Arc<Event>>>>,
+}
+
+impl TrackerQueue {
+    pub(crate) fn new(tracker: Arc<InviteTracker>, client: Client, token: &str) -> Self {
+        Self { tracker, client, token: token.to_string(), guilds: Mutex::new(HashMap::new()) }
+    }
+
+    /// Queues an event behind the earlier events of its guild. Events the
+    /// tracker does not use are dropped.
+    pub(crate) fn push(&self, event: Arc<Event>) {
+        let Some(guild_id) = tracked_guild_id(&event) else { return };
+        let guild_id = guild_id.to_string();
+        let mut guilds = self.guilds.lock().unwrap();
+        let queue = guilds.entry(guild_id.clone()).or_insert_with(|| self.spawn_worker(guild_id.clone()));
+        let _ = queue.send(event.clone());
+        if let Event::GuildDelete(_) = &*event {
+            // The worker stops once it has applied the queued events.
+            guilds.remove(&guild_id);
+        }
+    }
+
+    fn spawn_worker(&self, guild_id: String) -> UnboundedSender<Arc<Event>> {
+        let (sender, mut events) = unbounded_channel::<Arc<Event>>();
+        let (tracker, client, token) = (self.tracker.clone(), self.client.clone(), self.token.clone());
+        tokio::spawn(async move {
+            while let Some(event) = events.recv().await {
+                match AssertUnwindSafe(tracker.update(&client, &token, &event)).catch_unwind().await {
+                    Ok(Ok(_)) => {}
+                    Ok(Err(error)) => eprintln!("Error in invite tracking for guild {}: {}", guild_id, error),
+                    Err(_) => eprintln!("Panic in invite tracking for guild {}", guild_id),
+                }
+            }
+        });
+        sender
+    }
+}
+
+/// Returns the guild of an event the tracker uses.
+fn tracked_guild_id(event: &Event) -> Option<&str> {
+    match event {
+        Event::GuildCreate(guild) | Event::GuildUpdate(guild) => Some(&guild.id),
+        Event::GuildDelete(deleted) => Some(&deleted.id),
+        Event::InviteCreate(invite) => invite.guild_id(),
+        Event::InviteDelete(deleted) => deleted.guild_id.as_deref(),
+        Event::GuildMemberAdd(added) => Some(&added.guild_id),
+        _ => None,
+    }
+}
@@ -1,6 +1,19 @@
+#![allow(non_snake_case)]
+
 pub mod utils;
 pub mod router;
+pub mod model;
+pub mod event;
+pub mod collector;
+pub mod paginator;
+pub mod invite_tracker;
+pub mod cache;
+pub mod handler;
+pub mod gateway;
+pub mod voice;
 
 pub use utils::*;
 
-pub use router::{Command, CommandRouter};
+pub use router::{Command, CommandRouter, ComponentHandler, ModalHandler};
+pub use handler::EventHandler;
+pub use gateway::{GatewayClient, GatewayError, Intents};
@@ -0,0 +1,215 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::time::SystemTime;
+
+use crate::utils::timestamp::parse_timestamp;
+
+/// A Discord user.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct User {
+    pub id: String,
+    pub username: String,
+    #[serde(default)]
+    pub discriminator: Option<String>,
+    #[serde(default)]
+    pub global_name: Option<String>,
+    #[serde(default)]
+    pub avatar: Option<String>,
+    #[serde(default)]
+    pub bot: bool,
+}
+
+/// A message sent in a channel.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Message {
+    pub id: String,
+    pub channel_id: String,
+    #[serde(default)]
+    pub guild_id: Option<String>,
+    pub author: User,
+    #[serde(default)]
+    pub content: String,
+    #[serde(default)]
+    pub timestamp: Option<String>,
+    #[serde(default)]
+    pub edited_timestamp: Option<String>,
+    #[serde(default)]
+    pub member: Option<Value>,
+    #[serde(default)]
+    pub embeds: Vec<Value>,
+    #[serde(default)]
+    pub components: Vec<Value>,
+    #[serde(default)]
+    pub attachments: Vec<Value>,
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+/// A member of a guild.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Member {
+    /// The user this member represents. Missing in some message payloads.
+    #[serde(default)]
+    pub user: Option<User>,
+    #[serde(default)]
+    pub nick: Option<String>,
+    #[serde(default)]
+    pub avatar: Option<String>,
+    #[serde(default)]
+    pub roles: Vec<String>,
+    #[serde(default)]
+    pub joined_at: Option<String>,
+    #[serde(default)]
+    pub premium_since: Option<String>,
+    #[serde(default)]
+    pub deaf: bool,
+    #[serde(default)]
+    pub mute: bool,
+    #[serde(default)]
+    pub pending: bool,
+    #[serde(default)]
+    pub flags: u64,
+    #[serde(default)]
+    pub communication_disabled_until: Option<String>,
+}
+
+impl Member {
+    /// Returns when the member's timeout ends. Discord keeps the timestamp
+    /// after a timeout expires, so this may be in the past.
+    ///
+    /// # Returns
+    ///
+    /// The end of the timeout, or `None` if there is none or it cannot be parsed.
+    pub fn timed_out_until(&self) -> Option<SystemTime> {
+        self.communication_disabled_until.as_deref().and_then(parse_timestamp)
+    }
+
+    /// Returns whether the member is currently timed out.
+    pub fn is_timed_out(&self) -> bool {
+        self.is_timed_out_at(SystemTime::now())
+    }
+
+    /// Returns whether the member is timed out at the given time.
+    pub fn is_timed_out_at(&self, now: SystemTime) -> bool {
+        self.timed_out_until().is_some_and(|until| until > now)
+    }
+}
+
+/// A role in a guild.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Role {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub color: u32,
+    #[serde(default)]
+    pub hoist: bool,
+    #[serde(default)]
+    pub position: i64,
+    /// The permission bit set, as a decimal string.
+    #[serde(default)]
+    pub permissions: String,
+    #[serde(default)]
+    pub managed: bool,
+    #[serde(default)]
+    pub mentionable: bool,
+}
+
+/// A permission overwrite on a channel.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PermissionOverwrite {
+    pub id: String,
+    /// 0 for a role, 1 for a member.
+    #[serde(rename = "type")]
+    pub kind: u8,
+    pub allow: String,
+    pub deny: String,
+}
+
+/// A guild channel, DM or thread.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Channel {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub kind: u8,
+    #[serde(default)]
+    pub guild_id: Option<String>,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub position: Option<i64>,
+    #[serde(default)]
+    pub parent_id: Option<String>,
+    #[serde(default)]
+    pub topic: Option<String>,
+    #[serde(default)]
+    pub nsfw: bool,
+    #[serde(default)]
+    pub last_message_id: Option<String>,
+    #[serde(default)]
+    pub permission_overwrites: Vec<PermissionOverwrite>,
+    #[serde(default)]
+    pub rate_limit_per_user: Option<u32>,
+    #[serde(default)]
+    pub owner_id: Option<String>,
+    #[serde(default)]
+    pub thread_metadata: Option<Value>,
+}
+
+/// A guild. Guilds received over the gateway in GUILD_CREATE also carry their
+/// channels, members and roles.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Guild {
+    pub id: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub icon: Option<String>,
+    #[serde(default)]
+    pub owner_id: Option<String>,
+    #[serde(default)]
+    pub roles: Vec<Role>,
+    #[serde(default)]
+    pub channels: Vec<Channel>,
+    #[serde(default)]
+    pub threads: Vec<Channel>,
+    #[serde(default)]
+    pub members: Vec<Member>,
+    #[serde(default)]
+    pub member_count: Option<u64>,
+    #[serde(default)]
+    pub vanity_url_code: Option<String>,
+    #[serde(default)]
+    pub unavailable: bool,
+}
+
+/// A user's voice connection state.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct VoiceState {
+    #[serde(default)]
+    pub guild_id: Option<String>,
+    /// The voice channel, or `None` after the user left.
+    #[serde(default)]
+    pub channel_id: Option<String>,
+    pub user_id: String,
+    #[serde(default)]
+    pub member: Option<Member>,
+    pub session_id: String,
+    #[serde(default)]
+    pub deaf: bool,
+    #[serde(default)]
+    pub mute: bool,
+    #[serde(default)]
+    pub self_deaf: bool,
+    #[serde(default)]
+    pub self_mute: bool,
+    #[serde(default)]
+    pub self_stream: bool,
+    #[serde(default)]
+    pub self_video: bool,
+    /// Whether the user may not speak in a stage channel.
+    #[serde(default)]
+    pub suppress: bool,
+    #[serde(default)]
+    pub request_to_speak_timestamp: Option<String>,
+}
@@ -0,0 +1,280 @@
+use futures::StreamExt;
+use reqwest::Client;
+use serde_json::{json, Value};
+use std::error::Error;
+use std::time::Duration;
+use tokio::time::{sleep_until, Instant};
+
+use crate::collector::Collector;
+use crate::event::EventBus;
+use crate::utils::channel;
+use crate::utils::component::{ActionRow, Button};
+use crate::utils::emoji::ReactionType;
+use crate::utils::interaction::{self, InteractionResponse};
+use crate::utils::message::{self, MessageBuilder};
+
+/// How the user navigates between pages.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PaginatorControls {
+    /// A row of first/previous/next/last buttons.
+    Buttons,
+    /// First/previous/next/last reactions on the message.
+    Reactions,
+}
+
+/// A navigation step between pages.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PageAction {
+    First,
+    Previous,
+    Next,
+    Last,
+}
+
+impl PageAction {
+    const ALL: [PageAction; 4] = [PageAction::First, PageAction::Previous, PageAction::Next, PageAction::Last];
+
+    /// Returns the page index reached by taking this step from `current`.
+    pub fn apply(self, current: usize, page_count: usize) -> usize {
+        let last = page_count.saturating_sub(1);
+        match self {
+            PageAction::First => 0,
+            PageAction::Previous => current.saturating_sub(1),
+            PageAction::Next => (current + 1).min(last),
+            PageAction::Last => last,
+        }
+    }
+
+    /// Returns the `custom_id` of the button for this step.
+    pub fn custom_id(self) -> &'static str {
+        match self {
+            PageAction::First => "paginator:first",
+            PageAction::Previous => "paginator:previous",
+            PageAction::Next => "paginator:next",
+            PageAction::Last => "paginator:last",
+        }
+    }
+
+    /// Returns the emoji of the button and reaction for this step.
+    pub fn emoji(self) -> &'static str {
+        match self {
+            PageAction::First => "⏮️",
+            PageAction::Previous => "◀️",
+            PageAction::Next => "▶️",
+            PageAction::Last => "⏭️",
+        }
+    }
+
+    /// Returns the step for a button `custom_id`, if it is one of the paginator's.
+    pub fn from_custom_id(custom_id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|action| action.custom_id() == custom_id)
+    }
+
+    /// Returns the step for a reaction emoji, if it is one of the paginator's.
+    pub fn from_emoji(emoji: &str) -> Option<Self> {
+        // Discord may or may not keep the emoji variation selector, so compare without it.
+        let emoji = emoji.replace('\u{FE0F}', "");
+        Self::ALL.into_iter().find(|action| action.emoji().replace('\u{FE0F}', "") == emoji)
+    }
+}
+
+/// Splits a list of items into embed pages and lets the invoking user page
+/// through them.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use serde_json::json;
+/// use Rust_Discord_API::paginator::{Paginator, PaginatorControls};
+/// # use reqwest::Client;
+/// # use Rust_Discord_API::event::EventBus;
+///
+/// # async fn example(client: &Client, token: &str, bus: &EventBus, channel_id: &str, author_id: &str, bans: Vec<String>) -> Result<(), Box<dyn std::error::Error>> {
+/// Paginator::from_items(&bans, 10, |chunk, _| json!({
+///         "title": "Bans",
+///         "description": chunk.join("\n"),
+///     }))
+///     .controls(PaginatorControls::Buttons)
+///     .timeout(Duration::from_secs(120))
+///     .send(client, token, bus, channel_id, author_id)
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct Paginator {
+    pages: Vec<Value>,
+    controls: PaginatorControls,
+    timeout: Duration,
+}
+
+impl Paginator {
+    /// Creates a paginator over prepared embeds, one per page.
+    pub fn new(pages: Vec<Value>) -> Self {
+        Self { pages, controls: PaginatorControls::Buttons, timeout: Duration::from_secs(60) }
+    }
+
+    /// Splits `items` into pages of `per_page` items and renders each page to
+    /// an embed. `render` receives the items on the page and the page index.
+    pub fn from_items<T>(items: &[T], per_page: usize, render: impl Fn(&[T], usize) -> Value) -> Self {
+        let pages = items.chunks(per_page.max(1))
+            .enumerate()
+            .map(|(index, chunk)| render(chunk, index))
+            .collect();
+
+        Self::new(pages)
+    }
+
+    /// Sets how the user navigates between pages. Buttons are used by default.
+    pub fn controls(mut self, controls: PaginatorControls) -> Self {
+        self.controls = controls;
+        self
+    }
+
+    /// Sets how long the paginator waits for input before removing its controls.
+    /// The timer restarts after every page change. Defaults to 60 seconds.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Returns the number of pages.
+    pub fn page_count(&self) -> usize {
+        self.pages.len()
+    }
+
+    /// Returns the embed for a page, with a "Page x/y" footer unless the page sets its own.
+    pub fn page(&self, index: usize) -> Value {
+        let mut page = self.pages.get(index).cloned().unwrap_or_else(|| json!({}));
+        if page.get("footer").is_none() && self.pages.len() > 1 {
+            page["footer"] = json!({ "text": format!("Page {}/{}", index + 1, self.pages.len()) });
+        }
+        page
+    }
+
+    /// Sends the first page and handles navigation until the timeout elapses.
+    ///
+    /// Only `author_id` can change pages. When the paginator times out, its
+    /// buttons or reactions are removed from the message.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The HTTP client used to send requests.
+    /// * `token` - The bot token for authentication.
+    /// * `bus` - The event bus the gateway publishes events to.
+    /// * `channel_id` - The ID of the channel to send the pages to.
+    /// * `author_id` - The ID of the user allowed to change pages.
+    ///
+    /// # Returns
+    ///
+    /// A result indicating success or failure.
+    pub async fn send(self, client: &Client, token: &str, bus: &EventBus, channel_id: &str, author_id: &str) -> Result<(), Box<dyn Error>> {
+        if self.pages.is_empty() {
+            return Err("paginator has no pages".into());
+        }
+        if self.pages.len() == 1 {
+            message::send_message_with(client, token, channel_id, &MessageBuilder::new().embed(self.page(0))).await?;
+            return Ok(());
+        }
+
+        match self.controls {
+            PaginatorControls::Buttons => self.run_buttons(client, token, bus, channel_id, author_id).await,
+            PaginatorControls::Reactions => self.run_reactions(client, token, bus, channel_id, author_id).await,
+        }
+    }
+
+    fn button_row(&self, index: usize) -> ActionRow {
+        let last = self.pages.len() - 1;
+        ActionRow::buttons(PageAction::ALL.iter().map(|action| {
+            let disabled = match action {
+                PageAction::First | PageAction::Previous => index == 0,
+                PageAction::Next | PageAction::Last => index == last,
+            };
+            Button::secondary(action.custom_id(), action.emoji()).disabled(disabled)
+        }).collect())
+    }
+
+    async fn run_buttons(&self, client: &Client, token: &str, bus: &EventBus, channel_id: &str, author_id: &str) -> Result<(), Box<dyn Error>> {
+        // Subscribe before sending so no click can slip through.
+        let collector = Collector::components(bus);
+        let mut index = 0;
+        let message = message::send_message_with(client, token, channel_id, &MessageBuilder::new()
+            .embed(self.page(index))
+            .action_row(self.button_row(index))).await?;
+        let message_id = message["id"].as_str().ok_or("sent message has no ID")?.to_string();
+        let mut clicks = collector.message(&message_id).stream();
+
+        // Only the author's navigation keeps the paginator alive, so other
+        // users can't hold it open by clicking.
+        let mut deadline = Instant::now() + self.timeout;
+        loop {
+            let click = tokio::select! {
+                click = clicks.next() => click,
+                _ = sleep_until(deadline) => None,
+            };
+            let Some(click) = click else { break };
+            let Some(action) = click.component_data().and_then(|data| PageAction::from_custom_id(&data.custom_id)) else {
+                continue;
+            };
+
+            let response = if click.user_id() == Some(author_id) {
+                index = action.apply(index, self.pages.len());
+                deadline = Instant::now() + self.timeout;
+                InteractionResponse::update_message(&MessageBuilder::new()
+                    .embed(self.page(index))
+                    .components(vec![self.button_row(index)]))
+            } else {
+                InteractionResponse::message(&MessageBuilder::new()
+                    .content("Only the person who ran the command can change pages.")
+                    .flags(1 << 6))
+            };
+            // A click can't be answered once its token expires; keep handling the others.
+            if let Err(error) = interaction::create_interaction_response(client, &click.id, &click.token, &response).await {
+                eprintln!("Failed to respond to paginator click: {}", error);
+            }
+        }
+
+        message::edit_message_with(client, token, channel_id, &message_id, &MessageBuilder::new()
+            .embed(self.page(index))
+            .components(Vec::new())).await?;
+
+        Ok(())
+    }
+
+    async fn run_reactions(&self, client: &Client, token: &str, bus: &EventBus, channel_id: &str, author_id: &str) -> Result<(), Box<dyn Error>> {
+        let collector = Collector::reactions(bus).author(author_id);
+        let mut index = 0;
+        let message = message::send_message_with(client, token, channel_id, &MessageBuilder::new().embed(self.page(index))).await?;
+        let message_id = message["id"].as_str().ok_or("sent message has no ID")?.to_string();
+        let mut reactions = collector.message(&message_id).stream();
+
+        for action in PageAction::ALL {
+            channel::create_reaction(client, token, channel_id, &message_id, &ReactionType::unicode(action.emoji())).await?;
+        }
+
+        while let Ok(Some(reaction)) = tokio::time::timeout(self.timeout, reactions.next()).await {
+            let Some(action) = reaction.emoji.name.as_deref().and_then(PageAction::from_emoji) else {
+                continue;
+            };
+
+            // Remove the user's reaction so the same control can be used again.
+            // This needs Manage Messages, so a failure only means the user has to un-react.
+            let _ = channel::delete_user_reaction(client, token, channel_id, &message_id, &ReactionType::unicode(action.emoji()), author_id).await;
+
+            let next = action.apply(index, self.pages.len());
+            if next != index {
+                index = next;
+                message::edit_message_with(client, token, channel_id, &message_id, &MessageBuilder::new().embed(self.page(index))).await?;
+            }
+        }
+
+        if channel::delete_all_reactions(client, token, channel_id, &message_id).await.is_err() {
+            // Without Manage Messages (for example in DMs) only our own reactions can be removed.
+            for action in PageAction::ALL {
+                channel::delete_own_reaction(client, token, channel_id, &message_id, &ReactionType::unicode(action.emoji())).await?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -1,10 +1,11 @@
 use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::RwLock;
 use async_trait::async_trait;
 use reqwest::Client;
 use std::error::Error;
 
+use crate::utils::interaction::{Interaction, ModalSubmitData};
+
 #[async_trait]
 /// The `Command` trait defines a common interface for all commands.
 /// Each command must implement the `execute` method which handles the command's logic.
@@ -24,9 +25,54 @@ pub trait Command: Send + Sync {
     async fn execute(&self, client: &Client, token: &str, channel_id: &str, args: &str) -> Result<(), Box<dyn Error>>;
 }
 
+#[async_trait]
+/// The `ComponentHandler` trait handles clicks on buttons and selections in select menus.
+pub trait ComponentHandler: Send + Sync {
+    /// Handle a message component interaction.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The HTTP client used to send requests.
+    /// * `token` - The bot token for authentication.
+    /// * `interaction` - The component interaction. Use `interaction.component_data()` for the `custom_id` and selected values.
+    ///
+    /// # Returns
+    ///
+    /// A result indicating success or failure.
+    async fn handle(&self, client: &Client, token: &str, interaction: &Interaction) -> Result<(), Box<dyn Error>>;
+}
+
+#[async_trait]
+/// The `ModalHandler` trait handles submitted modals.
+pub trait ModalHandler: Send + Sync {
+    /// Handle a modal submit interaction.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The HTTP client used to send requests.
+    /// * `token` - The bot token for authentication.
+    /// * `interaction` - The modal submit interaction.
+    /// * `data` - The submitted values. Use `data.parse()` to read them into a typed struct.
+    ///
+    /// # Returns
+    ///
+    /// A result indicating success or failure.
+    async fn handle(&self, client: &Client, token: &str, interaction: &Interaction, data: &ModalSubmitData) -> Result<(), Box<dyn Error>>;
+}
+
 /// The `CommandRouter` struct is responsible for managing and dispatching commands.
 pub struct CommandRouter {
     commands: HashMap<String, Arc<dyn Command>>,
+    components: HashMap<String, Arc<dyn ComponentHandler>>,
+    component_prefixes: Vec<(String, Arc<dyn ComponentHandler>)>,
+    modals: HashMap<String, Arc<dyn ModalHandler>>,
+    modal_prefixes: Vec<(String, Arc<dyn ModalHandler>)>,
+}
+
+impl Default for CommandRouter {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl CommandRouter {
@@ -35,13 +81,17 @@ impl CommandRouter {
     /// # Examples
     ///
     /// ```
-    /// use exampleapp::CommandRouter;
+    /// use Rust_Discord_API::CommandRouter;
     ///
     /// let command_router = CommandRouter::new();
     /// ```
     pub fn new() -> Self {
         Self {
             commands: HashMap::new(),
+            components: HashMap::new(),
+            component_prefixes: Vec::new(),
+            modals: HashMap::new(),
+            modal_prefixes: Vec::new(),
         }
     }
 
@@ -55,7 +105,7 @@ impl CommandRouter {
     /// # Examples
     ///
     /// ```
-    /// use exampleapp::{CommandRouter, Command};
+    /// use Rust_Discord_API::{CommandRouter, Command};
     /// use std::sync::Arc;
     /// use async_trait::async_trait;
     /// use reqwest::Client;
@@ -89,8 +139,8 @@ impl CommandRouter {
     ///
     /// # Examples
     ///
-    /// ```
-    /// use exampleapp::{CommandRouter, Command};
+    /// ```no_run
+    /// use Rust_Discord_API::{CommandRouter, Command};
     /// use std::sync::Arc;
     /// use tokio::sync::RwLock;
     /// use reqwest::Client;
@@ -144,4 +194,121 @@ impl CommandRouter {
 
         Ok(())
     }
+
+    /// Check whether any prefix commands are registered.
+    pub fn has_commands(&self) -> bool {
+        !self.commands.is_empty()
+    }
+
+    /// Check whether a message starts with a registered command name.
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The message content.
+    ///
+    /// # Returns
+    ///
+    /// `true` if `dispatch` would run a command for this message.
+    pub fn is_command(&self, content: &str) -> bool {
+        let command_name = content.split(' ').next().unwrap_or_default();
+        self.commands.contains_key(command_name)
+    }
+
+    /// Register a handler for the component with the given `custom_id`.
+    ///
+    /// # Arguments
+    ///
+    /// * `custom_id` - The exact `custom_id` of the button or select menu.
+    /// * `handler` - The handler to register.
+    pub fn register_component(&mut self, custom_id: &str, handler: Arc<dyn ComponentHandler>) {
+        self.components.insert(custom_id.to_string(), handler);
+    }
+
+    /// Register a handler for every component whose `custom_id` starts with `prefix`.
+    ///
+    /// This is useful when the `custom_id` carries state, for example `"ban:confirm:1234"`.
+    /// Exact matches registered with `register_component` take precedence, and the
+    /// longest matching prefix wins.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - The `custom_id` prefix to match.
+    /// * `handler` - The handler to register.
+    pub fn register_component_prefix(&mut self, prefix: &str, handler: Arc<dyn ComponentHandler>) {
+        self.component_prefixes.retain(|(existing, _)| existing != prefix);
+        self.component_prefixes.push((prefix.to_string(), handler));
+    }
+
+    /// Dispatch a message component interaction to the handler registered for its `custom_id`.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The HTTP client used to send requests.
+    /// * `token` - The bot token for authentication.
+    /// * `interaction` - The component interaction.
+    pub async fn dispatch_component(&self, client: &Client, token: &str, interaction: &Interaction) -> Result<(), Box<dyn Error>> {
+        let Some(data) = interaction.component_data() else {
+            return Err("interaction is not a message component interaction".into());
+        };
+
+        if let Some(handler) = find_handler(&self.components, &self.component_prefixes, &data.custom_id) {
+            handler.handle(client, token, interaction).await?;
+        } else {
+            println!("Component handler not found: {}", data.custom_id);
+        }
+
+        Ok(())
+    }
+
+    /// Register a handler for the modal with the given `custom_id`.
+    ///
+    /// # Arguments
+    ///
+    /// * `custom_id` - The exact `custom_id` of the modal.
+    /// * `handler` - The handler to register.
+    pub fn register_modal(&mut self, custom_id: &str, handler: Arc<dyn ModalHandler>) {
+        self.modals.insert(custom_id.to_string(), handler);
+    }
+
+    /// Register a handler for every modal whose `custom_id` starts with `prefix`.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - The `custom_id` prefix to match.
+    /// * `handler` - The handler to register.
+    pub fn register_modal_prefix(&mut self, prefix: &str, handler: Arc<dyn ModalHandler>) {
+        self.modal_prefixes.retain(|(existing, _)| existing != prefix);
+        self.modal_prefixes.push((prefix.to_string(), handler));
+    }
+
+    /// Dispatch a modal submit interaction to the handler registered for its `custom_id`.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The HTTP client used to send requests.
+    /// * `token` - The bot token for authentication.
+    /// * `interaction` - The modal submit interaction.
+    pub async fn dispatch_modal(&self, client: &Client, token: &str, interaction: &Interaction) -> Result<(), Box<dyn Error>> {
+        let Some(data) = interaction.modal_data() else {
+            return Err("interaction is not a modal submit interaction".into());
+        };
+
+        if let Some(handler) = find_handler(&self.modals, &self.modal_prefixes, &data.custom_id) {
+            handler.handle(client, token, interaction, &data).await?;
+        } else {
+            println!("Modal handler not found: {}", data.custom_id);
+        }
+
+        Ok(())
+    }
+}
+
+/// Looks up a handler by exact `custom_id`, falling back to the longest matching prefix.
+fn find_handler<'a, H: ?Sized>(exact: &'a HashMap<String, Arc<H>>, prefixes: &'a [(String, Arc<H>)], custom_id: &str) -> Option<&'a Arc<H>> {
+    exact.get(custom_id).or_else(|| {
+        prefixes.iter()
+            .filter(|(prefix, _)| custom_id.starts_with(prefix.as_str()))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(_, handler)| handler)
+    })
 }
@@ -0,0 +1,471 @@
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::error::Error;
+
+use super::audit_log::AuditLogReason;
+
+/// When a rule is checked.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(from = "u8", into = "u8")]
+pub enum EventType {
+    /// When a member sends or edits a message.
+    MessageSend,
+    /// When a member edits their profile.
+    MemberUpdate,
+    /// An event type this crate does not model yet.
+    Unknown(u8),
+}
+
+impl From<u8> for EventType {
+    fn from(value: u8) -> Self {
+        match value {
+            1 => EventType::MessageSend,
+            2 => EventType::MemberUpdate,
+            other => EventType::Unknown(other),
+        }
+    }
+}
+
+impl From<EventType> for u8 {
+    fn from(event_type: EventType) -> Self {
+        match event_type {
+            EventType::MessageSend => 1,
+            EventType::MemberUpdate => 2,
+            EventType::Unknown(value) => value,
+        }
+    }
+}
+
+/// What content a rule checks for.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(from = "u8", into = "u8")]
+pub enum TriggerType {
+    /// Content matching a list of keywords or regex patterns. At most 6 per guild.
+    Keyword,
+    /// Content Discord considers spam. At most 1 per guild.
+    Spam,
+    /// Content matching Discord's predefined word lists. At most 1 per guild.
+    KeywordPreset,
+    /// Messages with too many unique role and user mentions. At most 1 per guild.
+    MentionSpam,
+    /// Member profiles matching keywords or regex patterns. At most 1 per guild.
+    MemberProfile,
+    /// A trigger type this crate does not model yet.
+    Unknown(u8),
+}
+
+impl From<u8> for TriggerType {
+    fn from(value: u8) -> Self {
+        match value {
+            1 => TriggerType::Keyword,
+            3 => TriggerType::Spam,
+            4 => TriggerType::KeywordPreset,
+            5 => TriggerType::MentionSpam,
+            6 => TriggerType::MemberProfile,
+            other => TriggerType::Unknown(other),
+        }
+    }
+}
+
+impl From<TriggerType> for u8 {
+    fn from(trigger_type: TriggerType) -> Self {
+        match trigger_type {
+            TriggerType::Keyword => 1,
+            TriggerType::Spam => 3,
+            TriggerType::KeywordPreset => 4,
+            TriggerType::MentionSpam => 5,
+            TriggerType::MemberProfile => 6,
+            TriggerType::Unknown(value) => value,
+        }
+    }
+}
+
+/// A predefined word list for [`TriggerType::KeywordPreset`].
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(from = "u8", into = "u8")]
+pub enum KeywordPresetType {
+    /// Swearing and cursing.
+    Profanity,
+    /// Sexually explicit words.
+    SexualContent,
+    /// Personal insults and hate speech.
+    Slurs,
+    /// A word list this crate does not model yet.
+    Unknown(u8),
+}
+
+impl From<u8> for KeywordPresetType {
+    fn from(value: u8) -> Self {
+        match value {
+            1 => KeywordPresetType::Profanity,
+            2 => KeywordPresetType::SexualContent,
+            3 => KeywordPresetType::Slurs,
+            other => KeywordPresetType::Unknown(other),
+        }
+    }
+}
+
+impl From<KeywordPresetType> for u8 {
+    fn from(preset: KeywordPresetType) -> Self {
+        match preset {
+            KeywordPresetType::Profanity => 1,
+            KeywordPresetType::SexualContent => 2,
+            KeywordPresetType::Slurs => 3,
+            KeywordPresetType::Unknown(value) => value,
+        }
+    }
+}
+
+/// The settings of a rule's trigger. Which fields apply depends on the trigger type.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct TriggerMetadata {
+    /// Substrings to match, with `*` wildcards. For keyword and member profile rules; at most 1000 of 60 characters.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub keyword_filter: Vec<String>,
+    /// Rust-flavored regex patterns. For keyword and member profile rules; at most 10 of 260 characters.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub regex_patterns: Vec<String>,
+    /// The word lists to match. For keyword preset rules.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub presets: Vec<KeywordPresetType>,
+    /// Substrings that never match. For keyword, keyword preset and member profile rules.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allow_list: Vec<String>,
+    /// The number of unique mentions allowed per message, at most 50. For mention spam rules.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mention_total_limit: Option<u8>,
+    /// Whether to detect mention raids. For mention spam rules.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mention_raid_protection_enabled: Option<bool>,
+}
+
+/// What happens when a rule is triggered.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(from = "RawAction", into = "RawAction")]
+pub enum AutoModerationAction {
+    /// Blocks the message, showing the author the custom message (at most 150 characters) if any.
+    BlockMessage { custom_message: Option<String> },
+    /// Posts an alert about the content in a channel.
+    SendAlertMessage { channel_id: String },
+    /// Times the member out, for at most 4 weeks (2419200 seconds). For keyword,
+    /// mention spam and member profile rules.
+    Timeout { duration_seconds: u32 },
+    /// Stops the member from interacting in the guild until they change their
+    /// profile. For member profile rules.
+    BlockMemberInteraction,
+    /// An action type this crate does not model yet.
+    Unknown { kind: u8, metadata: Value },
+}
+
+/// An action as sent by Discord: a type and its metadata.
+#[derive(Serialize, Deserialize)]
+struct RawAction {
+    #[serde(rename = "type")]
+    kind: u8,
+    #[serde(default, skip_serializing_if = "Value::is_null")]
+    metadata: Value,
+}
+
+impl From<RawAction> for AutoModerationAction {
+    fn from(action: RawAction) -> Self {
+        let metadata = &action.metadata;
+        match action.kind {
+            1 => AutoModerationAction::BlockMessage { custom_message: metadata["custom_message"].as_str().map(str::to_string) },
+            2 => AutoModerationAction::SendAlertMessage { channel_id: metadata["channel_id"].as_str().unwrap_or_default().to_string() },
+            3 => AutoModerationAction::Timeout { duration_seconds: metadata["duration_seconds"].as_u64().unwrap_or_default() as u32 },
+            4 => AutoModerationAction::BlockMemberInteraction,
+            kind => AutoModerationAction::Unknown { kind, metadata: action.metadata },
+        }
+    }
+}
+
+impl From<AutoModerationAction> for RawAction {
+    fn from(action: AutoModerationAction) -> Self {
+        let (kind, metadata) = match action {
+            AutoModerationAction::BlockMessage { custom_message: Some(custom_message) } => (1, json!({ "custom_message": custom_message })),
+            AutoModerationAction::BlockMessage { custom_message: None } => (1, Value::Null),
+            AutoModerationAction::SendAlertMessage { channel_id } => (2, json!({ "channel_id": channel_id })),
+            AutoModerationAction::Timeout { duration_seconds } => (3, json!({ "duration_seconds": duration_seconds })),
+            AutoModerationAction::BlockMemberInteraction => (4, Value::Null),
+            AutoModerationAction::Unknown { kind, metadata } => (kind, metadata),
+        };
+        RawAction { kind, metadata }
+    }
+}
+
+/// An auto moderation rule of a guild.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct AutoModerationRule {
+    pub id: String,
+    pub guild_id: String,
+    pub name: String,
+    /// The user who created the rule.
+    pub creator_id: String,
+    pub event_type: EventType,
+    pub trigger_type: TriggerType,
+    #[serde(default)]
+    pub trigger_metadata: TriggerMetadata,
+    #[serde(default)]
+    pub actions: Vec<AutoModerationAction>,
+    #[serde(default)]
+    pub enabled: bool,
+    /// Roles the rule does not apply to, at most 20.
+    #[serde(default)]
+    pub exempt_roles: Vec<String>,
+    /// Channels the rule does not apply to, at most 50.
+    #[serde(default)]
+    pub exempt_channels: Vec<String>,
+}
+
+/// Builds the body for creating or modifying an auto moderation rule.
+///
+/// # Examples
+///
+/// ```
+/// use Rust_Discord_API::auto_moderation::{AutoModerationAction, AutoModerationRuleBuilder, EventType, TriggerMetadata, TriggerType};
+///
+/// let rule = AutoModerationRuleBuilder::new("No invites", EventType::MessageSend, TriggerType::Keyword)
+///     .trigger_metadata(TriggerMetadata { keyword_filter: vec!["discord.gg/*".to_string()], ..Default::default() })
+///     .action(AutoModerationAction::BlockMessage { custom_message: Some("Invites aren't allowed here.".to_string()) })
+///     .action(AutoModerationAction::Timeout { duration_seconds: 600 })
+///     .exempt_role("81384788765712384")
+///     .enabled(true);
+/// assert_eq!(rule.build()["trigger_type"], 1);
+///
+/// // Only the fields set are changed when modifying a rule.
+/// let changes = AutoModerationRuleBuilder::edit().enabled(false);
+/// assert_eq!(changes.build(), serde_json::json!({ "enabled": false }));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct AutoModerationRuleBuilder {
+    name: Option<String>,
+    event_type: Option<EventType>,
+    trigger_type: Option<TriggerType>,
+    trigger_metadata: Option<TriggerMetadata>,
+    actions: Option<Vec<AutoModerationAction>>,
+    enabled: Option<bool>,
+    exempt_roles: Option<Vec<String>>,
+    exempt_channels: Option<Vec<String>>,
+}
+
+impl AutoModerationRuleBuilder {
+    /// Starts a new rule.
+    pub fn new(name: &str, event_type: EventType, trigger_type: TriggerType) -> Self {
+        Self { name: Some(name.to_string()), event_type: Some(event_type), trigger_type: Some(trigger_type), ..Self::default() }
+    }
+
+    /// Starts the changes to an existing rule. Its trigger type can't be changed.
+    pub fn edit() -> Self {
+        Self::default()
+    }
+
+    /// Sets the name of the rule.
+    pub fn name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    /// Sets when the rule is checked.
+    pub fn event_type(mut self, event_type: EventType) -> Self {
+        self.event_type = Some(event_type);
+        self
+    }
+
+    /// Sets the settings of the rule's trigger.
+    pub fn trigger_metadata(mut self, trigger_metadata: TriggerMetadata) -> Self {
+        self.trigger_metadata = Some(trigger_metadata);
+        self
+    }
+
+    /// Adds an action taken when the rule is triggered.
+    pub fn action(mut self, action: AutoModerationAction) -> Self {
+        self.actions.get_or_insert_with(Vec::new).push(action);
+        self
+    }
+
+    /// Sets whether the rule is enabled. Rules are disabled by default.
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = Some(enabled);
+        self
+    }
+
+    /// Exempts a role from the rule.
+    pub fn exempt_role(mut self, role_id: &str) -> Self {
+        self.exempt_roles.get_or_insert_with(Vec::new).push(role_id.to_string());
+        self
+    }
+
+    /// Exempts a channel from the rule.
+    pub fn exempt_channel(mut self, channel_id: &str) -> Self {
+        self.exempt_channels.get_or_insert_with(Vec::new).push(channel_id.to_string());
+        self
+    }
+
+    /// Builds the JSON body of the rule.
+    pub fn build(&self) -> Value {
+        let mut body = json!({});
+        if let Some(name) = &self.name {
+            body["name"] = json!(name);
+        }
+        if let Some(event_type) = self.event_type {
+            body["event_type"] = json!(event_type);
+        }
+        if let Some(trigger_type) = self.trigger_type {
+            body["trigger_type"] = json!(trigger_type);
+        }
+        if let Some(trigger_metadata) = &self.trigger_metadata {
+            body["trigger_metadata"] = json!(trigger_metadata);
+        }
+        if let Some(actions) = &self.actions {
+            body["actions"] = json!(actions);
+        }
+        if let Some(enabled) = self.enabled {
+            body["enabled"] = json!(enabled);
+        }
+        if let Some(exempt_roles) = &self.exempt_roles {
+            body["exempt_roles"] = json!(exempt_roles);
+        }
+        if let Some(exempt_channels) = &self.exempt_channels {
+            body["exempt_channels"] = json!(exempt_channels);
+        }
+        body
+    }
+}
+
+/// Lists the auto moderation rules of a guild. Needs the `MANAGE_GUILD` permission.
+///
+/// # Arguments
+///
+/// * `client` - The HTTP client used to send the request.
+/// * `token` - The bot token for authentication.
+/// * `guild_id` - The ID of the guild.
+///
+/// # Returns
+///
+/// A result containing the guild's rules.
+#[allow(dead_code)]
+pub async fn list_auto_moderation_rules(client: &Client, token: &str, guild_id: &str) -> Result<Vec<AutoModerationRule>, Box<dyn Error>> {
+    let url = format!("https://discord.com/api/v9/guilds/{}/auto-moderation/rules", guild_id);
+    let response: Vec<AutoModerationRule> = client.get(&url)
+        .bearer_auth(token)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    Ok(response)
+}
+
+/// Fetches an auto moderation rule of a guild. Needs the `MANAGE_GUILD` permission.
+///
+/// # Arguments
+///
+/// * `client` - The HTTP client used to send the request.
+/// * `token` - The bot token for authentication.
+/// * `guild_id` - The ID of the guild.
+/// * `rule_id` - The ID of the rule.
+///
+/// # Returns
+///
+/// A result containing the rule.
+#[allow(dead_code)]
+pub async fn get_auto_moderation_rule(client: &Client, token: &str, guild_id: &str, rule_id: &str) -> Result<AutoModerationRule, Box<dyn Error>> {
+    let url = format!("https://discord.com/api/v9/guilds/{}/auto-moderation/rules/{}", guild_id, rule_id);
+    let response: AutoModerationRule = client.get(&url)
+        .bearer_auth(token)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    Ok(response)
+}
+
+/// Creates an auto moderation rule in a guild. Needs the `MANAGE_GUILD` permission.
+///
+/// # Arguments
+///
+/// * `client` - The HTTP client used to send the request.
+/// * `token` - The bot token for authentication.
+/// * `guild_id` - The ID of the guild.
+/// * `rule` - The rule, started with `AutoModerationRuleBuilder::new`.
+/// * `reason` - The reason shown in the audit log, if any.
+///
+/// # Returns
+///
+/// A result containing the created rule.
+#[allow(dead_code)]
+pub async fn create_auto_moderation_rule(client: &Client, token: &str, guild_id: &str, rule: &AutoModerationRuleBuilder, reason: Option<&str>) -> Result<AutoModerationRule, Box<dyn Error>> {
+    let url = format!("https://discord.com/api/v9/guilds/{}/auto-moderation/rules", guild_id);
+    let response: AutoModerationRule = client.post(&url)
+        .bearer_auth(token)
+        .audit_log_reason(reason)
+        .json(&rule.build())
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    Ok(response)
+}
+
+/// Modifies an auto moderation rule of a guild. Needs the `MANAGE_GUILD` permission.
+///
+/// # Arguments
+///
+/// * `client` - The HTTP client used to send the request.
+/// * `token` - The bot token for authentication.
+/// * `guild_id` - The ID of the guild.
+/// * `rule_id` - The ID of the rule.
+/// * `changes` - The fields to change, started with `AutoModerationRuleBuilder::edit`.
+/// * `reason` - The reason shown in the audit log, if any.
+///
+/// # Returns
+///
+/// A result containing the modified rule.
+#[allow(dead_code)]
+pub async fn modify_auto_moderation_rule(client: &Client, token: &str, guild_id: &str, rule_id: &str, changes: &AutoModerationRuleBuilder, reason: Option<&str>) -> Result<AutoModerationRule, Box<dyn Error>> {
+    let url = format!("https://discord.com/api/v9/guilds/{}/auto-moderation/rules/{}", guild_id, rule_id);
+    let response: AutoModerationRule = client.patch(&url)
+        .bearer_auth(token)
+        .audit_log_reason(reason)
+        .json(&changes.build())
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    Ok(response)
+}
+
+/// Deletes an auto moderation rule of a guild. Needs the `MANAGE_GUILD` permission.
+///
+/// # Arguments
+///
+/// * `client` - The HTTP client used to send the request.
+/// * `token` - The bot token for authentication.
+/// * `guild_id` - The ID of the guild.
+/// * `rule_id` - The ID of the rule.
+/// * `reason` - The reason shown in the audit log, if any.
+///
+/// # Returns
+///
+/// A result indicating success or failure.
+#[allow(dead_code)]
+pub async fn delete_auto_moderation_rule(client: &Client, token: &str, guild_id: &str, rule_id: &str, reason: Option<&str>) -> Result<(), Box<dyn Error>> {
+    let url = format!("https://discord.com/api/v9/guilds/{}/auto-moderation/rules/{}", guild_id, rule_id);
+
+    client.delete(&url)
+        .bearer_auth(token)
+        .audit_log_reason(reason)
+        .send()
+        .await?
+        .error_for_status()?;
+
+    Ok(())
+}
@@ -1,7 +1,15 @@
+use futures::stream::BoxStream;
 use reqwest::Client;
 use serde_json::Value;
 use std::error::Error;
 
+use crate::model::User;
+
+use super::audit_log::AuditLogReason;
+use super::emoji::{ReactionKind, ReactionType};
+use super::invite::Invite;
+use super::pagination::{self, Endpoint, PageResult, PaginationOptions};
+
 /// Fetches information about a Discord channel.
 ///
 /// # Arguments
@@ -34,16 +42,18 @@ pub async fn fetch_channel_info(client: &Client, token: &str, channel_id: &str)
 /// * `token` - The bot token for authentication.
 /// * `channel_id` - The ID of the channel to modify.
 /// * `settings` - The JSON value of the settings to update.
+/// * `reason` - The reason shown in the audit log, if any.
 ///
 /// # Returns
 ///
 /// A result indicating success or failure.
 #[allow(dead_code)]
-pub async fn modify_channel(client: &Client, token: &str, channel_id: &str, settings: Value) -> Result<(), Box<dyn Error>> {
+pub async fn modify_channel(client: &Client, token: &str, channel_id: &str, settings: Value, reason: Option<&str>) -> Result<(), Box<dyn Error>> {
     let url = format!("https://discord.com/api/v9/channels/{}", channel_id);
     
     client.patch(&url)
         .bearer_auth(token)
+        .audit_log_reason(reason)
         .json(&settings)
         .send()
         .await?
@@ -59,16 +69,18 @@ pub async fn modify_channel(client: &Client, token: &str, channel_id: &str, sett
 /// * `client` - The HTTP client used to send the request.
 /// * `token` - The bot token for authentication.
 /// * `channel_id` - The ID of the channel to delete.
+/// * `reason` - The reason shown in the audit log, if any.
 ///
 /// # Returns
 ///
 /// A result indicating success or failure.
 #[allow(dead_code)]
-pub async fn delete_channel(client: &Client, token: &str, channel_id: &str) -> Result<(), Box<dyn Error>> {
+pub async fn delete_channel(client: &Client, token: &str, channel_id: &str, reason: Option<&str>) -> Result<(), Box<dyn Error>> {
     let url = format!("https://discord.com/api/v9/channels/{}", channel_id);
     
     client.delete(&url)
         .bearer_auth(token)
+        .audit_log_reason(reason)
         .send()
         .await?
         .error_for_status()?;
@@ -100,6 +112,32 @@ pub async fn get_channel_messages(client: &Client, token: &str, channel_id: &str
     Ok(response)
 }
 
+/// Streams messages from a Discord channel, following the `before`/`after` cursors.
+///
+/// # Arguments
+///
+/// * `client` - The HTTP client used to send the requests.
+/// * `token` - The bot token for authentication.
+/// * `channel_id` - The ID of the channel to fetch messages from.
+/// * `options` - The direction, starting message and maximum number of messages.
+///
+/// # Returns
+///
+/// A stream of messages as JSON values.
+#[allow(dead_code)]
+pub fn stream_channel_messages(client: &Client, token: &str, channel_id: &str, options: PaginationOptions) -> BoxStream<'static, PageResult> {
+    let endpoint = Endpoint {
+        url: format!("https://discord.com/api/v9/channels/{}/messages", channel_id),
+        query: Vec::new(),
+        max_page_size: 100,
+        supports_before: true,
+        items: pagination::array_items,
+        id: pagination::item_id,
+    };
+
+    pagination::paginate(client, token, endpoint, options)
+}
+
 /// Fetches a single message from a Discord channel.
 ///
 /// # Arguments
@@ -164,8 +202,8 @@ pub async fn crosspost_message(client: &Client, token: &str, channel_id: &str, m
 ///
 /// A result indicating success or failure.
 #[allow(dead_code)]
-pub async fn create_reaction(client: &Client, token: &str, channel_id: &str, message_id: &str, emoji: &str) -> Result<(), Box<dyn Error>> {
-    let url = format!("https://discord.com/api/v9/channels/{}/messages/{}/reactions/{}/@me", channel_id, message_id, emoji);
+pub async fn create_reaction(client: &Client, token: &str, channel_id: &str, message_id: &str, emoji: &ReactionType) -> Result<(), Box<dyn Error>> {
+    let url = format!("https://discord.com/api/v9/channels/{}/messages/{}/reactions/{}/@me", channel_id, message_id, emoji.path_segment());
     
     client.put(&url)
         .bearer_auth(token)
@@ -190,8 +228,8 @@ pub async fn create_reaction(client: &Client, token: &str, channel_id: &str, mes
 ///
 /// A result indicating success or failure.
 #[allow(dead_code)]
-pub async fn delete_own_reaction(client: &Client, token: &str, channel_id: &str, message_id: &str, emoji: &str) -> Result<(), Box<dyn Error>> {
-    let url = format!("https://discord.com/api/v9/channels/{}/messages/{}/reactions/{}/@me", channel_id, message_id, emoji);
+pub async fn delete_own_reaction(client: &Client, token: &str, channel_id: &str, message_id: &str, emoji: &ReactionType) -> Result<(), Box<dyn Error>> {
+    let url = format!("https://discord.com/api/v9/channels/{}/messages/{}/reactions/{}/@me", channel_id, message_id, emoji.path_segment());
     
     client.delete(&url)
         .bearer_auth(token)
@@ -217,8 +255,8 @@ pub async fn delete_own_reaction(client: &Client, token: &str, channel_id: &str,
 ///
 /// A result indicating success or failure.
 #[allow(dead_code)]
-pub async fn delete_user_reaction(client: &Client, token: &str, channel_id: &str, message_id: &str, emoji: &str, user_id: &str) -> Result<(), Box<dyn Error>> {
-    let url = format!("https://discord.com/api/v9/channels/{}/messages/{}/reactions/{}/{}", channel_id, message_id, emoji, user_id);
+pub async fn delete_user_reaction(client: &Client, token: &str, channel_id: &str, message_id: &str, emoji: &ReactionType, user_id: &str) -> Result<(), Box<dyn Error>> {
+    let url = format!("https://discord.com/api/v9/channels/{}/messages/{}/reactions/{}/{}", channel_id, message_id, emoji.path_segment(), user_id);
     
     client.delete(&url)
         .bearer_auth(token)
@@ -229,7 +267,8 @@ pub async fn delete_user_reaction(client: &Client, token: &str, channel_id: &str
     Ok(())
 }
 
-/// Gets all reactions for a message.
+/// Gets up to 100 of the users who reacted to a message with an emoji. Use
+/// `stream_reactions` to page through all of them.
 ///
 /// # Arguments
 ///
@@ -237,23 +276,61 @@ pub async fn delete_user_reaction(client: &Client, token: &str, channel_id: &str
 /// * `token` - The bot token for authentication.
 /// * `channel_id` - The ID of the channel where the message is located.
 /// * `message_id` - The ID of the message to get reactions for.
+/// * `emoji` - The emoji to list users for.
+/// * `kind` - Whether to list normal reactions or super reactions.
+/// * `after` - Only list users with a higher ID than this, such as the last user of the previous page.
 ///
 /// # Returns
 ///
-/// A result containing the reactions as a JSON value.
+/// A result containing the users.
 #[allow(dead_code)]
-pub async fn get_reactions(client: &Client, token: &str, channel_id: &str, message_id: &str) -> Result<Value, Box<dyn Error>> {
-    let url = format!("https://discord.com/api/v9/channels/{}/messages/{}/reactions", channel_id, message_id);
-    let response: Value = client.get(&url)
+pub async fn get_reactions(client: &Client, token: &str, channel_id: &str, message_id: &str, emoji: &ReactionType, kind: ReactionKind, after: Option<&str>) -> Result<Vec<User>, Box<dyn Error>> {
+    let url = format!("https://discord.com/api/v9/channels/{}/messages/{}/reactions/{}", channel_id, message_id, emoji.path_segment());
+    let mut query = vec![("type", kind.query_value()), ("limit", "100".to_string())];
+    if let Some(after) = after {
+        query.push(("after", after.to_string()));
+    }
+    let response: Vec<User> = client.get(&url)
         .bearer_auth(token)
+        .query(&query)
         .send()
         .await?
+        .error_for_status()?
         .json()
         .await?;
     
     Ok(response)
 }
 
+/// Streams the users who reacted to a message with an emoji, following the `after` cursor.
+///
+/// # Arguments
+///
+/// * `client` - The HTTP client used to send the requests.
+/// * `token` - The bot token for authentication.
+/// * `channel_id` - The ID of the channel where the message is located.
+/// * `message_id` - The ID of the message to get reactions for.
+/// * `emoji` - The emoji to list users for.
+/// * `kind` - Whether to list normal reactions or super reactions.
+/// * `options` - The starting user and maximum number of users. Only `Direction::After` is supported.
+///
+/// # Returns
+///
+/// A stream of users as JSON values.
+#[allow(dead_code)]
+pub fn stream_reactions(client: &Client, token: &str, channel_id: &str, message_id: &str, emoji: &ReactionType, kind: ReactionKind, options: PaginationOptions) -> BoxStream<'static, PageResult> {
+    let endpoint = Endpoint {
+        url: format!("https://discord.com/api/v9/channels/{}/messages/{}/reactions/{}", channel_id, message_id, emoji.path_segment()),
+        query: vec![("type", kind.query_value())],
+        max_page_size: 100,
+        supports_before: false,
+        items: pagination::array_items,
+        id: pagination::item_id,
+    };
+
+    pagination::paginate(client, token, endpoint, options)
+}
+
 /// Deletes all reactions for a message.
 ///
 /// # Arguments
@@ -293,8 +370,8 @@ pub async fn delete_all_reactions(client: &Client, token: &str, channel_id: &str
 ///
 /// A result indicating success or failure.
 #[allow(dead_code)]
-pub async fn delete_all_reactions_for_emoji(client: &Client, token: &str, channel_id: &str, message_id: &str, emoji: &str) -> Result<(), Box<dyn Error>> {
-    let url = format!("https://discord.com/api/v9/channels/{}/messages/{}/reactions/{}", channel_id, message_id, emoji);
+pub async fn delete_all_reactions_for_emoji(client: &Client, token: &str, channel_id: &str, message_id: &str, emoji: &ReactionType) -> Result<(), Box<dyn Error>> {
+    let url = format!("https://discord.com/api/v9/channels/{}/messages/{}/reactions/{}", channel_id, message_id, emoji.path_segment());
     
     client.delete(&url)
         .bearer_auth(token)
@@ -341,16 +418,18 @@ pub async fn edit_message(client: &Client, token: &str, channel_id: &str, messag
 /// * `token` - The bot token for authentication.
 /// * `channel_id` - The ID of the channel where the message is located.
 /// * `message_id` - The ID of the message to delete.
+/// * `reason` - The reason shown in the audit log, if any.
 ///
 /// # Returns
 ///
 /// A result indicating success or failure.
 #[allow(dead_code)]
-pub async fn delete_message(client: &Client, token: &str, channel_id: &str, message_id: &str) -> Result<(), Box<dyn Error>> {
+pub async fn delete_message(client: &Client, token: &str, channel_id: &str, message_id: &str, reason: Option<&str>) -> Result<(), Box<dyn Error>> {
     let url = format!("https://discord.com/api/v9/channels/{}/messages/{}", channel_id, message_id);
     
     client.delete(&url)
         .bearer_auth(token)
+        .audit_log_reason(reason)
         .send()
         .await?
         .error_for_status()?;
@@ -366,17 +445,19 @@ pub async fn delete_message(client: &Client, token: &str, channel_id: &str, mess
 /// * `token` - The bot token for authentication.
 /// * `channel_id` - The ID of the channel where the messages are located.
 /// * `message_ids` - A list of message IDs to delete.
+/// * `reason` - The reason shown in the audit log, if any.
 ///
 /// # Returns
 ///
 /// A result indicating success or failure.
 #[allow(dead_code)]
-pub async fn bulk_delete_messages(client: &Client, token: &str, channel_id: &str, message_ids: Vec<&str>) -> Result<(), Box<dyn Error>> {
+pub async fn bulk_delete_messages(client: &Client, token: &str, channel_id: &str, message_ids: Vec<&str>, reason: Option<&str>) -> Result<(), Box<dyn Error>> {
     let url = format!("https://discord.com/api/v9/channels/{}/messages/bulk-delete", channel_id);
     let body = serde_json::json!({ "messages": message_ids });
     
     client.post(&url)
         .bearer_auth(token)
+        .audit_log_reason(reason)
         .json(&body)
         .send()
         .await?
@@ -394,16 +475,18 @@ pub async fn bulk_delete_messages(client: &Client, token: &str, channel_id: &str
 /// * `channel_id` - The ID of the channel to edit permissions for.
 /// * `overwrite_id` - The ID of the overwrite to edit.
 /// * `permissions` - The JSON value of the permissions to update.
+/// * `reason` - The reason shown in the audit log, if any.
 ///
 /// # Returns
 ///
 /// A result indicating success or failure.
 #[allow(dead_code)]
-pub async fn edit_channel_permissions(client: &Client, token: &str, channel_id: &str, overwrite_id: &str, permissions: Value) -> Result<(), Box<dyn Error>> {
+pub async fn edit_channel_permissions(client: &Client, token: &str, channel_id: &str, overwrite_id: &str, permissions: Value, reason: Option<&str>) -> Result<(), Box<dyn Error>> {
     let url = format!("https://discord.com/api/v9/channels/{}/permissions/{}", channel_id, overwrite_id);
     
     client.put(&url)
         .bearer_auth(token)
+        .audit_log_reason(reason)
         .json(&permissions)
         .send()
         .await?
@@ -422,14 +505,15 @@ pub async fn edit_channel_permissions(client: &Client, token: &str, channel_id:
 ///
 /// # Returns
 ///
-/// A result containing the channel invites as a JSON value.
+/// A result containing the channel invites, with their use counts.
 #[allow(dead_code)]
-pub async fn get_channel_invites(client: &Client, token: &str, channel_id: &str) -> Result<Value, Box<dyn Error>> {
+pub async fn get_channel_invites(client: &Client, token: &str, channel_id: &str) -> Result<Vec<Invite>, Box<dyn Error>> {
     let url = format!("https://discord.com/api/v9/channels/{}/invites", channel_id);
-    let response: Value = client.get(&url)
+    let response: Vec<Invite> = client.get(&url)
         .bearer_auth(token)
         .send()
         .await?
+        .error_for_status()?
         .json()
         .await?;
     
@@ -444,18 +528,21 @@ pub async fn get_channel_invites(client: &Client, token: &str, channel_id: &str)
 /// * `token` - The bot token for authentication.
 /// * `channel_id` - The ID of the channel to create an invite for.
 /// * `invite_settings` - The JSON value of the invite settings.
+/// * `reason` - The reason shown in the audit log, if any.
 ///
 /// # Returns
 ///
-/// A result containing the created invite as a JSON value.
+/// A result containing the created invite.
 #[allow(dead_code)]
-pub async fn create_channel_invite(client: &Client, token: &str, channel_id: &str, invite_settings: Value) -> Result<Value, Box<dyn Error>> {
+pub async fn create_channel_invite(client: &Client, token: &str, channel_id: &str, invite_settings: Value, reason: Option<&str>) -> Result<Invite, Box<dyn Error>> {
     let url = format!("https://discord.com/api/v9/channels/{}/invites", channel_id);
-    let response: Value = client.post(&url)
+    let response: Invite = client.post(&url)
         .bearer_auth(token)
+        .audit_log_reason(reason)
         .json(&invite_settings)
         .send()
         .await?
+        .error_for_status()?
         .json()
         .await?;
     
@@ -470,16 +557,18 @@ pub async fn create_channel_invite(client: &Client, token: &str, channel_id: &st
 /// * `token` - The bot token for authentication.
 /// * `channel_id` - The ID of the channel to delete the permission overwrite for.
 /// * `overwrite_id` - The ID of the overwrite to delete.
+/// * `reason` - The reason shown in the audit log, if any.
 ///
 /// # Returns
 ///
 /// A result indicating success or failure.
 #[allow(dead_code)]
-pub async fn delete_channel_permission(client: &Client, token: &str, channel_id: &str, overwrite_id: &str) -> Result<(), Box<dyn Error>> {
+pub async fn delete_channel_permission(client: &Client, token: &str, channel_id: &str, overwrite_id: &str, reason: Option<&str>) -> Result<(), Box<dyn Error>> {
     let url = format!("https://discord.com/api/v9/channels/{}/permissions/{}", channel_id, overwrite_id);
     
     client.delete(&url)
         .bearer_auth(token)
+        .audit_log_reason(reason)
         .send()
         .await?
         .error_for_status()?;
@@ -495,16 +584,18 @@ pub async fn delete_channel_permission(client: &Client, token: &str, channel_id:
 /// * `token` - The bot token for authentication.
 /// * `channel_id` - The ID of the announcement channel to follow.
 /// * `webhook_channel_id` - The ID of the channel to send messages to.
+/// * `reason` - The reason shown in the audit log, if any.
 ///
 /// # Returns
 ///
 /// A result containing the follow response as a JSON value.
 #[allow(dead_code)]
-pub async fn follow_announcement_channel(client: &Client, token: &str, channel_id: &str, webhook_channel_id: &str) -> Result<Value, Box<dyn Error>> {
+pub async fn follow_announcement_channel(client: &Client, token: &str, channel_id: &str, webhook_channel_id: &str, reason: Option<&str>) -> Result<Value, Box<dyn Error>> {
     let url = format!("https://discord.com/api/v9/channels/{}/followers", channel_id);
     let body = serde_json::json!({ "webhook_channel_id": webhook_channel_id });
     let response: Value = client.post(&url)
         .bearer_auth(token)
+        .audit_log_reason(reason)
         .json(&body)
         .send()
         .await?
@@ -570,16 +661,18 @@ pub async fn get_pinned_messages(client: &Client, token: &str, channel_id: &str)
 /// * `token` - The bot token for authentication.
 /// * `channel_id` - The ID of the channel where the message is located.
 /// * `message_id` - The ID of the message to pin.
+/// * `reason` - The reason shown in the audit log, if any.
 ///
 /// # Returns
 ///
 /// A result indicating success or failure.
 #[allow(dead_code)]
-pub async fn pin_message(client: &Client, token: &str, channel_id: &str, message_id: &str) -> Result<(), Box<dyn Error>> {
+pub async fn pin_message(client: &Client, token: &str, channel_id: &str, message_id: &str, reason: Option<&str>) -> Result<(), Box<dyn Error>> {
     let url = format!("https://discord.com/api/v9/channels/{}/pins/{}", channel_id, message_id);
     
     client.put(&url)
         .bearer_auth(token)
+        .audit_log_reason(reason)
         .send()
         .await?
         .error_for_status()?;
@@ -595,16 +688,18 @@ pub async fn pin_message(client: &Client, token: &str, channel_id: &str, message
 /// * `token` - The bot token for authentication.
 /// * `channel_id` - The ID of the channel where the message is located.
 /// * `message_id` - The ID of the message to unpin.
+/// * `reason` - The reason shown in the audit log, if any.
 ///
 /// # Returns
 ///
 /// A result indicating success or failure.
 #[allow(dead_code)]
-pub async fn unpin_message(client: &Client, token: &str, channel_id: &str, message_id: &str) -> Result<(), Box<dyn Error>> {
+pub async fn unpin_message(client: &Client, token: &str, channel_id: &str, message_id: &str, reason: Option<&str>) -> Result<(), Box<dyn Error>> {
     let url = format!("https://discord.com/api/v9/channels/{}/pins/{}", channel_id, message_id);
     
     client.delete(&url)
         .bearer_auth(token)
+        .audit_log_reason(reason)
         .send()
         .await?
         .error_for_status()?;
@@ -675,15 +770,17 @@ pub async fn group_dm_remove_recipient(client: &Client, token: &str, channel_id:
 /// * `channel_id` - The ID of the channel where the message is located.
 /// * `message_id` - The ID of the message to start the thread from.
 /// * `thread_settings` - The JSON value of the thread settings.
+/// * `reason` - The reason shown in the audit log, if any.
 ///
 /// # Returns
 ///
 /// A result containing the created thread information as a JSON value.
 #[allow(dead_code)]
-pub async fn start_thread_from_message(client: &Client, token: &str, channel_id: &str, message_id: &str, thread_settings: Value) -> Result<Value, Box<dyn Error>> {
+pub async fn start_thread_from_message(client: &Client, token: &str, channel_id: &str, message_id: &str, thread_settings: Value, reason: Option<&str>) -> Result<Value, Box<dyn Error>> {
     let url = format!("https://discord.com/api/v9/channels/{}/messages/{}/threads", channel_id, message_id);
     let response: Value = client.post(&url)
         .bearer_auth(token)
+        .audit_log_reason(reason)
         .json(&thread_settings)
         .send()
         .await?
@@ -701,15 +798,17 @@ pub async fn start_thread_from_message(client: &Client, token: &str, channel_id:
 /// * `token` - The bot token for authentication.
 /// * `channel_id` - The ID of the channel to start the thread in.
 /// * `thread_settings` - The JSON value of the thread settings.
+/// * `reason` - The reason shown in the audit log, if any.
 ///
 /// # Returns
 ///
 /// A result containing the created thread information as a JSON value.
 #[allow(dead_code)]
-pub async fn start_thread_without_message(client: &Client, token: &str, channel_id: &str, thread_settings: Value) -> Result<Value, Box<dyn Error>> {
+pub async fn start_thread_without_message(client: &Client, token: &str, channel_id: &str, thread_settings: Value, reason: Option<&str>) -> Result<Value, Box<dyn Error>> {
     let url = format!("https://discord.com/api/v9/channels/{}/threads", channel_id);
     let response: Value = client.post(&url)
         .bearer_auth(token)
+        .audit_log_reason(reason)
         .json(&thread_settings)
         .send()
         .await?
@@ -0,0 +1,501 @@
+use serde::{Deserialize, Deserializer, Serialize};
+use serde::de::Error as DeError;
+use serde_json::Value;
+use serde_repr::{Deserialize_repr, Serialize_repr};
+
+/// The type of a message component, as sent in the `type` field.
+#[derive(Serialize_repr, Deserialize_repr, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum ComponentType {
+    ActionRow = 1,
+    Button = 2,
+    StringSelect = 3,
+    TextInput = 4,
+    UserSelect = 5,
+    RoleSelect = 6,
+    MentionableSelect = 7,
+    ChannelSelect = 8,
+}
+
+/// The visual style of a button.
+#[derive(Serialize_repr, Deserialize_repr, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum ButtonStyle {
+    Primary = 1,
+    Secondary = 2,
+    Success = 3,
+    Danger = 4,
+    Link = 5,
+    Premium = 6,
+}
+
+/// The size of a text input.
+#[derive(Serialize_repr, Deserialize_repr, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum TextInputStyle {
+    /// A single-line input.
+    Short = 1,
+    /// A multi-line input.
+    Paragraph = 2,
+}
+
+/// A partial emoji used on buttons and select options.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PartialEmoji {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub animated: bool,
+}
+
+impl PartialEmoji {
+    /// Creates a partial emoji for a Unicode emoji such as `"✅"`.
+    pub fn unicode(name: &str) -> Self {
+        Self { id: None, name: Some(name.to_string()), animated: false }
+    }
+
+    /// Creates a partial emoji for a custom guild emoji.
+    pub fn custom(name: &str, id: &str, animated: bool) -> Self {
+        Self { id: Some(id.to_string()), name: Some(name.to_string()), animated }
+    }
+}
+
+/// Any component that can appear in a message or modal.
+///
+/// Serializes to the component's own JSON object; deserializes by looking at
+/// the `type` field.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum Component {
+    ActionRow(ActionRow),
+    Button(Button),
+    SelectMenu(SelectMenu),
+    TextInput(TextInput),
+}
+
+impl<'de> Deserialize<'de> for Component {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+        let kind: ComponentType = value.get("type")
+            .cloned()
+            .map(serde_json::from_value)
+            .transpose()
+            .map_err(DeError::custom)?
+            .ok_or_else(|| DeError::missing_field("type"))?;
+
+        let component = match kind {
+            ComponentType::ActionRow => serde_json::from_value(value).map(Component::ActionRow),
+            ComponentType::Button => serde_json::from_value(value).map(Component::Button),
+            ComponentType::StringSelect
+            | ComponentType::UserSelect
+            | ComponentType::RoleSelect
+            | ComponentType::MentionableSelect
+            | ComponentType::ChannelSelect => serde_json::from_value(value).map(Component::SelectMenu),
+            ComponentType::TextInput => serde_json::from_value(value).map(Component::TextInput),
+        };
+
+        component.map_err(DeError::custom)
+    }
+}
+
+impl From<ActionRow> for Component {
+    fn from(row: ActionRow) -> Self {
+        Component::ActionRow(row)
+    }
+}
+
+impl From<Button> for Component {
+    fn from(button: Button) -> Self {
+        Component::Button(button)
+    }
+}
+
+impl From<SelectMenu> for Component {
+    fn from(menu: SelectMenu) -> Self {
+        Component::SelectMenu(menu)
+    }
+}
+
+impl From<TextInput> for Component {
+    fn from(input: TextInput) -> Self {
+        Component::TextInput(input)
+    }
+}
+
+/// A row of components. A row holds either up to five buttons, a single select
+/// menu or, inside a modal, a single text input.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ActionRow {
+    #[serde(rename = "type")]
+    kind: ComponentType,
+    pub components: Vec<Component>,
+}
+
+impl ActionRow {
+    /// Creates an empty action row.
+    pub fn new() -> Self {
+        Self { kind: ComponentType::ActionRow, components: Vec::new() }
+    }
+
+    /// Creates an action row holding the given buttons.
+    pub fn buttons(buttons: Vec<Button>) -> Self {
+        Self { kind: ComponentType::ActionRow, components: buttons.into_iter().map(Component::Button).collect() }
+    }
+
+    /// Creates an action row holding a single select menu.
+    pub fn select_menu(menu: SelectMenu) -> Self {
+        Self { kind: ComponentType::ActionRow, components: vec![Component::SelectMenu(menu)] }
+    }
+
+    /// Creates an action row holding a single text input, for use in a modal.
+    pub fn text_input(input: TextInput) -> Self {
+        Self { kind: ComponentType::ActionRow, components: vec![Component::TextInput(input)] }
+    }
+
+    /// Appends a component to the row.
+    pub fn push(mut self, component: impl Into<Component>) -> Self {
+        self.components.push(component.into());
+        self
+    }
+}
+
+impl Default for ActionRow {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A clickable button.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Button {
+    #[serde(rename = "type")]
+    kind: ComponentType,
+    pub style: ButtonStyle,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub emoji: Option<PartialEmoji>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub custom_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sku_id: Option<String>,
+    #[serde(default)]
+    pub disabled: bool,
+}
+
+impl Button {
+    fn with_style(style: ButtonStyle, custom_id: &str, label: &str) -> Self {
+        Self {
+            kind: ComponentType::Button,
+            style,
+            label: Some(label.to_string()),
+            emoji: None,
+            custom_id: Some(custom_id.to_string()),
+            url: None,
+            sku_id: None,
+            disabled: false,
+        }
+    }
+
+    /// Creates a blurple button.
+    pub fn primary(custom_id: &str, label: &str) -> Self {
+        Self::with_style(ButtonStyle::Primary, custom_id, label)
+    }
+
+    /// Creates a grey button.
+    pub fn secondary(custom_id: &str, label: &str) -> Self {
+        Self::with_style(ButtonStyle::Secondary, custom_id, label)
+    }
+
+    /// Creates a green button.
+    pub fn success(custom_id: &str, label: &str) -> Self {
+        Self::with_style(ButtonStyle::Success, custom_id, label)
+    }
+
+    /// Creates a red button.
+    pub fn danger(custom_id: &str, label: &str) -> Self {
+        Self::with_style(ButtonStyle::Danger, custom_id, label)
+    }
+
+    /// Creates a button that opens a URL. Link buttons do not send an interaction.
+    pub fn link(url: &str, label: &str) -> Self {
+        Self {
+            kind: ComponentType::Button,
+            style: ButtonStyle::Link,
+            label: Some(label.to_string()),
+            emoji: None,
+            custom_id: None,
+            url: Some(url.to_string()),
+            sku_id: None,
+            disabled: false,
+        }
+    }
+
+    /// Creates a button that prompts the user to purchase a SKU.
+    pub fn premium(sku_id: &str) -> Self {
+        Self {
+            kind: ComponentType::Button,
+            style: ButtonStyle::Premium,
+            label: None,
+            emoji: None,
+            custom_id: None,
+            url: None,
+            sku_id: Some(sku_id.to_string()),
+            disabled: false,
+        }
+    }
+
+    /// Sets the emoji shown on the button.
+    pub fn emoji(mut self, emoji: PartialEmoji) -> Self {
+        self.emoji = Some(emoji);
+        self
+    }
+
+    /// Sets whether the button is disabled.
+    pub fn disabled(mut self, disabled: bool) -> Self {
+        self.disabled = disabled;
+        self
+    }
+}
+
+/// A choice in a string select menu.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SelectOption {
+    pub label: String,
+    pub value: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub emoji: Option<PartialEmoji>,
+    #[serde(default)]
+    pub default: bool,
+}
+
+impl SelectOption {
+    /// Creates an option with a user-facing label and a developer-defined value.
+    pub fn new(label: &str, value: &str) -> Self {
+        Self { label: label.to_string(), value: value.to_string(), description: None, emoji: None, default: false }
+    }
+
+    /// Sets the additional description shown under the label.
+    pub fn description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_string());
+        self
+    }
+
+    /// Sets the emoji shown next to the label.
+    pub fn emoji(mut self, emoji: PartialEmoji) -> Self {
+        self.emoji = Some(emoji);
+        self
+    }
+
+    /// Sets whether the option is selected by default.
+    pub fn default_selected(mut self, default: bool) -> Self {
+        self.default = default;
+        self
+    }
+}
+
+/// The kind of entity a default value of an auto-populated select menu refers to.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SelectDefaultValueType {
+    User,
+    Role,
+    Channel,
+}
+
+/// A pre-selected entity in a user, role, mentionable or channel select menu.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SelectDefaultValue {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub kind: SelectDefaultValueType,
+}
+
+/// A dropdown menu. The component type decides whether options are supplied
+/// by the bot (string selects) or auto-populated by Discord (user, role,
+/// mentionable and channel selects).
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SelectMenu {
+    #[serde(rename = "type")]
+    pub kind: ComponentType,
+    pub custom_id: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub options: Vec<SelectOption>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub channel_types: Vec<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub placeholder: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub default_values: Vec<SelectDefaultValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_values: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_values: Option<u8>,
+    #[serde(default)]
+    pub disabled: bool,
+}
+
+impl SelectMenu {
+    fn with_kind(kind: ComponentType, custom_id: &str) -> Self {
+        Self {
+            kind,
+            custom_id: custom_id.to_string(),
+            options: Vec::new(),
+            channel_types: Vec::new(),
+            placeholder: None,
+            default_values: Vec::new(),
+            min_values: None,
+            max_values: None,
+            disabled: false,
+        }
+    }
+
+    /// Creates a select menu with bot-defined options.
+    pub fn string(custom_id: &str, options: Vec<SelectOption>) -> Self {
+        let mut menu = Self::with_kind(ComponentType::StringSelect, custom_id);
+        menu.options = options;
+        menu
+    }
+
+    /// Creates a select menu populated with users.
+    pub fn user(custom_id: &str) -> Self {
+        Self::with_kind(ComponentType::UserSelect, custom_id)
+    }
+
+    /// Creates a select menu populated with roles.
+    pub fn role(custom_id: &str) -> Self {
+        Self::with_kind(ComponentType::RoleSelect, custom_id)
+    }
+
+    /// Creates a select menu populated with users and roles.
+    pub fn mentionable(custom_id: &str) -> Self {
+        Self::with_kind(ComponentType::MentionableSelect, custom_id)
+    }
+
+    /// Creates a select menu populated with channels.
+    pub fn channel(custom_id: &str) -> Self {
+        Self::with_kind(ComponentType::ChannelSelect, custom_id)
+    }
+
+    /// Restricts a channel select menu to the given channel types.
+    pub fn channel_types(mut self, channel_types: Vec<u8>) -> Self {
+        self.channel_types = channel_types;
+        self
+    }
+
+    /// Sets the placeholder text shown when nothing is selected.
+    pub fn placeholder(mut self, placeholder: &str) -> Self {
+        self.placeholder = Some(placeholder.to_string());
+        self
+    }
+
+    /// Sets the pre-selected entities of an auto-populated select menu.
+    pub fn default_values(mut self, default_values: Vec<SelectDefaultValue>) -> Self {
+        self.default_values = default_values;
+        self
+    }
+
+    /// Sets the minimum number of items that must be chosen (0-25).
+    pub fn min_values(mut self, min_values: u8) -> Self {
+        self.min_values = Some(min_values);
+        self
+    }
+
+    /// Sets the maximum number of items that can be chosen (1-25).
+    pub fn max_values(mut self, max_values: u8) -> Self {
+        self.max_values = Some(max_values);
+        self
+    }
+
+    /// Sets whether the select menu is disabled.
+    pub fn disabled(mut self, disabled: bool) -> Self {
+        self.disabled = disabled;
+        self
+    }
+}
+
+/// A text field shown in a modal.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TextInput {
+    #[serde(rename = "type")]
+    kind: ComponentType,
+    pub custom_id: String,
+    pub style: TextInputStyle,
+    pub label: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_length: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_length: Option<u16>,
+    #[serde(default = "default_required")]
+    pub required: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub placeholder: Option<String>,
+}
+
+fn default_required() -> bool {
+    true
+}
+
+impl TextInput {
+    fn with_style(style: TextInputStyle, custom_id: &str, label: &str) -> Self {
+        Self {
+            kind: ComponentType::TextInput,
+            custom_id: custom_id.to_string(),
+            style,
+            label: label.to_string(),
+            min_length: None,
+            max_length: None,
+            required: true,
+            value: None,
+            placeholder: None,
+        }
+    }
+
+    /// Creates a single-line text input.
+    pub fn short(custom_id: &str, label: &str) -> Self {
+        Self::with_style(TextInputStyle::Short, custom_id, label)
+    }
+
+    /// Creates a multi-line text input.
+    pub fn paragraph(custom_id: &str, label: &str) -> Self {
+        Self::with_style(TextInputStyle::Paragraph, custom_id, label)
+    }
+
+    /// Sets the minimum input length (0-4000).
+    pub fn min_length(mut self, min_length: u16) -> Self {
+        self.min_length = Some(min_length);
+        self
+    }
+
+    /// Sets the maximum input length (1-4000).
+    pub fn max_length(mut self, max_length: u16) -> Self {
+        self.max_length = Some(max_length);
+        self
+    }
+
+    /// Sets whether the input must be filled in. Inputs are required by default.
+    pub fn required(mut self, required: bool) -> Self {
+        self.required = required;
+        self
+    }
+
+    /// Pre-fills the input with a value.
+    pub fn value(mut self, value: &str) -> Self {
+        self.value = Some(value.to_string());
+        self
+    }
+
+    /// Sets the placeholder text shown when the input is empty.
+    pub fn placeholder(mut self, placeholder: &str) -> Self {
+        self.placeholder = Some(placeholder.to_string());
+        self
+    }
+}
@@ -0,0 +1,513 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map};
+use serde_repr::{Deserialize_repr, Serialize_repr};
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::model::User;
+
+use super::api::percent_encode;
+use super::audit_log::AuditLogReason;
+use super::component::PartialEmoji;
+
+/// The largest image Discord accepts for an emoji, in bytes.
+pub const MAX_EMOJI_SIZE: usize = 256 * 1024;
+
+/// A custom emoji of a guild or an application.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Emoji {
+    pub id: Option<String>,
+    /// Missing for emojis that were deleted.
+    pub name: Option<String>,
+    /// The roles allowed to use the emoji. Everyone may use it when empty.
+    #[serde(default)]
+    pub roles: Vec<String>,
+    /// The user who uploaded the emoji. Needs the `MANAGE_GUILD_EXPRESSIONS` permission.
+    #[serde(default)]
+    pub user: Option<User>,
+    #[serde(default)]
+    pub require_colons: bool,
+    #[serde(default)]
+    pub managed: bool,
+    #[serde(default)]
+    pub animated: bool,
+    /// False when the emoji is unusable because the guild lost boosts.
+    #[serde(default = "available")]
+    pub available: bool,
+}
+
+fn available() -> bool {
+    true
+}
+
+impl Emoji {
+    /// Returns the text that renders the emoji in a message, such as `<:name:id>`.
+    pub fn mention(&self) -> String {
+        let prefix = if self.animated { "a" } else { "" };
+        format!("<{}:{}:{}>", prefix, self.name.as_deref().unwrap_or("_"), self.id.as_deref().unwrap_or_default())
+    }
+
+    /// Returns the emoji as a reaction.
+    pub fn reaction(&self) -> ReactionType {
+        ReactionType::from(self)
+    }
+}
+
+/// An emoji to react with, either a Unicode emoji or a custom one.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ReactionType {
+    /// A Unicode emoji such as `"👍"`.
+    Unicode(String),
+    /// A custom emoji, identified by its ID. The name only needs to be
+    /// present, Discord does not check it.
+    Custom { name: String, id: String },
+}
+
+impl ReactionType {
+    /// Creates a reaction with a Unicode emoji.
+    pub fn unicode(emoji: &str) -> Self {
+        ReactionType::Unicode(emoji.to_string())
+    }
+
+    /// Creates a reaction with a custom emoji.
+    pub fn custom(name: &str, id: &str) -> Self {
+        ReactionType::Custom { name: name.to_string(), id: id.to_string() }
+    }
+
+    /// Returns the emoji as a percent-encoded URL path segment, as the
+    /// reaction endpoints expect it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use Rust_Discord_API::emoji::ReactionType;
+    ///
+    /// assert_eq!(ReactionType::unicode("👍").path_segment(), "%F0%9F%91%8D");
+    /// assert_eq!(ReactionType::custom("blob", "41771983429993937").path_segment(), "blob%3A41771983429993937");
+    /// ```
+    pub fn path_segment(&self) -> String {
+        percent_encode(&self.to_string())
+    }
+}
+
+impl fmt::Display for ReactionType {
+    /// Formats the emoji as Discord names it in reaction URLs: the emoji
+    /// itself, or `name:id`.
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReactionType::Unicode(emoji) => formatter.write_str(emoji),
+            ReactionType::Custom { name, id } => write!(formatter, "{}:{}", name, id),
+        }
+    }
+}
+
+/// The error returned when a string is not a reaction emoji.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseReactionError(String);
+
+impl fmt::Display for ParseReactionError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "not a Unicode emoji or custom emoji: {:?}", self.0)
+    }
+}
+
+impl Error for ParseReactionError {}
+
+impl FromStr for ReactionType {
+    type Err = ParseReactionError;
+
+    /// Parses a Unicode emoji, a custom emoji as `name:id`, or a custom
+    /// emoji mention such as `<:name:id>` or `<a:name:id>`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use Rust_Discord_API::emoji::ReactionType;
+    ///
+    /// assert_eq!("👍".parse(), Ok(ReactionType::unicode("👍")));
+    /// assert_eq!("<a:blob:5>".parse(), Ok(ReactionType::custom("blob", "5")));
+    /// assert!(":thumbsup:".parse::<ReactionType>().is_err());
+    /// ```
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let error = || ParseReactionError(text.to_string());
+        let emoji = text.trim();
+        let custom = match emoji.strip_prefix('<').and_then(|mention| mention.strip_suffix('>')) {
+            Some(mention) => Some(mention.strip_prefix("a:").or_else(|| mention.strip_prefix(':')).ok_or_else(error)?),
+            None if emoji.contains(':') => Some(emoji),
+            None => None,
+        };
+        match custom {
+            Some(custom) => {
+                let (name, id) = custom.rsplit_once(':').ok_or_else(error)?;
+                if name.is_empty() || name.contains(':') || id.is_empty() || !id.bytes().all(|byte| byte.is_ascii_digit()) {
+                    return Err(error());
+                }
+                Ok(ReactionType::custom(name, id))
+            }
+            // Every Unicode emoji has a character outside ASCII, which rules
+            // out shortcodes and plain words.
+            None if !emoji.is_ascii() => Ok(ReactionType::unicode(emoji)),
+            None => Err(error()),
+        }
+    }
+}
+
+/// Whether a reaction is a normal reaction or a super reaction.
+#[derive(Serialize_repr, Deserialize_repr, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum ReactionKind {
+    #[default]
+    Normal = 0,
+    /// A super reaction, shown with an animation.
+    Burst = 1,
+}
+
+impl ReactionKind {
+    pub(crate) fn query_value(self) -> String {
+        (self as u8).to_string()
+    }
+}
+
+impl From<&Emoji> for ReactionType {
+    fn from(emoji: &Emoji) -> Self {
+        let name = emoji.name.clone().unwrap_or_else(|| "_".to_string());
+        match &emoji.id {
+            Some(id) => ReactionType::Custom { name, id: id.clone() },
+            None => ReactionType::Unicode(name),
+        }
+    }
+}
+
+impl From<&PartialEmoji> for ReactionType {
+    fn from(emoji: &PartialEmoji) -> Self {
+        let name = emoji.name.clone().unwrap_or_else(|| "_".to_string());
+        match &emoji.id {
+            Some(id) => ReactionType::Custom { name, id: id.clone() },
+            None => ReactionType::Unicode(name),
+        }
+    }
+}
+
+/// Encodes an image as the data URI Discord expects for uploads, detecting
+/// PNG, JPEG, GIF and WebP from the image's signature.
+///
+/// # Examples
+///
+/// ```
+/// use Rust_Discord_API::emoji::image_data_uri;
+///
+/// let png = b"\x89PNG\r\n\x1a\n";
+/// assert_eq!(image_data_uri(png).unwrap(), "data:image/png;base64,iVBORw0KGgo=");
+/// assert!(image_data_uri(b"plain text").is_err());
+/// ```
+pub fn image_data_uri(image: &[u8]) -> Result<String, Box<dyn Error>> {
+    let mime = if image.starts_with(b"\x89PNG\r\n\x1a\n") {
+        "image/png"
+    } else if image.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        "image/jpeg"
+    } else if image.starts_with(b"GIF87a") || image.starts_with(b"GIF89a") {
+        "image/gif"
+    } else if image.len() >= 12 && image.starts_with(b"RIFF") && &image[8..12] == b"WEBP" {
+        "image/webp"
+    } else {
+        return Err("image is not a PNG, JPEG, GIF or WebP".into());
+    };
+    Ok(format!("data:{};base64,{}", mime, STANDARD.encode(image)))
+}
+
+fn emoji_image(image: &[u8]) -> Result<String, Box<dyn Error>> {
+    if image.len() > MAX_EMOJI_SIZE {
+        return Err(format!("emoji image of {} bytes exceeds the {} byte limit", image.len(), MAX_EMOJI_SIZE).into());
+    }
+    image_data_uri(image)
+}
+
+/// Lists the custom emojis of a guild.
+///
+/// # Arguments
+///
+/// * `client` - The HTTP client used to send the request.
+/// * `token` - The bot token for authentication.
+/// * `guild_id` - The ID of the guild.
+///
+/// # Returns
+///
+/// A result containing the guild's emojis.
+#[allow(dead_code)]
+pub async fn list_guild_emojis(client: &Client, token: &str, guild_id: &str) -> Result<Vec<Emoji>, Box<dyn Error>> {
+    let url = format!("https://discord.com/api/v9/guilds/{}/emojis", guild_id);
+    let response: Vec<Emoji> = client.get(&url)
+        .bearer_auth(token)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    Ok(response)
+}
+
+/// Fetches a custom emoji of a guild.
+///
+/// # Arguments
+///
+/// * `client` - The HTTP client used to send the request.
+/// * `token` - The bot token for authentication.
+/// * `guild_id` - The ID of the guild.
+/// * `emoji_id` - The ID of the emoji.
+///
+/// # Returns
+///
+/// A result containing the emoji.
+#[allow(dead_code)]
+pub async fn get_guild_emoji(client: &Client, token: &str, guild_id: &str, emoji_id: &str) -> Result<Emoji, Box<dyn Error>> {
+    let url = format!("https://discord.com/api/v9/guilds/{}/emojis/{}", guild_id, emoji_id);
+    let response: Emoji = client.get(&url)
+        .bearer_auth(token)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    Ok(response)
+}
+
+/// Uploads a custom emoji to a guild. Needs the `CREATE_GUILD_EXPRESSIONS` permission.
+///
+/// # Arguments
+///
+/// * `client` - The HTTP client used to send the request.
+/// * `token` - The bot token for authentication.
+/// * `guild_id` - The ID of the guild.
+/// * `name` - The name of the emoji.
+/// * `image` - The PNG, JPEG, GIF or WebP image, at most `MAX_EMOJI_SIZE` bytes.
+/// * `roles` - The roles allowed to use the emoji, or none to allow everyone.
+/// * `reason` - The reason shown in the audit log, if any.
+///
+/// # Returns
+///
+/// A result containing the created emoji.
+#[allow(dead_code)]
+pub async fn create_guild_emoji(client: &Client, token: &str, guild_id: &str, name: &str, image: &[u8], roles: &[&str], reason: Option<&str>) -> Result<Emoji, Box<dyn Error>> {
+    let url = format!("https://discord.com/api/v9/guilds/{}/emojis", guild_id);
+    let body = json!({ "name": name, "image": emoji_image(image)?, "roles": roles });
+    let response: Emoji = client.post(&url)
+        .bearer_auth(token)
+        .audit_log_reason(reason)
+        .json(&body)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    Ok(response)
+}
+
+/// Renames a custom emoji of a guild or changes which roles may use it.
+///
+/// # Arguments
+///
+/// * `client` - The HTTP client used to send the request.
+/// * `token` - The bot token for authentication.
+/// * `guild_id` - The ID of the guild.
+/// * `emoji_id` - The ID of the emoji.
+/// * `name` - The new name, or `None` to keep it.
+/// * `roles` - The roles allowed to use the emoji, `Some(&[])` to allow everyone, or `None` to keep them.
+/// * `reason` - The reason shown in the audit log, if any.
+///
+/// # Returns
+///
+/// A result containing the modified emoji.
+#[allow(dead_code)]
+pub async fn modify_guild_emoji(client: &Client, token: &str, guild_id: &str, emoji_id: &str, name: Option<&str>, roles: Option<&[&str]>, reason: Option<&str>) -> Result<Emoji, Box<dyn Error>> {
+    let url = format!("https://discord.com/api/v9/guilds/{}/emojis/{}", guild_id, emoji_id);
+    let mut body = Map::new();
+    if let Some(name) = name {
+        body.insert("name".to_string(), json!(name));
+    }
+    if let Some(roles) = roles {
+        body.insert("roles".to_string(), json!(roles));
+    }
+    let response: Emoji = client.patch(&url)
+        .bearer_auth(token)
+        .audit_log_reason(reason)
+        .json(&body)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    Ok(response)
+}
+
+/// Deletes a custom emoji of a guild.
+///
+/// # Arguments
+///
+/// * `client` - The HTTP client used to send the request.
+/// * `token` - The bot token for authentication.
+/// * `guild_id` - The ID of the guild.
+/// * `emoji_id` - The ID of the emoji.
+/// * `reason` - The reason shown in the audit log, if any.
+///
+/// # Returns
+///
+/// A result indicating success or failure.
+#[allow(dead_code)]
+pub async fn delete_guild_emoji(client: &Client, token: &str, guild_id: &str, emoji_id: &str, reason: Option<&str>) -> Result<(), Box<dyn Error>> {
+    let url = format!("https://discord.com/api/v9/guilds/{}/emojis/{}", guild_id, emoji_id);
+
+    client.delete(&url)
+        .bearer_auth(token)
+        .audit_log_reason(reason)
+        .send()
+        .await?
+        .error_for_status()?;
+
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct ApplicationEmojis {
+    items: Vec<Emoji>,
+}
+
+/// Lists the emojis owned by an application, which it can use in any guild.
+///
+/// # Arguments
+///
+/// * `client` - The HTTP client used to send the request.
+/// * `token` - The bot token for authentication.
+/// * `application_id` - The ID of the application.
+///
+/// # Returns
+///
+/// A result containing the application's emojis.
+#[allow(dead_code)]
+pub async fn list_application_emojis(client: &Client, token: &str, application_id: &str) -> Result<Vec<Emoji>, Box<dyn Error>> {
+    let url = format!("https://discord.com/api/v9/applications/{}/emojis", application_id);
+    let response: ApplicationEmojis = client.get(&url)
+        .bearer_auth(token)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    Ok(response.items)
+}
+
+/// Fetches an emoji owned by an application.
+///
+/// # Arguments
+///
+/// * `client` - The HTTP client used to send the request.
+/// * `token` - The bot token for authentication.
+/// * `application_id` - The ID of the application.
+/// * `emoji_id` - The ID of the emoji.
+///
+/// # Returns
+///
+/// A result containing the emoji.
+#[allow(dead_code)]
+pub async fn get_application_emoji(client: &Client, token: &str, application_id: &str, emoji_id: &str) -> Result<Emoji, Box<dyn Error>> {
+    let url = format!("https://discord.com/api/v9/applications/{}/emojis/{}", application_id, emoji_id);
+    let response: Emoji = client.get(&url)
+        .bearer_auth(token)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    Ok(response)
+}
+
+/// Uploads an emoji owned by an application.
+///
+/// # Arguments
+///
+/// * `client` - The HTTP client used to send the request.
+/// * `token` - The bot token for authentication.
+/// * `application_id` - The ID of the application.
+/// * `name` - The name of the emoji.
+/// * `image` - The PNG, JPEG, GIF or WebP image, at most `MAX_EMOJI_SIZE` bytes.
+///
+/// # Returns
+///
+/// A result containing the created emoji.
+#[allow(dead_code)]
+pub async fn create_application_emoji(client: &Client, token: &str, application_id: &str, name: &str, image: &[u8]) -> Result<Emoji, Box<dyn Error>> {
+    let url = format!("https://discord.com/api/v9/applications/{}/emojis", application_id);
+    let body = json!({ "name": name, "image": emoji_image(image)? });
+    let response: Emoji = client.post(&url)
+        .bearer_auth(token)
+        .json(&body)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    Ok(response)
+}
+
+/// Renames an emoji owned by an application.
+///
+/// # Arguments
+///
+/// * `client` - The HTTP client used to send the request.
+/// * `token` - The bot token for authentication.
+/// * `application_id` - The ID of the application.
+/// * `emoji_id` - The ID of the emoji.
+/// * `name` - The new name.
+///
+/// # Returns
+///
+/// A result containing the modified emoji.
+#[allow(dead_code)]
+pub async fn modify_application_emoji(client: &Client, token: &str, application_id: &str, emoji_id: &str, name: &str) -> Result<Emoji, Box<dyn Error>> {
+    let url = format!("https://discord.com/api/v9/applications/{}/emojis/{}", application_id, emoji_id);
+    let response: Emoji = client.patch(&url)
+        .bearer_auth(token)
+        .json(&json!({ "name": name }))
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    Ok(response)
+}
+
+/// Deletes an emoji owned by an application.
+///
+/// # Arguments
+///
+/// * `client` - The HTTP client used to send the request.
+/// * `token` - The bot token for authentication.
+/// * `application_id` - The ID of the application.
+/// * `emoji_id` - The ID of the emoji.
+///
+/// # Returns
+///
+/// A result indicating success or failure.
+#[allow(dead_code)]
+pub async fn delete_application_emoji(client: &Client, token: &str, application_id: &str, emoji_id: &str) -> Result<(), Box<dyn Error>> {
+    let url = format!("https://discord.com/api/v9/applications/{}/emojis/{}", application_id, emoji_id);
+
+    client.delete(&url)
+        .bearer_auth(token)
+        .send()
+        .await?
+        .error_for_status()?;
+
+    Ok(())
+}
@@ -1,6 +1,15 @@
-use reqwest::Client;
+use futures::stream::BoxStream;
+use reqwest::{Client, StatusCode};
 use serde_json::Value;
 use std::error::Error;
+use std::time::{Duration, SystemTime};
+
+use crate::model::Member;
+
+use super::audit_log::AuditLogReason;
+use super::invite::{Invite, VanityUrl};
+use super::pagination::{self, Endpoint, PageResult, PaginationOptions};
+use super::timestamp::format_timestamp;
 
 /// Creates a new Discord guild.
 ///
@@ -83,16 +92,18 @@ pub async fn get_guild_preview(client: &Client, token: &str, guild_id: &str) ->
 /// * `token` - The bot token for authentication.
 /// * `guild_id` - The ID of the guild to modify.
 /// * `settings` - The JSON value of the settings to update.
+/// * `reason` - The reason shown in the audit log, if any.
 ///
 /// # Returns
 ///
 /// A result indicating success or failure.
 #[allow(dead_code)]
-pub async fn modify_guild(client: &Client, token: &str, guild_id: &str, settings: Value) -> Result<(), Box<dyn Error>> {
+pub async fn modify_guild(client: &Client, token: &str, guild_id: &str, settings: Value, reason: Option<&str>) -> Result<(), Box<dyn Error>> {
     let url = format!("https://discord.com/api/v9/guilds/{}", guild_id);
     
     client.patch(&url)
         .bearer_auth(token)
+        .audit_log_reason(reason)
         .json(&settings)
         .send()
         .await?
@@ -157,15 +168,17 @@ pub async fn get_guild_channels(client: &Client, token: &str, guild_id: &str) ->
 /// * `token` - The bot token for authentication.
 /// * `guild_id` - The ID of the guild to create the channel in.
 /// * `channel_settings` - The JSON value of the channel settings.
+/// * `reason` - The reason shown in the audit log, if any.
 ///
 /// # Returns
 ///
 /// A result containing the created channel information as a JSON value.
 #[allow(dead_code)]
-pub async fn create_guild_channel(client: &Client, token: &str, guild_id: &str, channel_settings: Value) -> Result<Value, Box<dyn Error>> {
+pub async fn create_guild_channel(client: &Client, token: &str, guild_id: &str, channel_settings: Value, reason: Option<&str>) -> Result<Value, Box<dyn Error>> {
     let url = format!("https://discord.com/api/v9/guilds/{}/channels", guild_id);
     let response: Value = client.post(&url)
         .bearer_auth(token)
+        .audit_log_reason(reason)
         .json(&channel_settings)
         .send()
         .await?
@@ -183,16 +196,18 @@ pub async fn create_guild_channel(client: &Client, token: &str, guild_id: &str,
 /// * `token` - The bot token for authentication.
 /// * `guild_id` - The ID of the guild to modify channel positions for.
 /// * `positions` - The JSON value of the new positions.
+/// * `reason` - The reason shown in the audit log, if any.
 ///
 /// # Returns
 ///
 /// A result indicating success or failure.
 #[allow(dead_code)]
-pub async fn modify_guild_channel_positions(client: &Client, token: &str, guild_id: &str, positions: Value) -> Result<(), Box<dyn Error>> {
+pub async fn modify_guild_channel_positions(client: &Client, token: &str, guild_id: &str, positions: Value, reason: Option<&str>) -> Result<(), Box<dyn Error>> {
     let url = format!("https://discord.com/api/v9/guilds/{}/channels", guild_id);
     
     client.patch(&url)
         .bearer_auth(token)
+        .audit_log_reason(reason)
         .json(&positions)
         .send()
         .await?
@@ -274,6 +289,32 @@ pub async fn list_guild_members(client: &Client, token: &str, guild_id: &str) ->
     Ok(response)
 }
 
+/// Streams the members of a Discord guild, following the `after` cursor.
+///
+/// # Arguments
+///
+/// * `client` - The HTTP client used to send the requests.
+/// * `token` - The bot token for authentication.
+/// * `guild_id` - The ID of the guild to list members for.
+/// * `options` - The starting user and maximum number of members. Only `Direction::After` is supported.
+///
+/// # Returns
+///
+/// A stream of guild members as JSON values.
+#[allow(dead_code)]
+pub fn stream_guild_members(client: &Client, token: &str, guild_id: &str, options: PaginationOptions) -> BoxStream<'static, PageResult> {
+    let endpoint = Endpoint {
+        url: format!("https://discord.com/api/v9/guilds/{}/members", guild_id),
+        query: Vec::new(),
+        max_page_size: 1000,
+        supports_before: false,
+        items: pagination::array_items,
+        id: pagination::user_id,
+    };
+
+    pagination::paginate(client, token, endpoint, options)
+}
+
 /// Searches for members in a Discord guild.
 ///
 /// # Arguments
@@ -335,16 +376,18 @@ pub async fn add_guild_member(client: &Client, token: &str, guild_id: &str, user
 /// * `guild_id` - The ID of the guild.
 /// * `user_id` - The ID of the member to modify.
 /// * `settings` - The JSON value of the settings to update.
+/// * `reason` - The reason shown in the audit log, if any.
 ///
 /// # Returns
 ///
 /// A result indicating success or failure.
 #[allow(dead_code)]
-pub async fn modify_guild_member(client: &Client, token: &str, guild_id: &str, user_id: &str, settings: Value) -> Result<(), Box<dyn Error>> {
+pub async fn modify_guild_member(client: &Client, token: &str, guild_id: &str, user_id: &str, settings: Value, reason: Option<&str>) -> Result<(), Box<dyn Error>> {
     let url = format!("https://discord.com/api/v9/guilds/{}/members/{}", guild_id, user_id);
     
     client.patch(&url)
         .bearer_auth(token)
+        .audit_log_reason(reason)
         .json(&settings)
         .send()
         .await?
@@ -353,6 +396,69 @@ pub async fn modify_guild_member(client: &Client, token: &str, guild_id: &str, u
     Ok(())
 }
 
+/// The longest a member can be timed out for.
+pub const MAX_TIMEOUT: Duration = Duration::from_secs(28 * 24 * 60 * 60);
+
+/// Times out a member in a Discord guild, preventing them from sending
+/// messages, reacting and joining voice channels until the timeout ends.
+///
+/// # Arguments
+///
+/// * `client` - The HTTP client used to send the request.
+/// * `token` - The bot token for authentication.
+/// * `guild_id` - The ID of the guild.
+/// * `user_id` - The ID of the member to time out.
+/// * `duration` - How long the timeout lasts, at most `MAX_TIMEOUT`.
+/// * `reason` - The reason shown in the audit log, if any.
+///
+/// # Returns
+///
+/// A result containing the updated member, or an error if the duration is zero or longer than `MAX_TIMEOUT`.
+#[allow(dead_code)]
+pub async fn timeout_member(client: &Client, token: &str, guild_id: &str, user_id: &str, duration: Duration, reason: Option<&str>) -> Result<Member, Box<dyn Error>> {
+    if duration.is_zero() {
+        return Err("timeout duration must be positive; use remove_timeout to end a timeout".into());
+    }
+    if duration > MAX_TIMEOUT {
+        return Err(format!("timeout duration of {}s exceeds the 28 day maximum", duration.as_secs()).into());
+    }
+    let until = format_timestamp(SystemTime::now() + duration);
+    set_communication_disabled_until(client, token, guild_id, user_id, Value::String(until), reason).await
+}
+
+/// Removes a member's timeout in a Discord guild.
+///
+/// # Arguments
+///
+/// * `client` - The HTTP client used to send the request.
+/// * `token` - The bot token for authentication.
+/// * `guild_id` - The ID of the guild.
+/// * `user_id` - The ID of the member whose timeout to remove.
+/// * `reason` - The reason shown in the audit log, if any.
+///
+/// # Returns
+///
+/// A result containing the updated member.
+#[allow(dead_code)]
+pub async fn remove_timeout(client: &Client, token: &str, guild_id: &str, user_id: &str, reason: Option<&str>) -> Result<Member, Box<dyn Error>> {
+    set_communication_disabled_until(client, token, guild_id, user_id, Value::Null, reason).await
+}
+
+async fn set_communication_disabled_until(client: &Client, token: &str, guild_id: &str, user_id: &str, until: Value, reason: Option<&str>) -> Result<Member, Box<dyn Error>> {
+    let url = format!("https://discord.com/api/v9/guilds/{}/members/{}", guild_id, user_id);
+    let member = client.patch(&url)
+        .bearer_auth(token)
+        .audit_log_reason(reason)
+        .json(&serde_json::json!({ "communication_disabled_until": until }))
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    Ok(member)
+}
+
 /// Modifies the current member in a Discord guild.
 ///
 /// # Arguments
@@ -361,16 +467,18 @@ pub async fn modify_guild_member(client: &Client, token: &str, guild_id: &str, u
 /// * `token` - The bot token for authentication.
 /// * `guild_id` - The ID of the guild.
 /// * `settings` - The JSON value of the settings to update.
+/// * `reason` - The reason shown in the audit log, if any.
 ///
 /// # Returns
 ///
 /// A result indicating success or failure.
 #[allow(dead_code)]
-pub async fn modify_current_member(client: &Client, token: &str, guild_id: &str, settings: Value) -> Result<(), Box<dyn Error>> {
+pub async fn modify_current_member(client: &Client, token: &str, guild_id: &str, settings: Value, reason: Option<&str>) -> Result<(), Box<dyn Error>> {
     let url = format!("https://discord.com/api/v9/guilds/{}/members/@me", guild_id);
     
     client.patch(&url)
         .bearer_auth(token)
+        .audit_log_reason(reason)
         .json(&settings)
         .send()
         .await?
@@ -387,17 +495,19 @@ pub async fn modify_current_member(client: &Client, token: &str, guild_id: &str,
 /// * `token` - The bot token for authentication.
 /// * `guild_id` - The ID of the guild.
 /// * `nick` - The new nickname.
+/// * `reason` - The reason shown in the audit log, if any.
 ///
 /// # Returns
 ///
 /// A result indicating success or failure.
 #[allow(dead_code)]
-pub async fn modify_current_user_nick(client: &Client, token: &str, guild_id: &str, nick: &str) -> Result<(), Box<dyn Error>> {
+pub async fn modify_current_user_nick(client: &Client, token: &str, guild_id: &str, nick: &str, reason: Option<&str>) -> Result<(), Box<dyn Error>> {
     let url = format!("https://discord.com/api/v9/guilds/{}/members/@me/nick", guild_id);
     let body = serde_json::json!({ "nick": nick });
     
     client.patch(&url)
         .bearer_auth(token)
+        .audit_log_reason(reason)
         .json(&body)
         .send()
         .await?
@@ -415,16 +525,18 @@ pub async fn modify_current_user_nick(client: &Client, token: &str, guild_id: &s
 /// * `guild_id` - The ID of the guild.
 /// * `user_id` - The ID of the member to add the role to.
 /// * `role_id` - The ID of the role to add.
+/// * `reason` - The reason shown in the audit log, if any.
 ///
 /// # Returns
 ///
 /// A result indicating success or failure.
 #[allow(dead_code)]
-pub async fn add_guild_member_role(client: &Client, token: &str, guild_id: &str, user_id: &str, role_id: &str) -> Result<(), Box<dyn Error>> {
+pub async fn add_guild_member_role(client: &Client, token: &str, guild_id: &str, user_id: &str, role_id: &str, reason: Option<&str>) -> Result<(), Box<dyn Error>> {
     let url = format!("https://discord.com/api/v9/guilds/{}/members/{}/roles/{}", guild_id, user_id, role_id);
     
     client.put(&url)
         .bearer_auth(token)
+        .audit_log_reason(reason)
         .send()
         .await?
         .error_for_status()?;
@@ -441,16 +553,18 @@ pub async fn add_guild_member_role(client: &Client, token: &str, guild_id: &str,
 /// * `guild_id` - The ID of the guild.
 /// * `user_id` - The ID of the member to remove the role from.
 /// * `role_id` - The ID of the role to remove.
+/// * `reason` - The reason shown in the audit log, if any.
 ///
 /// # Returns
 ///
 /// A result indicating success or failure.
 #[allow(dead_code)]
-pub async fn remove_guild_member_role(client: &Client, token: &str, guild_id: &str, user_id: &str, role_id: &str) -> Result<(), Box<dyn Error>> {
+pub async fn remove_guild_member_role(client: &Client, token: &str, guild_id: &str, user_id: &str, role_id: &str, reason: Option<&str>) -> Result<(), Box<dyn Error>> {
     let url = format!("https://discord.com/api/v9/guilds/{}/members/{}/roles/{}", guild_id, user_id, role_id);
     
     client.delete(&url)
         .bearer_auth(token)
+        .audit_log_reason(reason)
         .send()
         .await?
         .error_for_status()?;
@@ -466,16 +580,18 @@ pub async fn remove_guild_member_role(client: &Client, token: &str, guild_id: &s
 /// * `token` - The bot token for authentication.
 /// * `guild_id` - The ID of the guild.
 /// * `user_id` - The ID of the member to remove.
+/// * `reason` - The reason shown in the audit log, if any.
 ///
 /// # Returns
 ///
 /// A result indicating success or failure.
 #[allow(dead_code)]
-pub async fn remove_guild_member(client: &Client, token: &str, guild_id: &str, user_id: &str) -> Result<(), Box<dyn Error>> {
+pub async fn remove_guild_member(client: &Client, token: &str, guild_id: &str, user_id: &str, reason: Option<&str>) -> Result<(), Box<dyn Error>> {
     let url = format!("https://discord.com/api/v9/guilds/{}/members/{}", guild_id, user_id);
     
     client.delete(&url)
         .bearer_auth(token)
+        .audit_log_reason(reason)
         .send()
         .await?
         .error_for_status()?;
@@ -507,6 +623,32 @@ pub async fn get_guild_bans(client: &Client, token: &str, guild_id: &str) -> Res
     Ok(response)
 }
 
+/// Streams the bans of a Discord guild, following the `before`/`after` cursors.
+///
+/// # Arguments
+///
+/// * `client` - The HTTP client used to send the requests.
+/// * `token` - The bot token for authentication.
+/// * `guild_id` - The ID of the guild to fetch bans for.
+/// * `options` - The direction, starting user and maximum number of bans.
+///
+/// # Returns
+///
+/// A stream of bans as JSON values.
+#[allow(dead_code)]
+pub fn stream_guild_bans(client: &Client, token: &str, guild_id: &str, options: PaginationOptions) -> BoxStream<'static, PageResult> {
+    let endpoint = Endpoint {
+        url: format!("https://discord.com/api/v9/guilds/{}/bans", guild_id),
+        query: Vec::new(),
+        max_page_size: 1000,
+        supports_before: true,
+        items: pagination::array_items,
+        id: pagination::user_id,
+    };
+
+    pagination::paginate(client, token, endpoint, options)
+}
+
 /// Fetches a specific ban in a Discord guild.
 ///
 /// # Arguments
@@ -541,16 +683,18 @@ pub async fn get_guild_ban(client: &Client, token: &str, guild_id: &str, user_id
 /// * `guild_id` - The ID of the guild.
 /// * `user_id` - The ID of the user to ban.
 /// * `ban_settings` - The JSON value of the ban settings.
+/// * `reason` - The reason shown in the audit log, if any.
 ///
 /// # Returns
 ///
 /// A result indicating success or failure.
 #[allow(dead_code)]
-pub async fn create_guild_ban(client: &Client, token: &str, guild_id: &str, user_id: &str, ban_settings: Value) -> Result<(), Box<dyn Error>> {
+pub async fn create_guild_ban(client: &Client, token: &str, guild_id: &str, user_id: &str, ban_settings: Value, reason: Option<&str>) -> Result<(), Box<dyn Error>> {
     let url = format!("https://discord.com/api/v9/guilds/{}/bans/{}", guild_id, user_id);
     
     client.put(&url)
         .bearer_auth(token)
+        .audit_log_reason(reason)
         .json(&ban_settings)
         .send()
         .await?
@@ -567,16 +711,18 @@ pub async fn create_guild_ban(client: &Client, token: &str, guild_id: &str, user
 /// * `token` - The bot token for authentication.
 /// * `guild_id` - The ID of the guild.
 /// * `user_id` - The ID of the user to unban.
+/// * `reason` - The reason shown in the audit log, if any.
 ///
 /// # Returns
 ///
 /// A result indicating success or failure.
 #[allow(dead_code)]
-pub async fn remove_guild_ban(client: &Client, token: &str, guild_id: &str, user_id: &str) -> Result<(), Box<dyn Error>> {
+pub async fn remove_guild_ban(client: &Client, token: &str, guild_id: &str, user_id: &str, reason: Option<&str>) -> Result<(), Box<dyn Error>> {
     let url = format!("https://discord.com/api/v9/guilds/{}/bans/{}", guild_id, user_id);
     
     client.delete(&url)
         .bearer_auth(token)
+        .audit_log_reason(reason)
         .send()
         .await?
         .error_for_status()?;
@@ -592,17 +738,19 @@ pub async fn remove_guild_ban(client: &Client, token: &str, guild_id: &str, user
 /// * `token` - The bot token for authentication.
 /// * `guild_id` - The ID of the guild.
 /// * `user_ids` - A list of user IDs to ban.
+/// * `reason` - The reason shown in the audit log, if any.
 ///
 /// # Returns
 ///
 /// A result indicating success or failure.
 #[allow(dead_code)]
-pub async fn bulk_guild_ban(client: &Client, token: &str, guild_id: &str, user_ids: Vec<&str>) -> Result<(), Box<dyn Error>> {
+pub async fn bulk_guild_ban(client: &Client, token: &str, guild_id: &str, user_ids: Vec<&str>, reason: Option<&str>) -> Result<(), Box<dyn Error>> {
     let url = format!("https://discord.com/api/v9/guilds/{}/bans", guild_id);
     let body = serde_json::json!({ "user_ids": user_ids });
     
     client.post(&url)
         .bearer_auth(token)
+        .audit_log_reason(reason)
         .json(&body)
         .send()
         .await?
@@ -643,15 +791,17 @@ pub async fn get_guild_roles(client: &Client, token: &str, guild_id: &str) -> Re
 /// * `token` - The bot token for authentication.
 /// * `guild_id` - The ID of the guild to create the role in.
 /// * `role_settings` - The JSON value of the role settings.
+/// * `reason` - The reason shown in the audit log, if any.
 ///
 /// # Returns
 ///
 /// A result containing the created role information as a JSON value.
 #[allow(dead_code)]
-pub async fn create_guild_role(client: &Client, token: &str, guild_id: &str, role_settings: Value) -> Result<Value, Box<dyn Error>> {
+pub async fn create_guild_role(client: &Client, token: &str, guild_id: &str, role_settings: Value, reason: Option<&str>) -> Result<Value, Box<dyn Error>> {
     let url = format!("https://discord.com/api/v9/guilds/{}/roles", guild_id);
     let response: Value = client.post(&url)
         .bearer_auth(token)
+        .audit_log_reason(reason)
         .json(&role_settings)
         .send()
         .await?
@@ -669,16 +819,18 @@ pub async fn create_guild_role(client: &Client, token: &str, guild_id: &str, rol
 /// * `token` - The bot token for authentication.
 /// * `guild_id` - The ID of the guild to modify role positions for.
 /// * `positions` - The JSON value of the new positions.
+/// * `reason` - The reason shown in the audit log, if any.
 ///
 /// # Returns
 ///
 /// A result indicating success or failure.
 #[allow(dead_code)]
-pub async fn modify_guild_role_positions(client: &Client, token: &str, guild_id: &str, positions: Value) -> Result<(), Box<dyn Error>> {
+pub async fn modify_guild_role_positions(client: &Client, token: &str, guild_id: &str, positions: Value, reason: Option<&str>) -> Result<(), Box<dyn Error>> {
     let url = format!("https://discord.com/api/v9/guilds/{}/roles", guild_id);
     
     client.patch(&url)
         .bearer_auth(token)
+        .audit_log_reason(reason)
         .json(&positions)
         .send()
         .await?
@@ -696,16 +848,18 @@ pub async fn modify_guild_role_positions(client: &Client, token: &str, guild_id:
 /// * `guild_id` - The ID of the guild.
 /// * `role_id` - The ID of the role to modify.
 /// * `settings` - The JSON value of the settings to update.
+/// * `reason` - The reason shown in the audit log, if any.
 ///
 /// # Returns
 ///
 /// A result indicating success or failure.
 #[allow(dead_code)]
-pub async fn modify_guild_role(client: &Client, token: &str, guild_id: &str, role_id: &str, settings: Value) -> Result<(), Box<dyn Error>> {
+pub async fn modify_guild_role(client: &Client, token: &str, guild_id: &str, role_id: &str, settings: Value, reason: Option<&str>) -> Result<(), Box<dyn Error>> {
     let url = format!("https://discord.com/api/v9/guilds/{}/roles/{}", guild_id, role_id);
     
     client.patch(&url)
         .bearer_auth(token)
+        .audit_log_reason(reason)
         .json(&settings)
         .send()
         .await?
@@ -722,17 +876,19 @@ pub async fn modify_guild_role(client: &Client, token: &str, guild_id: &str, rol
 /// * `token` - The bot token for authentication.
 /// * `guild_id` - The ID of the guild.
 /// * `level` - The new MFA level.
+/// * `reason` - The reason shown in the audit log, if any.
 ///
 /// # Returns
 ///
 /// A result indicating success or failure.
 #[allow(dead_code)]
-pub async fn modify_guild_mfa_level(client: &Client, token: &str, guild_id: &str, level: u8) -> Result<(), Box<dyn Error>> {
+pub async fn modify_guild_mfa_level(client: &Client, token: &str, guild_id: &str, level: u8, reason: Option<&str>) -> Result<(), Box<dyn Error>> {
     let url = format!("https://discord.com/api/v9/guilds/{}/mfa", guild_id);
     let body = serde_json::json!({ "level": level });
     
     client.post(&url)
         .bearer_auth(token)
+        .audit_log_reason(reason)
         .json(&body)
         .send()
         .await?
@@ -749,16 +905,18 @@ pub async fn modify_guild_mfa_level(client: &Client, token: &str, guild_id: &str
 /// * `token` - The bot token for authentication.
 /// * `guild_id` - The ID of the guild.
 /// * `role_id` - The ID of the role to delete.
+/// * `reason` - The reason shown in the audit log, if any.
 ///
 /// # Returns
 ///
 /// A result indicating success or failure.
 #[allow(dead_code)]
-pub async fn delete_guild_role(client: &Client, token: &str, guild_id: &str, role_id: &str) -> Result<(), Box<dyn Error>> {
+pub async fn delete_guild_role(client: &Client, token: &str, guild_id: &str, role_id: &str, reason: Option<&str>) -> Result<(), Box<dyn Error>> {
     let url = format!("https://discord.com/api/v9/guilds/{}/roles/{}", guild_id, role_id);
     
     client.delete(&url)
         .bearer_auth(token)
+        .audit_log_reason(reason)
         .send()
         .await?
         .error_for_status()?;
@@ -799,16 +957,18 @@ pub async fn get_guild_prune_count(client: &Client, token: &str, guild_id: &str,
 /// * `token` - The bot token for authentication.
 /// * `guild_id` - The ID of the guild to prune members in.
 /// * `days` - The number of days to count members without activity.
+/// * `reason` - The reason shown in the audit log, if any.
 ///
 /// # Returns
 ///
 /// A result containing the prune count as a JSON value.
 #[allow(dead_code)]
-pub async fn begin_guild_prune(client: &Client, token: &str, guild_id: &str, days: u8) -> Result<Value, Box<dyn Error>> {
+pub async fn begin_guild_prune(client: &Client, token: &str, guild_id: &str, days: u8, reason: Option<&str>) -> Result<Value, Box<dyn Error>> {
     let url = format!("https://discord.com/api/v9/guilds/{}/prune", guild_id);
     let body = serde_json::json!({ "days": days });
     let response: Value = client.post(&url)
         .bearer_auth(token)
+        .audit_log_reason(reason)
         .json(&body)
         .send()
         .await?
@@ -852,14 +1012,15 @@ pub async fn get_guild_voice_regions(client: &Client, token: &str, guild_id: &st
 ///
 /// # Returns
 ///
-/// A result containing the guild invites as a JSON value.
+/// A result containing the guild invites, with their use counts.
 #[allow(dead_code)]
-pub async fn get_guild_invites(client: &Client, token: &str, guild_id: &str) -> Result<Value, Box<dyn Error>> {
+pub async fn get_guild_invites(client: &Client, token: &str, guild_id: &str) -> Result<Vec<Invite>, Box<dyn Error>> {
     let url = format!("https://discord.com/api/v9/guilds/{}/invites", guild_id);
-    let response: Value = client.get(&url)
+    let response: Vec<Invite> = client.get(&url)
         .bearer_auth(token)
         .send()
         .await?
+        .error_for_status()?
         .json()
         .await?;
     
@@ -898,16 +1059,18 @@ pub async fn get_guild_integrations(client: &Client, token: &str, guild_id: &str
 /// * `token` - The bot token for authentication.
 /// * `guild_id` - The ID of the guild.
 /// * `integration_id` - The ID of the integration to delete.
+/// * `reason` - The reason shown in the audit log, if any.
 ///
 /// # Returns
 ///
 /// A result indicating success or failure.
 #[allow(dead_code)]
-pub async fn delete_guild_integration(client: &Client, token: &str, guild_id: &str, integration_id: &str) -> Result<(), Box<dyn Error>> {
+pub async fn delete_guild_integration(client: &Client, token: &str, guild_id: &str, integration_id: &str, reason: Option<&str>) -> Result<(), Box<dyn Error>> {
     let url = format!("https://discord.com/api/v9/guilds/{}/integrations/{}", guild_id, integration_id);
     
     client.delete(&url)
         .bearer_auth(token)
+        .audit_log_reason(reason)
         .send()
         .await?
         .error_for_status()?;
@@ -947,16 +1110,18 @@ pub async fn get_guild_widget_settings(client: &Client, token: &str, guild_id: &
 /// * `token` - The bot token for authentication.
 /// * `guild_id` - The ID of the guild to modify widget settings for.
 /// * `settings` - The JSON value of the settings to update.
+/// * `reason` - The reason shown in the audit log, if any.
 ///
 /// # Returns
 ///
 /// A result indicating success or failure.
 #[allow(dead_code)]
-pub async fn modify_guild_widget_settings(client: &Client, token: &str, guild_id: &str, settings: Value) -> Result<(), Box<dyn Error>> {
+pub async fn modify_guild_widget_settings(client: &Client, token: &str, guild_id: &str, settings: Value, reason: Option<&str>) -> Result<(), Box<dyn Error>> {
     let url = format!("https://discord.com/api/v9/guilds/{}/widget", guild_id);
     
     client.patch(&url)
         .bearer_auth(token)
+        .audit_log_reason(reason)
         .json(&settings)
         .send()
         .await?
@@ -989,6 +1154,9 @@ pub async fn get_guild_widget(client: &Client, token: &str, guild_id: &str) -> R
     Ok(response)
 }
 
+/// The JSON error code of "Missing Access".
+const MISSING_ACCESS: u64 = 50001;
+
 /// Fetches the vanity URL of a Discord guild.
 ///
 /// # Arguments
@@ -999,17 +1167,41 @@ pub async fn get_guild_widget(client: &Client, token: &str, guild_id: &str) -> R
 ///
 /// # Returns
 ///
-/// A result containing the vanity URL as a JSON value.
+/// A result containing the vanity invite code and its use count. The code
+/// is `None` when the guild doesn't have the `VANITY_URL` feature. Fails
+/// without the `MANAGE_GUILD` permission.
 #[allow(dead_code)]
-pub async fn get_guild_vanity_url(client: &Client, token: &str, guild_id: &str) -> Result<Value, Box<dyn Error>> {
+pub async fn get_guild_vanity_url(client: &Client, token: &str, guild_id: &str) -> Result<VanityUrl, Box<dyn Error>> {
     let url = format!("https://discord.com/api/v9/guilds/{}/vanity-url", guild_id);
-    let response: Value = client.get(&url)
+    let response = client.get(&url)
         .bearer_auth(token)
         .send()
-        .await?
-        .json()
         .await?;
-    
+
+    // Guilds without the VANITY_URL feature answer with "Missing Access", but
+    // so does a guild the bot can't access, so check which one it is.
+    if response.status() == StatusCode::FORBIDDEN {
+        let error = response.error_for_status_ref().unwrap_err();
+        let body: Value = response.json().await.unwrap_or_default();
+        if body["code"] != MISSING_ACCESS {
+            return Err(error.into());
+        }
+        let url = format!("https://discord.com/api/v9/guilds/{}", guild_id);
+        let guild: Value = client.get(&url)
+            .bearer_auth(token)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let has_vanity_url = guild["features"].as_array().is_some_and(|features| features.iter().any(|feature| feature == "VANITY_URL"));
+        if has_vanity_url {
+            return Err(error.into());
+        }
+        return Ok(VanityUrl { code: None, uses: 0 });
+    }
+    let response: VanityUrl = response.error_for_status()?.json().await?;
+
     Ok(response)
 }
 
@@ -1069,16 +1261,18 @@ pub async fn get_guild_welcome_screen(client: &Client, token: &str, guild_id: &s
 /// * `token` - The bot token for authentication.
 /// * `guild_id` - The ID of the guild to modify the welcome screen for.
 /// * `settings` - The JSON value of the settings to update.
+/// * `reason` - The reason shown in the audit log, if any.
 ///
 /// # Returns
 ///
 /// A result indicating success or failure.
 #[allow(dead_code)]
-pub async fn modify_guild_welcome_screen(client: &Client, token: &str, guild_id: &str, settings: Value) -> Result<(), Box<dyn Error>> {
+pub async fn modify_guild_welcome_screen(client: &Client, token: &str, guild_id: &str, settings: Value, reason: Option<&str>) -> Result<(), Box<dyn Error>> {
     let url = format!("https://discord.com/api/v9/guilds/{}/welcome-screen", guild_id);
     
     client.patch(&url)
         .bearer_auth(token)
+        .audit_log_reason(reason)
         .json(&settings)
         .send()
         .await?
@@ -1119,16 +1313,18 @@ pub async fn get_guild_onboarding(client: &Client, token: &str, guild_id: &str)
 /// * `token` - The bot token for authentication.
 /// * `guild_id` - The ID of the guild to modify the onboarding settings for.
 /// * `settings` - The JSON value of the settings to update.
+/// * `reason` - The reason shown in the audit log, if any.
 ///
 /// # Returns
 ///
 /// A result indicating success or failure.
 #[allow(dead_code)]
-pub async fn modify_guild_onboarding(client: &Client, token: &str, guild_id: &str, settings: Value) -> Result<(), Box<dyn Error>> {
+pub async fn modify_guild_onboarding(client: &Client, token: &str, guild_id: &str, settings: Value, reason: Option<&str>) -> Result<(), Box<dyn Error>> {
     let url = format!("https://discord.com/api/v9/guilds/{}/onboarding", guild_id);
     
     client.put(&url)
         .bearer_auth(token)
+        .audit_log_reason(reason)
         .json(&settings)
         .send()
         .await?
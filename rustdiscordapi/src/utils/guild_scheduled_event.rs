@@ -1,28 +1,539 @@
+use futures::stream::BoxStream;
 use reqwest::Client;
-use serde_json::Value;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use serde_repr::{Deserialize_repr, Serialize_repr};
 use std::error::Error;
+use std::time::SystemTime;
+
+use crate::model::User;
+
+use super::audit_log::AuditLogReason;
+use super::emoji::image_data_uri;
+use super::pagination::{self, Endpoint, PageResult, PaginationOptions};
+use super::stage_instance::PrivacyLevel;
+use super::timestamp::{format_timestamp, parse_timestamp};
+
+/// The longest name a scheduled event can have, in characters.
+pub const MAX_NAME_LENGTH: usize = 100;
+/// The longest description a scheduled event can have, in characters.
+pub const MAX_DESCRIPTION_LENGTH: usize = 1000;
+/// The longest location an external event can have, in characters.
+pub const MAX_LOCATION_LENGTH: usize = 100;
+
+/// Where a scheduled event is hosted.
+#[derive(Serialize_repr, Deserialize_repr, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum EntityType {
+    StageInstance = 1,
+    Voice = 2,
+    External = 3,
+}
+
+/// The lifecycle of a scheduled event.
+///
+/// An event moves from `Scheduled` to `Active` or `Canceled`, and from
+/// `Active` to `Completed`. `Completed` and `Canceled` are final.
+#[derive(Serialize_repr, Deserialize_repr, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum ScheduledEventStatus {
+    Scheduled = 1,
+    Active = 2,
+    Completed = 3,
+    Canceled = 4,
+}
+
+impl ScheduledEventStatus {
+    /// Returns whether an event with this status can be moved to `next`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use Rust_Discord_API::guild_scheduled_event::ScheduledEventStatus::*;
+    ///
+    /// assert!(Scheduled.can_transition_to(Active));
+    /// assert!(Active.can_transition_to(Completed));
+    /// assert!(!Active.can_transition_to(Canceled));
+    /// assert!(!Completed.can_transition_to(Active));
+    /// ```
+    pub fn can_transition_to(self, next: ScheduledEventStatus) -> bool {
+        use ScheduledEventStatus::*;
+        matches!((self, next), (Scheduled, Active) | (Scheduled, Canceled) | (Active, Completed))
+    }
+}
+
+/// Extra details of an event's entity.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct EntityMetadata {
+    /// Where an external event takes place.
+    #[serde(default)]
+    pub location: Option<String>,
+}
+
+/// Where a scheduled event is hosted, together with the details its entity
+/// type needs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EventEntity {
+    /// A stage in a stage channel.
+    StageInstance { channel_id: String },
+    /// A voice channel.
+    Voice { channel_id: String },
+    /// Somewhere outside Discord, such as a link or an address.
+    External { location: String },
+}
+
+impl EventEntity {
+    /// Returns the entity type of the entity.
+    pub fn kind(&self) -> EntityType {
+        match self {
+            EventEntity::StageInstance { .. } => EntityType::StageInstance,
+            EventEntity::Voice { .. } => EntityType::Voice,
+            EventEntity::External { .. } => EntityType::External,
+        }
+    }
+}
+
+/// How often a recurring event repeats.
+#[derive(Serialize_repr, Deserialize_repr, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum RecurrenceFrequency {
+    Yearly = 0,
+    Monthly = 1,
+    Weekly = 2,
+    Daily = 3,
+}
+
+/// A day of the week, as used by recurrence rules.
+#[derive(Serialize_repr, Deserialize_repr, Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[repr(u8)]
+pub enum Weekday {
+    Monday = 0,
+    Tuesday = 1,
+    Wednesday = 2,
+    Thursday = 3,
+    Friday = 4,
+    Saturday = 5,
+    Sunday = 6,
+}
+
+/// A month, as used by recurrence rules.
+#[derive(Serialize_repr, Deserialize_repr, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum Month {
+    January = 1,
+    February = 2,
+    March = 3,
+    April = 4,
+    May = 5,
+    June = 6,
+    July = 7,
+    August = 8,
+    September = 9,
+    October = 10,
+    November = 11,
+    December = 12,
+}
+
+/// The `n`th given weekday of a month, such as the second Tuesday.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct NWeekday {
+    /// The week of the month, from 1 to 5.
+    pub n: u8,
+    pub day: Weekday,
+}
+
+/// The weekday sets Discord accepts for daily recurrence.
+const DAILY_WEEKDAY_SETS: [&[Weekday]; 6] = {
+    use Weekday::*;
+    [
+        &[Monday, Tuesday, Wednesday, Thursday, Friday],
+        &[Tuesday, Wednesday, Thursday, Friday, Saturday],
+        &[Monday, Tuesday, Wednesday, Thursday, Sunday],
+        &[Friday, Saturday],
+        &[Saturday, Sunday],
+        &[Monday, Sunday],
+    ]
+};
+
+/// When a recurring event repeats.
+///
+/// Discord only accepts a subset of iCalendar rules, which the constructors
+/// cover. Rules built by hand can be checked with
+/// [`RecurrenceRule::validate`]. `end`, `by_year_day` and `count` are set by
+/// Discord and cannot be sent.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::{Duration, UNIX_EPOCH};
+/// use Rust_Discord_API::guild_scheduled_event::{RecurrenceRule, Weekday};
+///
+/// let start = UNIX_EPOCH + Duration::from_secs(1_735_722_000);
+/// let rule = RecurrenceRule::monthly(start, 2, Weekday::Tuesday);
+/// assert!(rule.validate().is_ok());
+/// assert!(RecurrenceRule::daily_on(start, &[Weekday::Monday]).validate().is_err());
+/// ```
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RecurrenceRule {
+    pub start: String,
+    #[serde(default)]
+    pub end: Option<String>,
+    pub frequency: RecurrenceFrequency,
+    pub interval: u32,
+    #[serde(default)]
+    pub by_weekday: Option<Vec<Weekday>>,
+    #[serde(default)]
+    pub by_n_weekday: Option<Vec<NWeekday>>,
+    #[serde(default)]
+    pub by_month: Option<Vec<Month>>,
+    #[serde(default)]
+    pub by_month_day: Option<Vec<u8>>,
+    #[serde(default)]
+    pub by_year_day: Option<Vec<u16>>,
+    #[serde(default)]
+    pub count: Option<u32>,
+}
+
+impl RecurrenceRule {
+    fn new(start: SystemTime, frequency: RecurrenceFrequency) -> Self {
+        Self {
+            start: format_timestamp(start),
+            end: None,
+            frequency,
+            interval: 1,
+            by_weekday: None,
+            by_n_weekday: None,
+            by_month: None,
+            by_month_day: None,
+            by_year_day: None,
+            count: None,
+        }
+    }
+
+    /// Repeats every day.
+    pub fn daily(start: SystemTime) -> Self {
+        Self::new(start, RecurrenceFrequency::Daily)
+    }
+
+    /// Repeats on the given days of every week. Only Monday to Friday,
+    /// Tuesday to Saturday, Sunday to Thursday, Friday and Saturday, Saturday
+    /// and Sunday, and Sunday and Monday are accepted.
+    pub fn daily_on(start: SystemTime, days: &[Weekday]) -> Self {
+        Self { by_weekday: Some(days.to_vec()), ..Self::new(start, RecurrenceFrequency::Daily) }
+    }
+
+    /// Repeats on the given day every week.
+    pub fn weekly(start: SystemTime, day: Weekday) -> Self {
+        Self { by_weekday: Some(vec![day]), ..Self::new(start, RecurrenceFrequency::Weekly) }
+    }
+
+    /// Repeats on the given day every other week.
+    pub fn every_other_week(start: SystemTime, day: Weekday) -> Self {
+        Self { interval: 2, ..Self::weekly(start, day) }
+    }
+
+    /// Repeats on the `n`th given weekday of every month, with `n` from 1 to 5.
+    pub fn monthly(start: SystemTime, n: u8, day: Weekday) -> Self {
+        Self { by_n_weekday: Some(vec![NWeekday { n, day }]), ..Self::new(start, RecurrenceFrequency::Monthly) }
+    }
+
+    /// Repeats on the given date every year.
+    pub fn yearly(start: SystemTime, month: Month, day: u8) -> Self {
+        Self { by_month: Some(vec![month]), by_month_day: Some(vec![day]), ..Self::new(start, RecurrenceFrequency::Yearly) }
+    }
+
+    /// Returns when the first event starts.
+    pub fn starts(&self) -> Option<SystemTime> {
+        parse_timestamp(&self.start)
+    }
+
+    /// Checks the rule against the combinations Discord accepts.
+    pub fn validate(&self) -> Result<(), Box<dyn Error>> {
+        if parse_timestamp(&self.start).is_none() {
+            return Err(format!("recurrence start is not a timestamp: {:?}", self.start).into());
+        }
+        if self.end.is_some() || self.by_year_day.is_some() || self.count.is_some() {
+            return Err("recurrence end, by_year_day and count are set by Discord".into());
+        }
+        let max_interval = match self.frequency {
+            RecurrenceFrequency::Weekly => 2,
+            _ => 1,
+        };
+        if self.interval == 0 || self.interval > max_interval {
+            return Err(format!("recurrence interval must be 1 to {} for {:?}, got {}", max_interval, self.frequency, self.interval).into());
+        }
+
+        let weekdays = self.by_weekday.as_deref();
+        let n_weekdays = self.by_n_weekday.as_deref();
+        let yearly = (self.by_month.as_deref(), self.by_month_day.as_deref());
+        let valid = match self.frequency {
+            RecurrenceFrequency::Daily => {
+                let days_valid = weekdays.is_none_or(|days| {
+                    let mut days = days.to_vec();
+                    days.sort();
+                    DAILY_WEEKDAY_SETS.contains(&days.as_slice())
+                });
+                days_valid && n_weekdays.is_none() && yearly == (None, None)
+            }
+            RecurrenceFrequency::Weekly => weekdays.is_some_and(|days| days.len() == 1) && n_weekdays.is_none() && yearly == (None, None),
+            RecurrenceFrequency::Monthly => {
+                weekdays.is_none() && n_weekdays.is_some_and(|days| matches!(days, [day] if (1..=5).contains(&day.n))) && yearly == (None, None)
+            }
+            RecurrenceFrequency::Yearly => {
+                weekdays.is_none() && n_weekdays.is_none() && matches!(yearly, (Some([_]), Some([day])) if (1..=31).contains(day))
+            }
+        };
+        if !valid {
+            return Err(format!("unsupported {:?} recurrence rule", self.frequency).into());
+        }
+        Ok(())
+    }
+}
+
+/// A scheduled event in a guild.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ScheduledEvent {
+    pub id: String,
+    pub guild_id: String,
+    /// `None` for external events.
+    #[serde(default)]
+    pub channel_id: Option<String>,
+    /// `None` for events created before October 25th, 2021.
+    #[serde(default)]
+    pub creator_id: Option<String>,
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub scheduled_start_time: String,
+    /// Always present for external events.
+    #[serde(default)]
+    pub scheduled_end_time: Option<String>,
+    pub privacy_level: PrivacyLevel,
+    pub status: ScheduledEventStatus,
+    pub entity_type: EntityType,
+    /// The ID of the stage instance of an active stage event.
+    #[serde(default)]
+    pub entity_id: Option<String>,
+    #[serde(default)]
+    pub entity_metadata: Option<EntityMetadata>,
+    #[serde(default)]
+    pub creator: Option<User>,
+    /// Only present when fetched with the user count.
+    #[serde(default)]
+    pub user_count: Option<u64>,
+    /// The cover image hash.
+    #[serde(default)]
+    pub image: Option<String>,
+    #[serde(default)]
+    pub recurrence_rule: Option<RecurrenceRule>,
+}
+
+impl ScheduledEvent {
+    /// Returns where the event is hosted, or `None` if the channel or
+    /// location is missing.
+    pub fn entity(&self) -> Option<EventEntity> {
+        match self.entity_type {
+            EntityType::StageInstance => Some(EventEntity::StageInstance { channel_id: self.channel_id.clone()? }),
+            EntityType::Voice => Some(EventEntity::Voice { channel_id: self.channel_id.clone()? }),
+            EntityType::External => Some(EventEntity::External { location: self.entity_metadata.as_ref()?.location.clone()? }),
+        }
+    }
+
+    /// Returns when the event is scheduled to start.
+    pub fn starts(&self) -> Option<SystemTime> {
+        parse_timestamp(&self.scheduled_start_time)
+    }
+
+    /// Returns when the event is scheduled to end, if an end is set.
+    pub fn ends(&self) -> Option<SystemTime> {
+        self.scheduled_end_time.as_deref().and_then(parse_timestamp)
+    }
+}
+
+/// Builds the body for creating or modifying a scheduled event.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::{Duration, UNIX_EPOCH};
+/// use Rust_Discord_API::guild_scheduled_event::{EventEntity, ScheduledEventBuilder};
+///
+/// let start = UNIX_EPOCH + Duration::from_secs(1_735_722_000);
+/// let meetup = ScheduledEventBuilder::new("Meetup", start, EventEntity::External { location: "Town hall".to_string() })
+///     .end_time(start + Duration::from_secs(7200));
+/// assert_eq!(meetup.build()["entity_metadata"]["location"], "Town hall");
+///
+/// // Moving an event into a voice channel clears its location.
+/// let moved = ScheduledEventBuilder::edit().entity(EventEntity::Voice { channel_id: "41771983423143937".to_string() });
+/// assert!(moved.build()["entity_metadata"].is_null());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ScheduledEventBuilder {
+    creating: bool,
+    name: Option<String>,
+    description: Option<String>,
+    start_time: Option<SystemTime>,
+    end_time: Option<SystemTime>,
+    entity: Option<EventEntity>,
+    image: Option<Vec<u8>>,
+    recurrence_rule: Option<RecurrenceRule>,
+}
+
+impl ScheduledEventBuilder {
+    /// Starts a new event. External events also need an end time.
+    pub fn new(name: &str, start_time: SystemTime, entity: EventEntity) -> Self {
+        Self { creating: true, name: Some(name.to_string()), start_time: Some(start_time), entity: Some(entity), ..Self::default() }
+    }
+
+    /// Starts the changes to an existing event. Use
+    /// `update_scheduled_event_status` to start, end or cancel it.
+    pub fn edit() -> Self {
+        Self::default()
+    }
+
+    /// Sets the name, at most `MAX_NAME_LENGTH` characters.
+    pub fn name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    /// Sets the description, at most `MAX_DESCRIPTION_LENGTH` characters.
+    pub fn description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_string());
+        self
+    }
+
+    /// Sets when the event starts.
+    pub fn start_time(mut self, start_time: SystemTime) -> Self {
+        self.start_time = Some(start_time);
+        self
+    }
+
+    /// Sets when the event ends. Required for external events.
+    pub fn end_time(mut self, end_time: SystemTime) -> Self {
+        self.end_time = Some(end_time);
+        self
+    }
+
+    /// Sets where the event is hosted. An external location needs an end time,
+    /// also when only changing the location of an existing event.
+    pub fn entity(mut self, entity: EventEntity) -> Self {
+        self.entity = Some(entity);
+        self
+    }
+
+    /// Sets the cover image from PNG, JPEG, GIF or WebP data.
+    pub fn image(mut self, image: &[u8]) -> Self {
+        self.image = Some(image.to_vec());
+        self
+    }
+
+    /// Makes the event repeat.
+    pub fn recurrence_rule(mut self, rule: RecurrenceRule) -> Self {
+        self.recurrence_rule = Some(rule);
+        self
+    }
+
+    /// Builds the JSON body of the event. An image that is not a supported
+    /// format is left out; `create_scheduled_event` and
+    /// `modify_scheduled_event` reject it instead.
+    pub fn build(&self) -> Value {
+        let mut body = json!({});
+        if self.creating {
+            body["privacy_level"] = json!(PrivacyLevel::GuildOnly);
+        }
+        if let Some(name) = &self.name {
+            body["name"] = json!(name);
+        }
+        if let Some(description) = &self.description {
+            body["description"] = json!(description);
+        }
+        if let Some(start_time) = self.start_time {
+            body["scheduled_start_time"] = json!(format_timestamp(start_time));
+        }
+        if let Some(end_time) = self.end_time {
+            body["scheduled_end_time"] = json!(format_timestamp(end_time));
+        }
+        if let Some(entity) = &self.entity {
+            body["entity_type"] = json!(entity.kind());
+            match entity {
+                EventEntity::StageInstance { channel_id } | EventEntity::Voice { channel_id } => {
+                    body["channel_id"] = json!(channel_id);
+                    body["entity_metadata"] = Value::Null;
+                }
+                EventEntity::External { location } => {
+                    body["channel_id"] = Value::Null;
+                    body["entity_metadata"] = json!({ "location": location });
+                }
+            }
+        }
+        if let Some(image) = self.image.as_deref().and_then(|image| image_data_uri(image).ok()) {
+            body["image"] = json!(image);
+        }
+        if let Some(rule) = &self.recurrence_rule {
+            body["recurrence_rule"] = json!(rule);
+        }
+        body
+    }
+
+    fn validate(&self) -> Result<(), Box<dyn Error>> {
+        check_length("name", self.name.as_deref(), 1, MAX_NAME_LENGTH)?;
+        check_length("description", self.description.as_deref(), 0, MAX_DESCRIPTION_LENGTH)?;
+        if let Some(EventEntity::External { location }) = &self.entity {
+            check_length("location", Some(location), 1, MAX_LOCATION_LENGTH)?;
+            if self.end_time.is_none() {
+                return Err("external events need an end time".into());
+            }
+        }
+        if let (Some(start), Some(end)) = (self.start_time, self.end_time) {
+            if end <= start {
+                return Err("scheduled events must end after they start".into());
+            }
+        }
+        if let Some(image) = &self.image {
+            image_data_uri(image)?;
+        }
+        if let Some(rule) = &self.recurrence_rule {
+            rule.validate()?;
+        }
+        Ok(())
+    }
+}
+
+fn check_length(field: &str, value: Option<&str>, min: usize, max: usize) -> Result<(), Box<dyn Error>> {
+    if let Some(value) = value {
+        let length = value.chars().count();
+        if length < min || length > max {
+            return Err(format!("scheduled event {} must be {} to {} characters, got {}", field, min, max, length).into());
+        }
+    }
+    Ok(())
+}
 
 #[allow(dead_code)]
-/// Lists scheduled events for a Discord guild.
+/// Lists the scheduled events of a Discord guild.
 ///
 /// # Arguments
 ///
 /// * `client` - The HTTP client used to send the request.
 /// * `token` - The bot token for authentication.
 /// * `guild_id` - The ID of the guild to list scheduled events for.
+/// * `with_user_count` - Whether to include the number of subscribed users.
 ///
 /// # Returns
 ///
-/// A result containing the list of scheduled events as a JSON value.
-pub async fn list_scheduled_events(client: &Client, token: &str, guild_id: &str, event_id: &str) -> Result<Value, Box<dyn Error>> {
-    let url = format!("https://discord.com/api/v9/guilds/{}/scheduled-events/{}", guild_id, event_id);
-    let response: Value = client.get(&url)
+/// A result containing the guild's scheduled events.
+pub async fn list_scheduled_events(client: &Client, token: &str, guild_id: &str, with_user_count: bool) -> Result<Vec<ScheduledEvent>, Box<dyn Error>> {
+    let url = format!("https://discord.com/api/v9/guilds/{}/scheduled-events", guild_id);
+    let response: Vec<ScheduledEvent> = client.get(&url)
         .bearer_auth(token)
+        .query(&[("with_user_count", with_user_count)])
         .send()
         .await?
+        .error_for_status()?
         .json()
         .await?;
-    
+
     Ok(response)
 }
 
@@ -34,21 +545,25 @@ pub async fn list_scheduled_events(client: &Client, token: &str, guild_id: &str,
 /// * `client` - The HTTP client used to send the request.
 /// * `token` - The bot token for authentication.
 /// * `guild_id` - The ID of the guild to create the scheduled event in.
-/// * `event_settings` - The JSON value of the event settings.
+/// * `event` - The event, started with `ScheduledEventBuilder::new`.
+/// * `reason` - The reason shown in the audit log, if any.
 ///
 /// # Returns
 ///
-/// A result containing the created scheduled event information as a JSON value.
-pub async fn create_scheduled_event(client: &Client, token: &str, guild_id: &str, event_settings: Value) -> Result<Value, Box<dyn Error>> {
+/// A result containing the created scheduled event, or an error if the event or its recurrence rule is invalid.
+pub async fn create_scheduled_event(client: &Client, token: &str, guild_id: &str, event: &ScheduledEventBuilder, reason: Option<&str>) -> Result<ScheduledEvent, Box<dyn Error>> {
+    event.validate()?;
     let url = format!("https://discord.com/api/v9/guilds/{}/scheduled-events", guild_id);
-    let response: Value = client.post(&url)
+    let response: ScheduledEvent = client.post(&url)
         .bearer_auth(token)
-        .json(&event_settings)
+        .audit_log_reason(reason)
+        .json(&event.build())
         .send()
         .await?
+        .error_for_status()?
         .json()
         .await?;
-    
+
     Ok(response)
 }
 
@@ -61,19 +576,22 @@ pub async fn create_scheduled_event(client: &Client, token: &str, guild_id: &str
 /// * `token` - The bot token for authentication.
 /// * `guild_id` - The ID of the guild.
 /// * `event_id` - The ID of the scheduled event to fetch.
+/// * `with_user_count` - Whether to include the number of subscribed users.
 ///
 /// # Returns
 ///
-/// A result containing the scheduled event information as a JSON value.
-pub async fn get_scheduled_event(client: &Client, token: &str, guild_id: &str, event_id: &str) -> Result<Value, Box<dyn Error>> {
+/// A result containing the scheduled event.
+pub async fn get_scheduled_event(client: &Client, token: &str, guild_id: &str, event_id: &str, with_user_count: bool) -> Result<ScheduledEvent, Box<dyn Error>> {
     let url = format!("https://discord.com/api/v9/guilds/{}/scheduled-events/{}", guild_id, event_id);
-    let response: Value = client.get(&url)
+    let response: ScheduledEvent = client.get(&url)
         .bearer_auth(token)
+        .query(&[("with_user_count", with_user_count)])
         .send()
         .await?
+        .error_for_status()?
         .json()
         .await?;
-    
+
     Ok(response)
 }
 
@@ -86,21 +604,25 @@ pub async fn get_scheduled_event(client: &Client, token: &str, guild_id: &str, e
 /// * `token` - The bot token for authentication.
 /// * `guild_id` - The ID of the guild.
 /// * `event_id` - The ID of the scheduled event to modify.
-/// * `event_settings` - The JSON value of the event settings.
+/// * `changes` - The changes, started with `ScheduledEventBuilder::edit`.
+/// * `reason` - The reason shown in the audit log, if any.
 ///
 /// # Returns
 ///
-/// A result containing the modified scheduled event information as a JSON value.
-pub async fn modify_scheduled_event(client: &Client, token: &str, guild_id: &str, event_id: &str, event_settings: Value) -> Result<Value, Box<dyn Error>> {
+/// A result containing the modified scheduled event.
+pub async fn modify_scheduled_event(client: &Client, token: &str, guild_id: &str, event_id: &str, changes: &ScheduledEventBuilder, reason: Option<&str>) -> Result<ScheduledEvent, Box<dyn Error>> {
+    changes.validate()?;
     let url = format!("https://discord.com/api/v9/guilds/{}/scheduled-events/{}", guild_id, event_id);
-    let response: Value = client.patch(&url)
+    let response: ScheduledEvent = client.patch(&url)
         .bearer_auth(token)
-        .json(&event_settings)
+        .audit_log_reason(reason)
+        .json(&changes.build())
         .send()
         .await?
+        .error_for_status()?
         .json()
         .await?;
-    
+
     Ok(response)
 }
 
@@ -155,52 +677,61 @@ pub async fn get_scheduled_event_users(client: &Client, token: &str, guild_id: &
 }
 
 #[allow(dead_code)]
-/// Updates the status of a scheduled event in a Discord guild.
+/// Streams the users subscribed to a scheduled event, following the `before`/`after` cursors.
 ///
 /// # Arguments
 ///
-/// * `client` - The HTTP client used to send the request.
+/// * `client` - The HTTP client used to send the requests.
 /// * `token` - The bot token for authentication.
 /// * `guild_id` - The ID of the guild.
-/// * `event_id` - The ID of the scheduled event to update the status for.
-/// * `status` - The new status of the scheduled event.
+/// * `event_id` - The ID of the scheduled event to fetch users for.
+/// * `with_member` - Whether to include guild member data for each user.
+/// * `options` - The direction, starting user and maximum number of users.
 ///
 /// # Returns
 ///
-/// A result containing the updated scheduled event information as a JSON value.
-pub async fn update_scheduled_event_status(client: &Client, token: &str, guild_id: &str, event_id: &str, status: &str) -> Result<Value, Box<dyn Error>> {
-    let url = format!("https://discord.com/api/v9/guilds/{}/scheduled-events/{}", guild_id, event_id);
-    let body = serde_json::json!({ "status": status });
-    let response: Value = client.patch(&url)
-        .bearer_auth(token)
-        .json(&body)
-        .send()
-        .await?
-        .json()
-        .await?;
-    
-    Ok(response)
+/// A stream of scheduled event users as JSON values.
+pub fn stream_scheduled_event_users(client: &Client, token: &str, guild_id: &str, event_id: &str, with_member: bool, options: PaginationOptions) -> BoxStream<'static, PageResult> {
+    let endpoint = Endpoint {
+        url: format!("https://discord.com/api/v9/guilds/{}/scheduled-events/{}/users", guild_id, event_id),
+        query: vec![("with_member", with_member.to_string())],
+        max_page_size: 100,
+        supports_before: true,
+        items: pagination::array_items,
+        id: pagination::user_id,
+    };
+
+    pagination::paginate(client, token, endpoint, options)
 }
 
 #[allow(dead_code)]
-/// Fetches the permissions required for guild scheduled events.
+/// Starts, ends or cancels a scheduled event.
 ///
 /// # Arguments
 ///
 /// * `client` - The HTTP client used to send the request.
 /// * `token` - The bot token for authentication.
+/// * `event` - The scheduled event, as last fetched or received.
+/// * `status` - The new status of the scheduled event.
+/// * `reason` - The reason shown in the audit log, if any.
 ///
 /// # Returns
 ///
-/// A result containing the permissions requirements as a JSON value.
-pub async fn get_scheduled_event_permissions(client: &Client, token: &str) -> Result<Value, Box<dyn Error>> {
-    let url = "https://discord.com/api/v9/scheduled-events/permissions";
-    let response: Value = client.get(url)
+/// A result containing the updated scheduled event, or an error if the event cannot move from its current status to `status`.
+pub async fn update_scheduled_event_status(client: &Client, token: &str, event: &ScheduledEvent, status: ScheduledEventStatus, reason: Option<&str>) -> Result<ScheduledEvent, Box<dyn Error>> {
+    if !event.status.can_transition_to(status) {
+        return Err(format!("scheduled event {} cannot move from {:?} to {:?}", event.id, event.status, status).into());
+    }
+    let url = format!("https://discord.com/api/v9/guilds/{}/scheduled-events/{}", event.guild_id, event.id);
+    let response: ScheduledEvent = client.patch(&url)
         .bearer_auth(token)
+        .audit_log_reason(reason)
+        .json(&json!({ "status": status }))
         .send()
         .await?
+        .error_for_status()?
         .json()
         .await?;
-    
+
     Ok(response)
-}
\ No newline at end of file
+}
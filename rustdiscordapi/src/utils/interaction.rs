@@ -0,0 +1,319 @@
+use reqwest::Client;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use serde_repr::{Deserialize_repr, Serialize_repr};
+use std::collections::HashMap;
+use std::error::Error;
+
+use super::component::{ActionRow, ComponentType, TextInput};
+use super::message::MessageBuilder;
+
+/// The type of an incoming interaction.
+#[derive(Serialize_repr, Deserialize_repr, Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum InteractionType {
+    Ping = 1,
+    ApplicationCommand = 2,
+    MessageComponent = 3,
+    ApplicationCommandAutocomplete = 4,
+    ModalSubmit = 5,
+}
+
+/// An interaction received from Discord, for example a button click.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Interaction {
+    pub id: String,
+    pub application_id: String,
+    #[serde(rename = "type")]
+    pub kind: InteractionType,
+    #[serde(default)]
+    pub data: Option<Value>,
+    #[serde(default)]
+    pub guild_id: Option<String>,
+    #[serde(default)]
+    pub channel_id: Option<String>,
+    #[serde(default)]
+    pub member: Option<Value>,
+    #[serde(default)]
+    pub user: Option<Value>,
+    pub token: String,
+    #[serde(default)]
+    pub message: Option<Value>,
+}
+
+impl Interaction {
+    /// Returns the ID of the user who triggered the interaction, whether it
+    /// happened in a guild or in a DM.
+    pub fn user_id(&self) -> Option<&str> {
+        self.member.as_ref()
+            .and_then(|member| member["user"]["id"].as_str())
+            .or_else(|| self.user.as_ref().and_then(|user| user["id"].as_str()))
+    }
+
+    /// Returns the component data if this is a message component interaction.
+    pub fn component_data(&self) -> Option<ComponentInteractionData> {
+        if self.kind != InteractionType::MessageComponent {
+            return None;
+        }
+        self.data.clone().and_then(|data| serde_json::from_value(data).ok())
+    }
+
+    /// Returns the submitted values if this is a modal submit interaction.
+    pub fn modal_data(&self) -> Option<ModalSubmitData> {
+        if self.kind != InteractionType::ModalSubmit {
+            return None;
+        }
+        self.data.clone().and_then(|data| serde_json::from_value(data).ok())
+    }
+}
+
+/// The data sent with a message component interaction.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ComponentInteractionData {
+    pub custom_id: String,
+    pub component_type: ComponentType,
+    /// The selected values, for select menus.
+    #[serde(default)]
+    pub values: Vec<String>,
+    /// The resolved users, members, roles and channels, for auto-populated select menus.
+    #[serde(default)]
+    pub resolved: Option<Value>,
+}
+
+/// The data sent when a user submits a modal.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ModalSubmitData {
+    pub custom_id: String,
+    /// The submitted components, as action rows of text inputs.
+    #[serde(default)]
+    pub components: Vec<Value>,
+}
+
+impl ModalSubmitData {
+    /// Collects the submitted text input values keyed by their `custom_id`.
+    pub fn values(&self) -> HashMap<String, String> {
+        let mut values = HashMap::new();
+        for component in &self.components {
+            collect_text_values(component, &mut values);
+        }
+        values
+    }
+
+    /// Returns the value of the text input with the given `custom_id`.
+    pub fn value(&self, custom_id: &str) -> Option<String> {
+        self.values().remove(custom_id)
+    }
+
+    /// Parses the submitted values into a typed struct whose field names match
+    /// the text inputs' `custom_id`s.
+    ///
+    /// Optional inputs left empty are treated as missing, so they can be
+    /// declared as `Option<String>` fields.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde::Deserialize;
+    /// use serde_json::json;
+    /// use Rust_Discord_API::interaction::ModalSubmitData;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Report {
+    ///     reason: String,
+    ///     details: Option<String>,
+    /// }
+    ///
+    /// let data: ModalSubmitData = serde_json::from_value(json!({
+    ///     "custom_id": "report",
+    ///     "components": [
+    ///         { "type": 1, "components": [{ "type": 4, "custom_id": "reason", "value": "spam" }] },
+    ///         { "type": 1, "components": [{ "type": 4, "custom_id": "details", "value": "" }] }
+    ///     ]
+    /// })).unwrap();
+    ///
+    /// let report: Report = data.parse().unwrap();
+    /// assert_eq!(report.reason, "spam");
+    /// assert!(report.details.is_none());
+    /// ```
+    pub fn parse<T: DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
+        let fields: Map<String, Value> = self.values()
+            .into_iter()
+            .filter(|(_, value)| !value.is_empty())
+            .map(|(custom_id, value)| (custom_id, Value::String(value)))
+            .collect();
+
+        serde_json::from_value(Value::Object(fields))
+    }
+}
+
+fn collect_text_values(component: &Value, values: &mut HashMap<String, String>) {
+    if let (Some(custom_id), Some(value)) = (component["custom_id"].as_str(), component["value"].as_str()) {
+        values.insert(custom_id.to_string(), value.to_string());
+    }
+    if let Some(children) = component["components"].as_array() {
+        for child in children {
+            collect_text_values(child, values);
+        }
+    }
+    if component["component"].is_object() {
+        collect_text_values(&component["component"], values);
+    }
+}
+
+/// A popup form with up to five text inputs.
+///
+/// # Examples
+///
+/// ```
+/// use Rust_Discord_API::interaction::Modal;
+/// use Rust_Discord_API::component::TextInput;
+///
+/// let modal = Modal::new("report", "Report a user")
+///     .text_input(TextInput::short("reason", "Reason").max_length(100))
+///     .text_input(TextInput::paragraph("details", "Details").required(false));
+/// ```
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Modal {
+    pub custom_id: String,
+    pub title: String,
+    pub components: Vec<ActionRow>,
+}
+
+impl Modal {
+    /// Creates an empty modal.
+    pub fn new(custom_id: &str, title: &str) -> Self {
+        Self { custom_id: custom_id.to_string(), title: title.to_string(), components: Vec::new() }
+    }
+
+    /// Adds a text input on its own row.
+    pub fn text_input(mut self, input: TextInput) -> Self {
+        self.components.push(ActionRow::text_input(input));
+        self
+    }
+}
+
+/// The type of response sent back to an interaction.
+#[derive(Serialize_repr, Deserialize_repr, Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum InteractionResponseType {
+    Pong = 1,
+    ChannelMessageWithSource = 4,
+    DeferredChannelMessageWithSource = 5,
+    DeferredUpdateMessage = 6,
+    UpdateMessage = 7,
+    ApplicationCommandAutocompleteResult = 8,
+    Modal = 9,
+}
+
+/// A response to an interaction.
+#[derive(Serialize, Clone, Debug)]
+pub struct InteractionResponse {
+    #[serde(rename = "type")]
+    pub kind: InteractionResponseType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+impl InteractionResponse {
+    /// Acknowledges a ping.
+    pub fn pong() -> Self {
+        Self { kind: InteractionResponseType::Pong, data: None }
+    }
+
+    /// Responds with a new message.
+    pub fn message(message: &MessageBuilder) -> Self {
+        Self { kind: InteractionResponseType::ChannelMessageWithSource, data: Some(message.build()) }
+    }
+
+    /// Acknowledges the interaction and shows a loading state; edit the original response later.
+    pub fn deferred_message() -> Self {
+        Self { kind: InteractionResponseType::DeferredChannelMessageWithSource, data: None }
+    }
+
+    /// Updates the message the component is attached to.
+    pub fn update_message(message: &MessageBuilder) -> Self {
+        Self { kind: InteractionResponseType::UpdateMessage, data: Some(message.build()) }
+    }
+
+    /// Acknowledges a component interaction without updating the message yet.
+    pub fn deferred_update() -> Self {
+        Self { kind: InteractionResponseType::DeferredUpdateMessage, data: None }
+    }
+
+    /// Opens a modal.
+    pub fn modal(modal: &Modal) -> Self {
+        Self { kind: InteractionResponseType::Modal, data: serde_json::to_value(modal).ok() }
+    }
+}
+
+/// Responds to an interaction.
+///
+/// # Arguments
+///
+/// * `client` - The HTTP client used to send the request.
+/// * `interaction_id` - The ID of the interaction.
+/// * `interaction_token` - The token of the interaction.
+/// * `response` - The response to send.
+///
+/// # Returns
+///
+/// A result indicating success or failure.
+#[allow(dead_code)]
+pub async fn create_interaction_response(client: &Client, interaction_id: &str, interaction_token: &str, response: &InteractionResponse) -> Result<(), Box<dyn Error>> {
+    let url = format!("https://discord.com/api/v9/interactions/{}/{}/callback", interaction_id, interaction_token);
+
+    client.post(&url)
+        .json(response)
+        .send()
+        .await?
+        .error_for_status()?;
+
+    Ok(())
+}
+
+/// Opens a modal in response to a command or component interaction.
+///
+/// # Arguments
+///
+/// * `client` - The HTTP client used to send the request.
+/// * `interaction` - The interaction to respond to.
+/// * `modal` - The modal to open.
+///
+/// # Returns
+///
+/// A result indicating success or failure.
+#[allow(dead_code)]
+pub async fn open_modal(client: &Client, interaction: &Interaction, modal: &Modal) -> Result<(), Box<dyn Error>> {
+    if !matches!(interaction.kind, InteractionType::ApplicationCommand | InteractionType::MessageComponent) {
+        return Err("modals can only be opened from command or component interactions".into());
+    }
+
+    create_interaction_response(client, &interaction.id, &interaction.token, &InteractionResponse::modal(modal)).await
+}
+
+/// Edits the original response to an interaction.
+///
+/// # Arguments
+///
+/// * `client` - The HTTP client used to send the request.
+/// * `application_id` - The ID of the application.
+/// * `interaction_token` - The token of the interaction.
+/// * `message` - The new message contents.
+///
+/// # Returns
+///
+/// A result containing the edited message as a JSON value.
+#[allow(dead_code)]
+pub async fn edit_original_interaction_response(client: &Client, application_id: &str, interaction_token: &str, message: &MessageBuilder) -> Result<Value, Box<dyn Error>> {
+    let url = format!("https://discord.com/api/v9/webhooks/{}/{}/messages/@original", application_id, interaction_token);
+    let response: Value = client.patch(&url)
+        .json(&message.build())
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    Ok(response)
+}
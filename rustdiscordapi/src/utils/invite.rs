@@ -0,0 +1,216 @@
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use serde_repr::{Deserialize_repr, Serialize_repr};
+use std::error::Error;
+use std::time::SystemTime;
+
+use crate::model::{Channel, Guild, User};
+
+use super::audit_log::AuditLogReason;
+use super::timestamp::parse_timestamp;
+
+/// What an invite leads to.
+#[derive(Serialize_repr, Deserialize_repr, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum InviteType {
+    Guild = 0,
+    GroupDm = 1,
+    Friend = 2,
+}
+
+/// What a voice channel invite opens once joined.
+#[derive(Serialize_repr, Deserialize_repr, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum InviteTargetType {
+    /// A user's stream, given by `target_user`.
+    Stream = 1,
+    /// An embedded application (activity), given by `target_application`.
+    EmbeddedApplication = 2,
+}
+
+/// An invite to a guild, group DM or channel.
+///
+/// The use counts and settings are only present on invites listed by
+/// `get_channel_invites` and `get_guild_invites`, returned by
+/// `create_channel_invite`, or sent in `INVITE_CREATE`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Invite {
+    pub code: String,
+    #[serde(rename = "type", default)]
+    pub kind: Option<InviteType>,
+    /// A partial guild, when the invite is for a guild.
+    #[serde(default)]
+    pub guild: Option<Guild>,
+    /// A partial channel.
+    #[serde(default)]
+    pub channel: Option<Channel>,
+    /// Sent instead of `guild` in `INVITE_CREATE`.
+    #[serde(default)]
+    pub guild_id: Option<String>,
+    /// Sent instead of `channel` in `INVITE_CREATE`.
+    #[serde(default)]
+    pub channel_id: Option<String>,
+    #[serde(default)]
+    pub inviter: Option<User>,
+    #[serde(default)]
+    pub target_type: Option<InviteTargetType>,
+    #[serde(default)]
+    pub target_user: Option<User>,
+    /// A partial application.
+    #[serde(default)]
+    pub target_application: Option<Value>,
+    /// Only present when fetched with counts.
+    #[serde(default)]
+    pub approximate_presence_count: Option<u64>,
+    /// Only present when fetched with counts.
+    #[serde(default)]
+    pub approximate_member_count: Option<u64>,
+    /// `None` when the invite never expires.
+    #[serde(default)]
+    pub expires_at: Option<String>,
+    #[serde(default)]
+    pub guild_scheduled_event: Option<Value>,
+    #[serde(default)]
+    pub uses: Option<u64>,
+    /// Zero when unlimited.
+    #[serde(default)]
+    pub max_uses: Option<u64>,
+    /// In seconds, zero when the invite never expires.
+    #[serde(default)]
+    pub max_age: Option<u64>,
+    /// Whether members who join through the invite are kicked when they
+    /// disconnect without having been given a role.
+    #[serde(default)]
+    pub temporary: Option<bool>,
+    #[serde(default)]
+    pub created_at: Option<String>,
+}
+
+impl Invite {
+    /// Returns the link to the invite.
+    pub fn url(&self) -> String {
+        format!("https://discord.gg/{}", self.code)
+    }
+
+    /// Returns the ID of the guild the invite is for, from either `guild` or `guild_id`.
+    pub fn guild_id(&self) -> Option<&str> {
+        self.guild.as_ref().map(|guild| guild.id.as_str()).or(self.guild_id.as_deref())
+    }
+
+    /// Returns when the invite expires, or `None` if it never does.
+    pub fn expires(&self) -> Option<SystemTime> {
+        self.expires_at.as_deref().and_then(parse_timestamp)
+    }
+
+    /// Returns whether the invite has expired at the given time.
+    pub fn is_expired_at(&self, now: SystemTime) -> bool {
+        self.expires().is_some_and(|expires| expires <= now)
+    }
+}
+
+/// A guild's vanity invite.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct VanityUrl {
+    /// `None` when the guild has no vanity invite set.
+    pub code: Option<String>,
+    #[serde(default)]
+    pub uses: u64,
+}
+
+/// Extracts the invite code from a code or an invite link such as
+/// `https://discord.gg/abc`, `discord.com/invite/abc` or
+/// `https://discordapp.com/invite/abc?event=1`.
+///
+/// # Examples
+///
+/// ```
+/// use Rust_Discord_API::invite::parse_invite_code;
+///
+/// assert_eq!(parse_invite_code("https://discord.gg/rust-lang"), Some("rust-lang"));
+/// assert_eq!(parse_invite_code("discord.com/invite/abc123/"), Some("abc123"));
+/// assert_eq!(parse_invite_code("abc123"), Some("abc123"));
+/// assert_eq!(parse_invite_code("https://example.com/invite/abc123"), None);
+/// ```
+pub fn parse_invite_code(invite: &str) -> Option<&str> {
+    let invite = invite.trim();
+    let link = invite.strip_prefix("https://").or_else(|| invite.strip_prefix("http://")).unwrap_or(invite);
+    let code = if link.contains('/') {
+        let (host, path) = link.split_once('/')?;
+        let path = match host.to_ascii_lowercase().trim_start_matches("www.") {
+            "discord.gg" => path,
+            "discord.com" | "discordapp.com" | "ptb.discord.com" | "canary.discord.com" => path.strip_prefix("invite/")?,
+            _ => return None,
+        };
+        let path = path.split(['?', '#']).next()?;
+        path.strip_suffix('/').unwrap_or(path)
+    } else {
+        link
+    };
+    let valid = !code.is_empty() && code.bytes().all(|byte| byte.is_ascii_alphanumeric() || byte == b'-');
+    valid.then_some(code)
+}
+
+fn invite_code(invite: &str) -> Result<&str, Box<dyn Error>> {
+    parse_invite_code(invite).ok_or_else(|| format!("not an invite code or link: {:?}", invite).into())
+}
+
+/// Resolves an invite by its code or link.
+///
+/// # Arguments
+///
+/// * `client` - The HTTP client used to send the request.
+/// * `token` - The bot token for authentication.
+/// * `invite` - The invite code, or an invite link.
+/// * `with_counts` - Whether to include the approximate member and presence counts.
+/// * `guild_scheduled_event_id` - The scheduled event to include, if any.
+///
+/// # Returns
+///
+/// A result containing the invite, with its expiration.
+#[allow(dead_code)]
+pub async fn get_invite(client: &Client, token: &str, invite: &str, with_counts: bool, guild_scheduled_event_id: Option<&str>) -> Result<Invite, Box<dyn Error>> {
+    let url = format!("https://discord.com/api/v9/invites/{}", invite_code(invite)?);
+    let mut query = vec![("with_counts", with_counts.to_string()), ("with_expiration", "true".to_string())];
+    if let Some(event_id) = guild_scheduled_event_id {
+        query.push(("guild_scheduled_event_id", event_id.to_string()));
+    }
+    let response: Invite = client.get(&url)
+        .bearer_auth(token)
+        .query(&query)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    Ok(response)
+}
+
+/// Revokes an invite. Needs the `MANAGE_CHANNELS` permission on the
+/// invite's channel, or `MANAGE_GUILD` to revoke any invite of the guild.
+///
+/// # Arguments
+///
+/// * `client` - The HTTP client used to send the request.
+/// * `token` - The bot token for authentication.
+/// * `invite` - The invite code, or an invite link.
+/// * `reason` - The reason shown in the audit log, if any.
+///
+/// # Returns
+///
+/// A result containing the deleted invite.
+#[allow(dead_code)]
+pub async fn delete_invite(client: &Client, token: &str, invite: &str, reason: Option<&str>) -> Result<Invite, Box<dyn Error>> {
+    let url = format!("https://discord.com/api/v9/invites/{}", invite_code(invite)?);
+    let response: Invite = client.delete(&url)
+        .bearer_auth(token)
+        .audit_log_reason(reason)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    Ok(response)
+}
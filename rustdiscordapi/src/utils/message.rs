@@ -1,7 +1,88 @@
 use reqwest::Client;
-use serde_json::json;
+use serde_json::{json, Value};
 use std::error::Error;
 
+use super::component::ActionRow;
+
+/// Builds the body of a message with content, embeds and components.
+///
+/// # Examples
+///
+/// ```
+/// use Rust_Discord_API::message::MessageBuilder;
+/// use Rust_Discord_API::component::{ActionRow, Button};
+///
+/// let message = MessageBuilder::new()
+///     .content("Ban this user?")
+///     .action_row(ActionRow::buttons(vec![
+///         Button::danger("ban:confirm", "Ban"),
+///         Button::secondary("ban:cancel", "Cancel"),
+///     ]));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct MessageBuilder {
+    content: Option<String>,
+    embeds: Vec<Value>,
+    components: Option<Vec<ActionRow>>,
+    flags: Option<u64>,
+}
+
+impl MessageBuilder {
+    /// Creates an empty message.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the text content of the message.
+    pub fn content(mut self, content: &str) -> Self {
+        self.content = Some(content.to_string());
+        self
+    }
+
+    /// Adds an embed to the message.
+    pub fn embed(mut self, embed: Value) -> Self {
+        self.embeds.push(embed);
+        self
+    }
+
+    /// Adds a row of components to the message.
+    pub fn action_row(mut self, row: ActionRow) -> Self {
+        self.components.get_or_insert_with(Vec::new).push(row);
+        self
+    }
+
+    /// Replaces the component rows of the message. Passing an empty list
+    /// removes all components when editing a message.
+    pub fn components(mut self, rows: Vec<ActionRow>) -> Self {
+        self.components = Some(rows);
+        self
+    }
+
+    /// Sets the message flags, for example `1 << 6` for an ephemeral interaction response.
+    pub fn flags(mut self, flags: u64) -> Self {
+        self.flags = Some(flags);
+        self
+    }
+
+    /// Builds the JSON body of the message.
+    pub fn build(&self) -> Value {
+        let mut body = json!({});
+        if let Some(content) = &self.content {
+            body["content"] = json!(content);
+        }
+        if !self.embeds.is_empty() {
+            body["embeds"] = json!(self.embeds);
+        }
+        if let Some(components) = &self.components {
+            body["components"] = json!(components);
+        }
+        if let Some(flags) = self.flags {
+            body["flags"] = json!(flags);
+        }
+        body
+    }
+}
+
 /// Sends a message to a specified Discord channel.
 ///
 /// # Arguments
@@ -131,3 +212,58 @@ pub async fn unpin_message(client: &Client, token: &str, channel_id: &str, messa
     
     Ok(())
 }
+
+/// Sends a message built with a [`MessageBuilder`] to a specified Discord channel.
+///
+/// # Arguments
+///
+/// * `client` - The HTTP client used to send the request.
+/// * `token` - The bot token for authentication.
+/// * `channel_id` - The ID of the channel to send the message to.
+/// * `message` - The message to send.
+///
+/// # Returns
+///
+/// A result containing the created message as a JSON value.
+#[allow(dead_code)]
+pub async fn send_message_with(client: &Client, token: &str, channel_id: &str, message: &MessageBuilder) -> Result<Value, Box<dyn Error>> {
+    let url = format!("https://discord.com/api/v9/channels/{}/messages", channel_id);
+    let response: Value = client.post(&url)
+        .bearer_auth(token)
+        .json(&message.build())
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    Ok(response)
+}
+
+/// Edits a message with the contents of a [`MessageBuilder`].
+///
+/// # Arguments
+///
+/// * `client` - The HTTP client used to send the request.
+/// * `token` - The bot token for authentication.
+/// * `channel_id` - The ID of the channel where the message is located.
+/// * `message_id` - The ID of the message to edit.
+/// * `message` - The new message contents.
+///
+/// # Returns
+///
+/// A result containing the edited message as a JSON value.
+#[allow(dead_code)]
+pub async fn edit_message_with(client: &Client, token: &str, channel_id: &str, message_id: &str, message: &MessageBuilder) -> Result<Value, Box<dyn Error>> {
+    let url = format!("https://discord.com/api/v9/channels/{}/messages/{}", channel_id, message_id);
+    let response: Value = client.patch(&url)
+        .bearer_auth(token)
+        .json(&message.build())
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    Ok(response)
+}
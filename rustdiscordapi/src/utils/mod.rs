@@ -8,4 +8,15 @@ pub mod role;
 pub mod guild;
 pub mod guild_scheduled_event;
 pub mod sticker;
-pub mod poll;
\ No newline at end of file
+pub mod poll;
+pub mod component;
+pub mod interaction;
+pub mod pagination;
+pub mod webhook;
+mod api;
+pub mod audit_log;
+pub mod auto_moderation;
+pub mod timestamp;
+pub mod emoji;
+pub mod invite;
+pub mod stage_instance;
@@ -0,0 +1,205 @@
+use futures::stream::{self, BoxStream, StreamExt};
+use reqwest::Client;
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::error::Error;
+
+/// An item yielded by a pagination stream.
+///
+/// The error is `Send + Sync` so streams can be driven from spawned tasks; it
+/// converts into `Box<dyn Error>` with `?`.
+pub type PageResult<T = Value> = Result<T, Box<dyn Error + Send + Sync>>;
+
+/// The largest snowflake Discord accepts, used as the starting cursor when
+/// paging backwards from the newest item.
+pub(crate) const MAX_SNOWFLAKE: &str = "9223372036854775807";
+
+/// Which way a pagination stream walks through a list.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Direction {
+    /// From older (lower) IDs to newer (higher) IDs, using the `after` cursor.
+    #[default]
+    After,
+    /// From newer (higher) IDs to older (lower) IDs, using the `before` cursor.
+    Before,
+}
+
+/// Options for a pagination stream.
+///
+/// # Examples
+///
+/// ```
+/// use Rust_Discord_API::pagination::{Direction, PaginationOptions};
+///
+/// // The 500 most recent messages, newest first.
+/// let options = PaginationOptions::new().direction(Direction::Before).max_items(500);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct PaginationOptions {
+    pub(crate) direction: Direction,
+    pub(crate) start: Option<String>,
+    pub(crate) max_items: Option<usize>,
+    pub(crate) page_size: Option<u32>,
+}
+
+impl PaginationOptions {
+    /// Creates options that walk forward from the beginning of the list.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the direction to walk in.
+    pub fn direction(mut self, direction: Direction) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    /// Starts after (or, walking backwards, before) the given ID instead of at
+    /// the start (or end) of the list.
+    pub fn start(mut self, id: &str) -> Self {
+        self.start = Some(id.to_string());
+        self
+    }
+
+    /// Stops after yielding `max_items` items.
+    pub fn max_items(mut self, max_items: usize) -> Self {
+        self.max_items = Some(max_items);
+        self
+    }
+
+    /// Sets how many items are requested per call. Defaults to the endpoint's maximum.
+    pub fn page_size(mut self, page_size: u32) -> Self {
+        self.page_size = Some(page_size);
+        self
+    }
+}
+
+/// Describes how to page through one list endpoint whose items are `T`.
+pub(crate) struct Endpoint<T = Value> {
+    pub url: String,
+    pub query: Vec<(&'static str, String)>,
+    pub max_page_size: u32,
+    pub supports_before: bool,
+    pub items: fn(Value) -> PageResult<Vec<T>>,
+    pub id: fn(&T) -> Option<&str>,
+}
+
+/// Returns the items of a response that is a plain JSON array.
+pub(crate) fn array_items(response: Value) -> PageResult<Vec<Value>> {
+    match response {
+        Value::Array(items) => Ok(items),
+        _ => Ok(Vec::new()),
+    }
+}
+
+/// Returns the `id` field of an item.
+pub(crate) fn item_id(item: &Value) -> Option<&str> {
+    item["id"].as_str()
+}
+
+/// Returns the `user.id` field of an item, for lists of members, bans and event users.
+pub(crate) fn user_id(item: &Value) -> Option<&str> {
+    item["user"]["id"].as_str()
+}
+
+struct State<T> {
+    client: Client,
+    token: String,
+    endpoint: Endpoint<T>,
+    direction: Direction,
+    cursor: String,
+    remaining: Option<usize>,
+    page_size: u32,
+    buffer: VecDeque<T>,
+    exhausted: bool,
+}
+
+impl<T> State<T> {
+    async fn fetch_page(&mut self) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let limit = match self.remaining {
+            Some(remaining) => self.page_size.min(u32::try_from(remaining).unwrap_or(u32::MAX)),
+            None => self.page_size,
+        };
+        let cursor_param = match self.direction {
+            Direction::After => "after",
+            Direction::Before => "before",
+        };
+
+        let response: Value = self.client.get(&self.endpoint.url)
+            .bearer_auth(&self.token)
+            .query(&self.endpoint.query)
+            .query(&[(cursor_param, self.cursor.as_str())])
+            .query(&[("limit", limit)])
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        let mut page = (self.endpoint.items)(response)?;
+        if page.len() < limit as usize {
+            self.exhausted = true;
+        }
+
+        // Endpoints differ in the order they return items in, so sort by ID to
+        // walk in a consistent direction and to find the next cursor.
+        let id_of = self.endpoint.id;
+        let snowflake = |item: &T| id_of(item).and_then(|id| id.parse::<u64>().ok()).unwrap_or(0);
+        page.sort_by_key(snowflake);
+        if self.direction == Direction::Before {
+            page.reverse();
+        }
+
+        match page.last().and_then(id_of) {
+            Some(id) => self.cursor = id.to_string(),
+            None => self.exhausted = true,
+        }
+        self.buffer.extend(page);
+
+        Ok(())
+    }
+}
+
+/// Creates a stream that follows the cursors of `endpoint` until the list or
+/// `options.max_items` is exhausted. A failed request is yielded once and ends the stream.
+pub(crate) fn paginate<T: Send + 'static>(client: &Client, token: &str, endpoint: Endpoint<T>, options: PaginationOptions) -> BoxStream<'static, PageResult<T>> {
+    if options.direction == Direction::Before && !endpoint.supports_before {
+        let error: Box<dyn Error + Send + Sync> = "this endpoint can only be paged forward".into();
+        return stream::once(async move { Err(error) }).boxed();
+    }
+
+    let cursor = options.start.unwrap_or_else(|| match options.direction {
+        Direction::After => "0".to_string(),
+        Direction::Before => MAX_SNOWFLAKE.to_string(),
+    });
+    let page_size = options.page_size.unwrap_or(endpoint.max_page_size).clamp(1, endpoint.max_page_size);
+    let state = State {
+        client: client.clone(),
+        token: token.to_string(),
+        endpoint,
+        direction: options.direction,
+        cursor,
+        remaining: options.max_items,
+        page_size,
+        buffer: VecDeque::new(),
+        exhausted: false,
+    };
+
+    stream::unfold(state, |mut state| async move {
+        if state.remaining == Some(0) {
+            return None;
+        }
+        if state.buffer.is_empty() && !state.exhausted {
+            if let Err(error) = state.fetch_page().await {
+                state.exhausted = true;
+                state.buffer.clear();
+                return Some((Err(error), state));
+            }
+        }
+
+        let item = state.buffer.pop_front()?;
+        state.remaining = state.remaining.map(|remaining| remaining - 1);
+        Some((Ok(item), state))
+    })
+    .boxed()
+}
@@ -2,6 +2,11 @@ use reqwest::Client;
 use serde_json::Value;
 use std::error::Error;
 
+use crate::cache::Cache;
+
+/// The `ADMINISTRATOR` permission bit, which implies every other permission.
+pub const ADMINISTRATOR: u64 = 1 << 3;
+
 /// Checks if a user has a specific permission in a Discord guild.
 ///
 /// # Arguments
@@ -32,3 +37,39 @@ pub async fn check_permission(client: &Client, token: &str, guild_id: &str, user
         Ok(false)
     }
 }
+
+/// Checks if a user has a permission in a Discord guild, using the cache for the
+/// guild, member and roles and only falling back to the REST API for what is missing.
+///
+/// Channel permission overwrites are not taken into account.
+///
+/// # Arguments
+///
+/// * `cache` - The cache to read from.
+/// * `client` - The HTTP client used to send requests on a cache miss.
+/// * `token` - The bot token for authentication.
+/// * `guild_id` - The ID of the guild.
+/// * `user_id` - The ID of the user.
+/// * `permission` - The permission bit(s) to check for, for example `1 << 2` for `BAN_MEMBERS`.
+///
+/// # Returns
+///
+/// A result indicating whether the user has all of the given permission bits.
+#[allow(dead_code)]
+pub async fn check_permission_cached(cache: &Cache, client: &Client, token: &str, guild_id: &str, user_id: &str, permission: u64) -> Result<bool, Box<dyn Error>> {
+    let guild = cache.guild_or_fetch(client, token, guild_id).await?;
+    if guild.owner_id.as_deref() == Some(user_id) {
+        return Ok(true);
+    }
+
+    let member = cache.member_or_fetch(client, token, guild_id, user_id).await?;
+    let roles = cache.guild_roles_or_fetch(client, token, guild_id).await?;
+
+    // The @everyone role shares its ID with the guild.
+    let permissions = roles.iter()
+        .filter(|role| role.id == guild_id || member.roles.contains(&role.id))
+        .filter_map(|role| role.permissions.parse::<u64>().ok())
+        .fold(0, |permissions, role_permissions| permissions | role_permissions);
+
+    Ok(permissions & ADMINISTRATOR != 0 || permissions & permission == permission)
+}
@@ -1,7 +1,10 @@
+use futures::stream::BoxStream;
 use reqwest::Client;
 use serde_json::Value;
 use std::error::Error;
 
+use super::pagination::{self, Endpoint, PageResult, PaginationOptions};
+
 #[allow(dead_code)]
 /// Fetches the voters for a specific answer in a poll.
 ///
@@ -42,6 +45,37 @@ pub async fn get_answer_voters(client: &Client, token: &str, channel_id: &str, m
     Ok(response)
 }
 
+#[allow(dead_code)]
+/// Streams the voters for a specific answer in a poll, following the `after` cursor.
+///
+/// # Arguments
+///
+/// * `client` - The HTTP client used to send the requests.
+/// * `token` - The bot token for authentication.
+/// * `channel_id` - The ID of the channel.
+/// * `message_id` - The ID of the poll message.
+/// * `answer_id` - The ID of the answer to fetch voters for.
+/// * `options` - The starting user and maximum number of voters. Only `Direction::After` is supported.
+///
+/// # Returns
+///
+/// A stream of users as JSON values.
+pub fn stream_answer_voters(client: &Client, token: &str, channel_id: &str, message_id: &str, answer_id: &str, options: PaginationOptions) -> BoxStream<'static, PageResult> {
+    let endpoint = Endpoint {
+        url: format!("https://discord.com/api/v9/channels/{}/polls/{}/answers/{}", channel_id, message_id, answer_id),
+        query: Vec::new(),
+        max_page_size: 100,
+        supports_before: false,
+        items: |response| match response.get("users") {
+            Some(Value::Array(users)) => Ok(users.clone()),
+            _ => Ok(Vec::new()),
+        },
+        id: pagination::item_id,
+    };
+
+    pagination::paginate(client, token, endpoint, options)
+}
+
 #[allow(dead_code)]
 /// Ends a poll.
 ///
@@ -2,6 +2,8 @@ use reqwest::Client;
 use serde_json::Value;
 use std::error::Error;
 
+use super::audit_log::AuditLogReason;
+
 /// Adds a role to a user in a Discord guild.
 ///
 /// # Arguments
@@ -11,16 +13,18 @@ use std::error::Error;
 /// * `guild_id` - The ID of the guild.
 /// * `user_id` - The ID of the user to add the role to.
 /// * `role_id` - The ID of the role to add.
+/// * `reason` - The reason shown in the audit log, if any.
 ///
 /// # Returns
 ///
 /// A result indicating success or failure.
 #[allow(dead_code)]
-pub async fn add_role(client: &Client, token: &str, guild_id: &str, user_id: &str, role_id: &str) -> Result<(), Box<dyn Error>> {
+pub async fn add_role(client: &Client, token: &str, guild_id: &str, user_id: &str, role_id: &str, reason: Option<&str>) -> Result<(), Box<dyn Error>> {
     let url = format!("https://discord.com/api/v9/guilds/{}/members/{}/roles/{}", guild_id, user_id, role_id);
     
     client.put(&url)
         .bearer_auth(token)
+        .audit_log_reason(reason)
         .send()
         .await?
         .error_for_status()?;
@@ -37,16 +41,18 @@ pub async fn add_role(client: &Client, token: &str, guild_id: &str, user_id: &st
 /// * `guild_id` - The ID of the guild.
 /// * `user_id` - The ID of the user to remove the role from.
 /// * `role_id` - The ID of the role to remove.
+/// * `reason` - The reason shown in the audit log, if any.
 ///
 /// # Returns
 ///
 /// A result indicating success or failure.
 #[allow(dead_code)]
-pub async fn remove_role(client: &Client, token: &str, guild_id: &str, user_id: &str, role_id: &str) -> Result<(), Box<dyn Error>> {
+pub async fn remove_role(client: &Client, token: &str, guild_id: &str, user_id: &str, role_id: &str, reason: Option<&str>) -> Result<(), Box<dyn Error>> {
     let url = format!("https://discord.com/api/v9/guilds/{}/members/{}/roles/{}", guild_id, user_id, role_id);
     
     client.delete(&url)
         .bearer_auth(token)
+        .audit_log_reason(reason)
         .send()
         .await?
         .error_for_status()?;
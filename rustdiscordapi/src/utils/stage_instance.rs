@@ -0,0 +1,337 @@
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use serde_repr::{Deserialize_repr, Serialize_repr};
+use std::error::Error;
+use std::time::SystemTime;
+
+use super::audit_log::AuditLogReason;
+use super::timestamp::format_timestamp;
+
+/// The longest topic a stage instance can have, in characters.
+pub const MAX_TOPIC_LENGTH: usize = 120;
+
+/// Who can find a stage instance.
+#[derive(Serialize_repr, Deserialize_repr, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum PrivacyLevel {
+    /// Visible outside the guild. No longer supported by Discord.
+    Public = 1,
+    /// Only visible to guild members.
+    GuildOnly = 2,
+}
+
+/// A live stage in a stage channel.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct StageInstance {
+    pub id: String,
+    pub guild_id: String,
+    pub channel_id: String,
+    pub topic: String,
+    pub privacy_level: PrivacyLevel,
+    /// The scheduled event the stage was started for, if any.
+    #[serde(default)]
+    pub guild_scheduled_event_id: Option<String>,
+}
+
+/// Builds the body for starting or modifying a stage instance.
+///
+/// # Examples
+///
+/// ```
+/// use Rust_Discord_API::stage_instance::{PrivacyLevel, StageInstanceBuilder};
+///
+/// let stage = StageInstanceBuilder::new("1015385093587439686", "Weekly Q&A")
+///     .send_start_notification(true)
+///     .guild_scheduled_event("1015385107713974272");
+/// assert_eq!(stage.build()["guild_scheduled_event_id"], "1015385107713974272");
+///
+/// // Only the fields set are changed when modifying a stage.
+/// let changes = StageInstanceBuilder::edit().privacy_level(PrivacyLevel::GuildOnly);
+/// assert_eq!(changes.build(), serde_json::json!({ "privacy_level": 2 }));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct StageInstanceBuilder {
+    channel_id: Option<String>,
+    topic: Option<String>,
+    privacy_level: Option<PrivacyLevel>,
+    send_start_notification: Option<bool>,
+    guild_scheduled_event_id: Option<String>,
+}
+
+impl StageInstanceBuilder {
+    /// Starts a stage in a stage channel.
+    pub fn new(channel_id: &str, topic: &str) -> Self {
+        Self { channel_id: Some(channel_id.to_string()), topic: Some(topic.to_string()), ..Self::default() }
+    }
+
+    /// Starts the changes to a running stage. Only its topic and privacy
+    /// level can be changed.
+    pub fn edit() -> Self {
+        Self::default()
+    }
+
+    /// Sets the topic of the stage, at most `MAX_TOPIC_LENGTH` characters.
+    pub fn topic(mut self, topic: &str) -> Self {
+        self.topic = Some(topic.to_string());
+        self
+    }
+
+    /// Sets who can find the stage. Defaults to `PrivacyLevel::GuildOnly`.
+    pub fn privacy_level(mut self, privacy_level: PrivacyLevel) -> Self {
+        self.privacy_level = Some(privacy_level);
+        self
+    }
+
+    /// Sets whether @everyone is notified that the stage started. Needs the
+    /// `MENTION_EVERYONE` permission.
+    pub fn send_start_notification(mut self, notify: bool) -> Self {
+        self.send_start_notification = Some(notify);
+        self
+    }
+
+    /// Links the stage to the scheduled event it was started for.
+    pub fn guild_scheduled_event(mut self, event_id: &str) -> Self {
+        self.guild_scheduled_event_id = Some(event_id.to_string());
+        self
+    }
+
+    /// Builds the JSON body of the stage instance.
+    pub fn build(&self) -> Value {
+        let mut body = json!({});
+        if let Some(channel_id) = &self.channel_id {
+            body["channel_id"] = json!(channel_id);
+        }
+        if let Some(topic) = &self.topic {
+            body["topic"] = json!(topic);
+        }
+        if let Some(privacy_level) = self.privacy_level {
+            body["privacy_level"] = json!(privacy_level);
+        }
+        if let Some(notify) = self.send_start_notification {
+            body["send_start_notification"] = json!(notify);
+        }
+        if let Some(event_id) = &self.guild_scheduled_event_id {
+            body["guild_scheduled_event_id"] = json!(event_id);
+        }
+        body
+    }
+
+    fn validate(&self) -> Result<(), Box<dyn Error>> {
+        if let Some(topic) = &self.topic {
+            let length = topic.chars().count();
+            if length == 0 || length > MAX_TOPIC_LENGTH {
+                return Err(format!("stage topic must be 1 to {} characters, got {}", MAX_TOPIC_LENGTH, length).into());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Starts a stage in a stage channel. Needs the `MANAGE_CHANNELS`,
+/// `MUTE_MEMBERS` and `MOVE_MEMBERS` permissions in the channel.
+///
+/// # Arguments
+///
+/// * `client` - The HTTP client used to send the request.
+/// * `token` - The bot token for authentication.
+/// * `stage` - The stage, started with `StageInstanceBuilder::new`.
+/// * `reason` - The reason shown in the audit log, if any.
+///
+/// # Returns
+///
+/// A result containing the stage instance, or an error if the topic is empty or too long.
+#[allow(dead_code)]
+pub async fn create_stage_instance(client: &Client, token: &str, stage: &StageInstanceBuilder, reason: Option<&str>) -> Result<StageInstance, Box<dyn Error>> {
+    stage.validate()?;
+    let url = "https://discord.com/api/v9/stage-instances";
+    let response: StageInstance = client.post(url)
+        .bearer_auth(token)
+        .audit_log_reason(reason)
+        .json(&stage.build())
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    Ok(response)
+}
+
+/// Fetches the stage running in a stage channel.
+///
+/// # Arguments
+///
+/// * `client` - The HTTP client used to send the request.
+/// * `token` - The bot token for authentication.
+/// * `channel_id` - The ID of the stage channel.
+///
+/// # Returns
+///
+/// A result containing the stage instance.
+#[allow(dead_code)]
+pub async fn get_stage_instance(client: &Client, token: &str, channel_id: &str) -> Result<StageInstance, Box<dyn Error>> {
+    let url = format!("https://discord.com/api/v9/stage-instances/{}", channel_id);
+    let response: StageInstance = client.get(&url)
+        .bearer_auth(token)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    Ok(response)
+}
+
+/// Changes the topic or privacy level of the stage running in a stage channel.
+///
+/// # Arguments
+///
+/// * `client` - The HTTP client used to send the request.
+/// * `token` - The bot token for authentication.
+/// * `channel_id` - The ID of the stage channel.
+/// * `changes` - The changes, started with `StageInstanceBuilder::edit`.
+/// * `reason` - The reason shown in the audit log, if any.
+///
+/// # Returns
+///
+/// A result containing the modified stage instance.
+#[allow(dead_code)]
+pub async fn modify_stage_instance(client: &Client, token: &str, channel_id: &str, changes: &StageInstanceBuilder, reason: Option<&str>) -> Result<StageInstance, Box<dyn Error>> {
+    changes.validate()?;
+    let url = format!("https://discord.com/api/v9/stage-instances/{}", channel_id);
+    let response: StageInstance = client.patch(&url)
+        .bearer_auth(token)
+        .audit_log_reason(reason)
+        .json(&changes.build())
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    Ok(response)
+}
+
+/// Ends the stage running in a stage channel.
+///
+/// # Arguments
+///
+/// * `client` - The HTTP client used to send the request.
+/// * `token` - The bot token for authentication.
+/// * `channel_id` - The ID of the stage channel.
+/// * `reason` - The reason shown in the audit log, if any.
+///
+/// # Returns
+///
+/// A result indicating success or failure.
+#[allow(dead_code)]
+pub async fn delete_stage_instance(client: &Client, token: &str, channel_id: &str, reason: Option<&str>) -> Result<(), Box<dyn Error>> {
+    let url = format!("https://discord.com/api/v9/stage-instances/{}", channel_id);
+
+    client.delete(&url)
+        .bearer_auth(token)
+        .audit_log_reason(reason)
+        .send()
+        .await?
+        .error_for_status()?;
+
+    Ok(())
+}
+
+async fn set_suppressed(client: &Client, token: &str, guild_id: &str, channel_id: &str, user_id: &str, suppress: bool) -> Result<(), Box<dyn Error>> {
+    let url = format!("https://discord.com/api/v9/guilds/{}/voice-states/{}", guild_id, user_id);
+
+    client.patch(&url)
+        .bearer_auth(token)
+        .json(&json!({ "channel_id": channel_id, "suppress": suppress }))
+        .send()
+        .await?
+        .error_for_status()?;
+
+    Ok(())
+}
+
+/// Invites a member of the stage's audience to speak. They become a speaker
+/// once they accept. Needs the `MUTE_MEMBERS` permission.
+///
+/// # Arguments
+///
+/// * `client` - The HTTP client used to send the request.
+/// * `token` - The bot token for authentication.
+/// * `guild_id` - The ID of the guild.
+/// * `channel_id` - The ID of the stage channel the member is in.
+/// * `user_id` - The ID of the member.
+///
+/// # Returns
+///
+/// A result indicating success or failure.
+#[allow(dead_code)]
+pub async fn invite_to_speak(client: &Client, token: &str, guild_id: &str, channel_id: &str, user_id: &str) -> Result<(), Box<dyn Error>> {
+    set_suppressed(client, token, guild_id, channel_id, user_id, false).await
+}
+
+/// Approves a member's request to speak, making them a speaker. Needs the
+/// `MUTE_MEMBERS` permission.
+///
+/// # Arguments
+///
+/// * `client` - The HTTP client used to send the request.
+/// * `token` - The bot token for authentication.
+/// * `guild_id` - The ID of the guild.
+/// * `channel_id` - The ID of the stage channel the member is in.
+/// * `user_id` - The ID of the member who raised their hand.
+///
+/// # Returns
+///
+/// A result indicating success or failure.
+#[allow(dead_code)]
+pub async fn approve_request_to_speak(client: &Client, token: &str, guild_id: &str, channel_id: &str, user_id: &str) -> Result<(), Box<dyn Error>> {
+    set_suppressed(client, token, guild_id, channel_id, user_id, false).await
+}
+
+/// Denies a member's request to speak, or moves a speaker back to the
+/// audience. Needs the `MUTE_MEMBERS` permission.
+///
+/// # Arguments
+///
+/// * `client` - The HTTP client used to send the request.
+/// * `token` - The bot token for authentication.
+/// * `guild_id` - The ID of the guild.
+/// * `channel_id` - The ID of the stage channel the member is in.
+/// * `user_id` - The ID of the member.
+///
+/// # Returns
+///
+/// A result indicating success or failure.
+#[allow(dead_code)]
+pub async fn deny_request_to_speak(client: &Client, token: &str, guild_id: &str, channel_id: &str, user_id: &str) -> Result<(), Box<dyn Error>> {
+    set_suppressed(client, token, guild_id, channel_id, user_id, true).await
+}
+
+/// Raises the current user's hand in a stage channel it is in.
+///
+/// # Arguments
+///
+/// * `client` - The HTTP client used to send the request.
+/// * `token` - The bot token for authentication.
+/// * `guild_id` - The ID of the guild.
+/// * `channel_id` - The ID of the stage channel.
+///
+/// # Returns
+///
+/// A result indicating success or failure.
+#[allow(dead_code)]
+pub async fn request_to_speak(client: &Client, token: &str, guild_id: &str, channel_id: &str) -> Result<(), Box<dyn Error>> {
+    let url = format!("https://discord.com/api/v9/guilds/{}/voice-states/@me", guild_id);
+    let body = json!({ "channel_id": channel_id, "request_to_speak_timestamp": format_timestamp(SystemTime::now()) });
+
+    client.patch(&url)
+        .bearer_auth(token)
+        .json(&body)
+        .send()
+        .await?
+        .error_for_status()?;
+
+    Ok(())
+}
@@ -2,6 +2,8 @@ use reqwest::Client;
 use serde_json::Value;
 use std::error::Error;
 
+use super::audit_log::AuditLogReason;
+
 #[allow(dead_code)]
 /// Fetches a sticker by its ID.
 ///
@@ -107,14 +109,16 @@ pub async fn get_guild_sticker(client: &Client, token: &str, guild_id: &str, sti
 /// * `token` - The bot token for authentication.
 /// * `guild_id` - The ID of the guild.
 /// * `sticker_data` - The JSON value of the sticker data.
+/// * `reason` - The reason shown in the audit log, if any.
 ///
 /// # Returns
 ///
 /// A result containing the created guild sticker information as a JSON value.
-pub async fn create_guild_sticker(client: &Client, token: &str, guild_id: &str, sticker_data: Value) -> Result<Value, Box<dyn Error>> {
+pub async fn create_guild_sticker(client: &Client, token: &str, guild_id: &str, sticker_data: Value, reason: Option<&str>) -> Result<Value, Box<dyn Error>> {
     let url = format!("https://discord.com/api/v9/guilds/{}/stickers", guild_id);
     let response: Value = client.post(&url)
         .bearer_auth(token)
+        .audit_log_reason(reason)
         .json(&sticker_data)
         .send()
         .await?
@@ -134,14 +138,16 @@ pub async fn create_guild_sticker(client: &Client, token: &str, guild_id: &str,
 /// * `guild_id` - The ID of the guild.
 /// * `sticker_id` - The ID of the sticker to modify.
 /// * `sticker_data` - The JSON value of the sticker data.
+/// * `reason` - The reason shown in the audit log, if any.
 ///
 /// # Returns
 ///
 /// A result containing the modified guild sticker information as a JSON value.
-pub async fn modify_guild_sticker(client: &Client, token: &str, guild_id: &str, sticker_id: &str, sticker_data: Value) -> Result<Value, Box<dyn Error>> {
+pub async fn modify_guild_sticker(client: &Client, token: &str, guild_id: &str, sticker_id: &str, sticker_data: Value, reason: Option<&str>) -> Result<Value, Box<dyn Error>> {
     let url = format!("https://discord.com/api/v9/guilds/{}/stickers/{}", guild_id, sticker_id);
     let response: Value = client.patch(&url)
         .bearer_auth(token)
+        .audit_log_reason(reason)
         .json(&sticker_data)
         .send()
         .await?
@@ -160,15 +166,17 @@ pub async fn modify_guild_sticker(client: &Client, token: &str, guild_id: &str,
 /// * `token` - The bot token for authentication.
 /// * `guild_id` - The ID of the guild.
 /// * `sticker_id` - The ID of the sticker to delete.
+/// * `reason` - The reason shown in the audit log, if any.
 ///
 /// # Returns
 ///
 /// A result indicating success or failure.
-pub async fn delete_guild_sticker(client: &Client, token: &str, guild_id: &str, sticker_id: &str) -> Result<(), Box<dyn Error>> {
+pub async fn delete_guild_sticker(client: &Client, token: &str, guild_id: &str, sticker_id: &str, reason: Option<&str>) -> Result<(), Box<dyn Error>> {
     let url = format!("https://discord.com/api/v9/guilds/{}/stickers/{}", guild_id, sticker_id);
     
     client.delete(&url)
         .bearer_auth(token)
+        .audit_log_reason(reason)
         .send()
         .await?
         .error_for_status()?;
@@ -2,6 +2,8 @@ use reqwest::Client;
 use serde_json::Value;
 use std::error::Error;
 
+use super::audit_log::AuditLogReason;
+
 #[allow(dead_code)]
 /// Fetches the current user's information.
 ///
@@ -280,15 +282,17 @@ pub async fn update_current_user_application_role_connection(client: &Client, to
 /// * `token` - The bot token for authentication.
 /// * `guild_id` - The ID of the guild.
 /// * `user_id` - The ID of the user to kick.
+/// * `reason` - The reason shown in the audit log, if any.
 ///
 /// # Returns
 ///
 /// A result indicating success or failure.
-pub async fn kick_user(client: &Client, token: &str, guild_id: &str, user_id: &str) -> Result<(), Box<dyn Error>> {
+pub async fn kick_user(client: &Client, token: &str, guild_id: &str, user_id: &str, reason: Option<&str>) -> Result<(), Box<dyn Error>> {
     let url = format!("https://discord.com/api/v9/guilds/{}/members/{}", guild_id, user_id);
     
     client.delete(&url)
         .bearer_auth(token)
+        .audit_log_reason(reason)
         .send()
         .await?
         .error_for_status()?;
@@ -306,20 +310,20 @@ pub async fn kick_user(client: &Client, token: &str, guild_id: &str, user_id: &s
 /// * `guild_id` - The ID of the guild.
 /// * `user_id` - The ID of the user to ban.
 /// * `delete_message_days` - The number of days to delete messages for (0-7).
-/// * `reason` - The reason for the ban.
+/// * `reason` - The reason shown in the audit log, if any.
 ///
 /// # Returns
 ///
 /// A result indicating success or failure.
-pub async fn ban_user(client: &Client, token: &str, guild_id: &str, user_id: &str, delete_message_days: u8, reason: &str) -> Result<(), Box<dyn Error>> {
+pub async fn ban_user(client: &Client, token: &str, guild_id: &str, user_id: &str, delete_message_days: u8, reason: Option<&str>) -> Result<(), Box<dyn Error>> {
     let url = format!("https://discord.com/api/v9/guilds/{}/bans/{}", guild_id, user_id);
     let body = serde_json::json!({
-        "delete_message_days": delete_message_days,
-        "reason": reason
+        "delete_message_days": delete_message_days
     });
     
     client.put(&url)
         .bearer_auth(token)
+        .audit_log_reason(reason)
         .json(&body)
         .send()
         .await?
@@ -2,6 +2,8 @@ use reqwest::Client;
 use serde_json::Value;
 use std::error::Error;
 
+use super::audit_log::AuditLogReason;
+
 #[allow(dead_code)]
 /// Creates a new webhook.
 ///
@@ -11,14 +13,16 @@ use std::error::Error;
 /// * `token` - The bot token for authentication.
 /// * `channel_id` - The ID of the channel to create the webhook in.
 /// * `webhook_settings` - The JSON value of the webhook settings.
+/// * `reason` - The reason shown in the audit log, if any.
 ///
 /// # Returns
 ///
 /// A result containing the created webhook information as a JSON value.
-pub async fn create_webhook(client: &Client, token: &str, channel_id: &str, webhook_settings: Value) -> Result<Value, Box<dyn Error>> {
+pub async fn create_webhook(client: &Client, token: &str, channel_id: &str, webhook_settings: Value, reason: Option<&str>) -> Result<Value, Box<dyn Error>> {
     let url = format!("https://discord.com/api/v9/channels/{}/webhooks", channel_id);
     let response: Value = client.post(&url)
         .bearer_auth(token)
+        .audit_log_reason(reason)
         .json(&webhook_settings)
         .send()
         .await?
@@ -132,14 +136,16 @@ pub async fn get_webhook_with_token(client: &Client, webhook_id: &str, webhook_t
 /// * `token` - The bot token for authentication.
 /// * `webhook_id` - The ID of the webhook to modify.
 /// * `settings` - The JSON value of the settings to update.
+/// * `reason` - The reason shown in the audit log, if any.
 ///
 /// # Returns
 ///
 /// A result containing the modified webhook information as a JSON value.
-pub async fn modify_webhook(client: &Client, token: &str, webhook_id: &str, settings: Value) -> Result<Value, Box<dyn Error>> {
+pub async fn modify_webhook(client: &Client, token: &str, webhook_id: &str, settings: Value, reason: Option<&str>) -> Result<Value, Box<dyn Error>> {
     let url = format!("https://discord.com/api/v9/webhooks/{}", webhook_id);
     let response: Value = client.patch(&url)
         .bearer_auth(token)
+        .audit_log_reason(reason)
         .json(&settings)
         .send()
         .await?
@@ -158,13 +164,15 @@ pub async fn modify_webhook(client: &Client, token: &str, webhook_id: &str, sett
 /// * `webhook_id` - The ID of the webhook to modify.
 /// * `webhook_token` - The token of the webhook to modify.
 /// * `settings` - The JSON value of the settings to update.
+/// * `reason` - The reason shown in the audit log, if any.
 ///
 /// # Returns
 ///
 /// A result containing the modified webhook information as a JSON value.
-pub async fn modify_webhook_with_token(client: &Client, webhook_id: &str, webhook_token: &str, settings: Value) -> Result<Value, Box<dyn Error>> {
+pub async fn modify_webhook_with_token(client: &Client, webhook_id: &str, webhook_token: &str, settings: Value, reason: Option<&str>) -> Result<Value, Box<dyn Error>> {
     let url = format!("https://discord.com/api/v9/webhooks/{}/{}", webhook_id, webhook_token);
     let response: Value = client.patch(&url)
+        .audit_log_reason(reason)
         .json(&settings)
         .send()
         .await?
@@ -182,15 +190,17 @@ pub async fn modify_webhook_with_token(client: &Client, webhook_id: &str, webhoo
 /// * `client` - The HTTP client used to send the request.
 /// * `token` - The bot token for authentication.
 /// * `webhook_id` - The ID of the webhook to delete.
+/// * `reason` - The reason shown in the audit log, if any.
 ///
 /// # Returns
 ///
 /// A result indicating success or failure.
-pub async fn delete_webhook(client: &Client, token: &str, webhook_id: &str) -> Result<(), Box<dyn Error>> {
+pub async fn delete_webhook(client: &Client, token: &str, webhook_id: &str, reason: Option<&str>) -> Result<(), Box<dyn Error>> {
     let url = format!("https://discord.com/api/v9/webhooks/{}", webhook_id);
     
     client.delete(&url)
         .bearer_auth(token)
+        .audit_log_reason(reason)
         .send()
         .await?
         .error_for_status()?;
@@ -206,14 +216,16 @@ pub async fn delete_webhook(client: &Client, token: &str, webhook_id: &str) -> R
 /// * `client` - The HTTP client used to send the request.
 /// * `webhook_id` - The ID of the webhook to delete.
 /// * `webhook_token` - The token of the webhook to delete.
+/// * `reason` - The reason shown in the audit log, if any.
 ///
 /// # Returns
 ///
 /// A result indicating success or failure.
-pub async fn delete_webhook_with_token(client: &Client, webhook_id: &str, webhook_token: &str) -> Result<(), Box<dyn Error>> {
+pub async fn delete_webhook_with_token(client: &Client, webhook_id: &str, webhook_token: &str, reason: Option<&str>) -> Result<(), Box<dyn Error>> {
     let url = format!("https://discord.com/api/v9/webhooks/{}/{}", webhook_id, webhook_token);
     
     client.delete(&url)
+        .audit_log_reason(reason)
         .send()
         .await?
         .error_for_status()?;
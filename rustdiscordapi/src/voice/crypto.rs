@@ -0,0 +1,152 @@
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::Aes256Gcm;
+use chacha20poly1305::XChaCha20Poly1305;
+
+use super::rtp::RtpHeader;
+use super::VoiceError;
+
+/// Bytes of the nonce counter appended to each packet.
+const NONCE_SIZE: usize = 4;
+
+/// The encryption modes for voice packets. Discord only accepts the AEAD
+/// `_rtpsize` modes: the RTP header is authenticated but sent in the clear,
+/// and a 32-bit counter padded with zeroes is the nonce, appended to the packet.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EncryptionMode {
+    /// AES-256-GCM, preferred when the voice server offers it.
+    Aes256Gcm,
+    /// XChaCha20-Poly1305, which every voice server supports.
+    XChaCha20Poly1305,
+}
+
+impl EncryptionMode {
+    /// Returns the name sent in Select Protocol.
+    pub fn name(&self) -> &'static str {
+        match self {
+            EncryptionMode::Aes256Gcm => "aead_aes256_gcm_rtpsize",
+            EncryptionMode::XChaCha20Poly1305 => "aead_xchacha20_poly1305_rtpsize",
+        }
+    }
+
+    /// Parses a mode name from Ready or the session description.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "aead_aes256_gcm_rtpsize" => Some(EncryptionMode::Aes256Gcm),
+            "aead_xchacha20_poly1305_rtpsize" => Some(EncryptionMode::XChaCha20Poly1305),
+            _ => None,
+        }
+    }
+
+    /// Picks the best supported mode among those a voice server offers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use Rust_Discord_API::voice::EncryptionMode;
+    ///
+    /// let modes = ["xsalsa20_poly1305", "aead_xchacha20_poly1305_rtpsize", "aead_aes256_gcm_rtpsize"].map(String::from);
+    /// assert_eq!(EncryptionMode::preferred(&modes), Some(EncryptionMode::Aes256Gcm));
+    /// ```
+    pub fn preferred(modes: &[String]) -> Option<Self> {
+        [EncryptionMode::Aes256Gcm, EncryptionMode::XChaCha20Poly1305].into_iter().find(|mode| modes.iter().any(|offered| offered == mode.name()))
+    }
+}
+
+enum Algorithm {
+    Aes256Gcm(Box<Aes256Gcm>),
+    XChaCha20Poly1305(Box<XChaCha20Poly1305>),
+}
+
+/// Encrypts and decrypts voice packets with the key from the session description.
+pub struct Cipher {
+    mode: EncryptionMode,
+    aead: Algorithm,
+}
+
+impl Cipher {
+    /// Creates a cipher for a mode and its 32-byte secret key.
+    pub fn new(mode: EncryptionMode, secret_key: &[u8]) -> Result<Self, VoiceError> {
+        let invalid = |_| VoiceError::Protocol(format!("the secret key must be 32 bytes, not {}", secret_key.len()));
+        let aead = match mode {
+            EncryptionMode::Aes256Gcm => Algorithm::Aes256Gcm(Box::new(Aes256Gcm::new_from_slice(secret_key).map_err(invalid)?)),
+            EncryptionMode::XChaCha20Poly1305 => Algorithm::XChaCha20Poly1305(Box::new(XChaCha20Poly1305::new_from_slice(secret_key).map_err(invalid)?)),
+        };
+        Ok(Self { mode, aead })
+    }
+
+    /// Returns the encryption mode.
+    pub fn mode(&self) -> EncryptionMode {
+        self.mode
+    }
+
+    /// Builds an encrypted packet: the header, the encrypted payload with
+    /// its tag, then the nonce counter.
+    ///
+    /// # Arguments
+    ///
+    /// * `header` - The RTP header, sent in the clear and authenticated.
+    /// * `payload` - The audio payload, such as an Opus packet.
+    /// * `nonce` - The nonce counter, which must not repeat for a key.
+    ///
+    /// # Returns
+    ///
+    /// The packet to send.
+    pub fn encrypt(&self, header: &[u8], payload: &[u8], nonce: u32) -> Vec<u8> {
+        let payload = Payload { msg: payload, aad: header };
+        let encrypted = match &self.aead {
+            Algorithm::Aes256Gcm(aead) => aead.encrypt(&nonce_bytes::<12>(nonce).into(), payload),
+            Algorithm::XChaCha20Poly1305(aead) => aead.encrypt(&nonce_bytes::<24>(nonce).into(), payload),
+        }
+        .expect("encrypting a voice packet cannot fail with a valid key");
+
+        let mut packet = Vec::with_capacity(header.len() + encrypted.len() + NONCE_SIZE);
+        packet.extend_from_slice(header);
+        packet.extend_from_slice(&encrypted);
+        packet.extend_from_slice(&nonce.to_be_bytes());
+        packet
+    }
+
+    /// Decrypts a received packet.
+    ///
+    /// # Arguments
+    ///
+    /// * `packet` - The packet as received.
+    ///
+    /// # Returns
+    ///
+    /// A result containing the header and the audio payload, without any header extension.
+    pub fn decrypt(&self, packet: &[u8]) -> Result<(RtpHeader, Vec<u8>), VoiceError> {
+        let header = RtpHeader::parse(packet).ok_or_else(|| VoiceError::Protocol("the voice packet is too short for an RTP header".to_string()))?;
+        // Only the first 4 bytes of a header extension are authenticated; its
+        // contents are encrypted with the payload.
+        let authenticated = header.size() + if header.extension { 4 } else { 0 };
+        if packet.len() < authenticated + NONCE_SIZE {
+            return Err(VoiceError::Protocol("the voice packet is too short".to_string()));
+        }
+        let (body, nonce) = packet.split_at(packet.len() - NONCE_SIZE);
+        let nonce = u32::from_be_bytes(nonce.try_into().unwrap());
+        let payload = Payload { msg: &body[authenticated..], aad: &body[..authenticated] };
+        let decrypted = match &self.aead {
+            Algorithm::Aes256Gcm(aead) => aead.decrypt(&nonce_bytes::<12>(nonce).into(), payload),
+            Algorithm::XChaCha20Poly1305(aead) => aead.decrypt(&nonce_bytes::<24>(nonce).into(), payload),
+        }
+        .map_err(|_| VoiceError::Protocol("the voice packet failed to decrypt".to_string()))?;
+
+        let mut payload = decrypted;
+        if header.extension {
+            let words = u16::from_be_bytes([packet[header.size() + 2], packet[header.size() + 3]]) as usize;
+            if payload.len() < words * 4 {
+                return Err(VoiceError::Protocol("the voice packet is shorter than its header extension".to_string()));
+            }
+            payload.drain(..words * 4);
+        }
+        Ok((header, payload))
+    }
+}
+
+/// Pads the nonce counter with zeroes to the cipher's nonce size.
+fn nonce_bytes<const N: usize>(nonce: u32) -> [u8; N] {
+    let mut bytes = [0; N];
+    bytes[..NONCE_SIZE].copy_from_slice(&nonce.to_be_bytes());
+    bytes
+}
@@ -0,0 +1,356 @@
+//! Voice connections.
+//!
+//! Joining a voice channel takes two connections: the main gateway announces
+//! the voice server with VOICE_STATE_UPDATE and VOICE_SERVER_UPDATE (see
+//! [`ShardHandle::join_voice`](crate::gateway::ShardHandle::join_voice)), then
+//! a [`VoiceGateway`] connects to that server to negotiate the audio session.
+//! With the `voice` feature, a [`VoiceUdp`] socket then carries the encrypted
+//! audio.
+
+#[cfg(feature = "voice")]
+mod crypto;
+mod rtp;
+#[cfg(feature = "voice")]
+mod udp;
+
+#[cfg(feature = "voice")]
+pub use crypto::{Cipher, EncryptionMode};
+pub use rtp::{RtpHeader, OPUS_PAYLOAD_TYPE, RTP_HEADER_SIZE};
+#[cfg(feature = "voice")]
+pub use udp::{AudioSender, VoiceUdp, FRAME_DURATION, SAMPLES_PER_FRAME, SILENCE_FRAME};
+
+use bitflags::bitflags;
+use futures::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::time::{Duration, SystemTime};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tokio::time::{interval_at, Instant};
+use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+use tokio_tungstenite::tungstenite::protocol::CloseFrame;
+use tokio_tungstenite::tungstenite::Message as WsMessage;
+use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
+
+/// The voice gateway version requested on connect.
+const VOICE_GATEWAY_VERSION: u8 = 8;
+
+/// Voice gateway opcodes.
+mod op {
+    pub const IDENTIFY: u8 = 0;
+    pub const SELECT_PROTOCOL: u8 = 1;
+    pub const READY: u8 = 2;
+    pub const HEARTBEAT: u8 = 3;
+    pub const SESSION_DESCRIPTION: u8 = 4;
+    pub const SPEAKING: u8 = 5;
+    pub const HEARTBEAT_ACK: u8 = 6;
+    pub const HELLO: u8 = 8;
+}
+
+type Socket = WebSocketStream<MaybeTlsStream<tokio::net::TcpStream>>;
+
+/// Everything needed to connect to a voice server, gathered from the
+/// VOICE_STATE_UPDATE and VOICE_SERVER_UPDATE events of the main gateway.
+#[derive(Clone, Debug, PartialEq)]
+pub struct VoiceServerInfo {
+    pub guild_id: String,
+    pub channel_id: Option<String>,
+    /// The bot's user ID.
+    pub user_id: String,
+    pub session_id: String,
+    pub token: String,
+    /// The voice server host, such as `"c-ams01.discord.media:443"`.
+    pub endpoint: String,
+}
+
+/// An error on a voice connection.
+#[derive(Debug)]
+pub enum VoiceError {
+    /// The WebSocket connection failed.
+    WebSocket(tokio_tungstenite::tungstenite::Error),
+    /// The UDP socket failed.
+    Io(io::Error),
+    /// The voice server sent something other than the expected payload.
+    Protocol(String),
+    /// The voice server closed the connection, for example with 4006 (session no longer valid).
+    Closed { code: u16, reason: String },
+}
+
+impl fmt::Display for VoiceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VoiceError::WebSocket(error) => write!(f, "voice connection failed: {}", error),
+            VoiceError::Io(error) => write!(f, "voice UDP socket failed: {}", error),
+            VoiceError::Protocol(message) => write!(f, "unexpected voice payload: {}", message),
+            VoiceError::Closed { code, reason } => write!(f, "voice server closed with code {}: {}", code, reason),
+        }
+    }
+}
+
+impl Error for VoiceError {}
+
+impl From<tokio_tungstenite::tungstenite::Error> for VoiceError {
+    fn from(error: tokio_tungstenite::tungstenite::Error) -> Self {
+        VoiceError::WebSocket(error)
+    }
+}
+
+impl From<io::Error> for VoiceError {
+    fn from(error: io::Error) -> Self {
+        VoiceError::Io(error)
+    }
+}
+
+bitflags! {
+    /// What kind of audio a user is sending, set with [`VoiceGateway::speaking`].
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    pub struct SpeakingFlags: u8 {
+        /// Normal voice audio.
+        const MICROPHONE = 1 << 0;
+        /// Audio of a stream, without a speaking indicator.
+        const SOUNDSHARE = 1 << 1;
+        /// Priority speaker, lowering other users' volume.
+        const PRIORITY = 1 << 2;
+    }
+}
+
+/// The voice server's answer to Identify.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct VoiceReady {
+    /// The synchronization source identifying our audio stream.
+    pub ssrc: u32,
+    /// The address of the UDP voice server.
+    pub ip: String,
+    pub port: u16,
+    /// The supported encryption modes.
+    pub modes: Vec<String>,
+}
+
+/// The voice server's answer to Select Protocol.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SessionDescription {
+    /// The encryption mode selected.
+    pub mode: String,
+    /// The key to encrypt voice packets with.
+    pub secret_key: Vec<u8>,
+}
+
+/// A connection to a voice server's WebSocket gateway.
+///
+/// The connection identifies on creation and keeps itself alive with
+/// heartbeats in a background task until it is dropped.
+///
+/// # Examples
+///
+/// ```no_run
+/// use Rust_Discord_API::gateway::ShardHandle;
+/// use Rust_Discord_API::voice::VoiceGateway;
+///
+/// async fn join(handle: &ShardHandle) -> Result<(), Box<dyn std::error::Error>> {
+///     let info = handle.join_voice("81384788765712384", "81384788765712385", false, true).await?;
+///     let mut voice = VoiceGateway::connect(&info).await?;
+///     // The external address comes from IP discovery on the UDP socket.
+///     let session = voice.select_protocol("203.0.113.7", 50000, "aead_aes256_gcm_rtpsize").await?;
+///     println!("Encrypting with {}", session.mode);
+///     Ok(())
+/// }
+/// ```
+pub struct VoiceGateway {
+    ready: VoiceReady,
+    commands: UnboundedSender<Value>,
+    payloads: UnboundedReceiver<Result<Value, VoiceError>>,
+}
+
+impl VoiceGateway {
+    /// Connects to the voice server, identifies and waits for Ready.
+    ///
+    /// # Arguments
+    ///
+    /// * `info` - The voice server and session, from `ShardHandle::join_voice`.
+    ///
+    /// # Returns
+    ///
+    /// A result containing the identified connection.
+    pub async fn connect(info: &VoiceServerInfo) -> Result<Self, VoiceError> {
+        let endpoint = info.endpoint.trim_end_matches('/');
+        let url = if endpoint.contains("://") {
+            format!("{}/?v={}", endpoint, VOICE_GATEWAY_VERSION)
+        } else {
+            format!("wss://{}/?v={}", endpoint, VOICE_GATEWAY_VERSION)
+        };
+        let (mut socket, _) = connect_async(url.as_str()).await?;
+
+        let hello = loop {
+            match socket.next().await {
+                Some(Ok(WsMessage::Text(text))) => break serde_json::from_str::<Value>(&text).map_err(|error| VoiceError::Protocol(error.to_string()))?,
+                Some(Ok(WsMessage::Close(frame))) => return Err(closed(frame)),
+                Some(Ok(_)) => {}
+                Some(Err(error)) => return Err(error.into()),
+                None => return Err(VoiceError::Protocol("expected Hello".to_string())),
+            }
+        };
+        let interval = match (hello["op"].as_u64(), hello["d"]["heartbeat_interval"].as_f64()) {
+            (Some(code), Some(interval)) if code == op::HELLO as u64 => Duration::from_secs_f64(interval / 1000.0),
+            _ => return Err(VoiceError::Protocol(hello.to_string())),
+        };
+
+        let identify = json!({
+            "op": op::IDENTIFY,
+            "d": {
+                "server_id": info.guild_id,
+                "user_id": info.user_id,
+                "session_id": info.session_id,
+                "token": info.token,
+            },
+        });
+        socket.send(WsMessage::text(identify.to_string())).await?;
+
+        let (commands, outgoing) = unbounded_channel();
+        let (incoming, payloads) = unbounded_channel();
+        tokio::spawn(run_connection(socket, interval, outgoing, incoming));
+
+        let mut gateway = Self { ready: VoiceReady { ssrc: 0, ip: String::new(), port: 0, modes: Vec::new() }, commands, payloads };
+        let ready = gateway.expect(op::READY).await?;
+        gateway.ready = serde_json::from_value(ready).map_err(|error| VoiceError::Protocol(error.to_string()))?;
+        Ok(gateway)
+    }
+
+    /// Returns the voice server's answer to Identify.
+    pub fn ready(&self) -> &VoiceReady {
+        &self.ready
+    }
+
+    /// Tells the voice server where to send audio and how to encrypt it, and
+    /// waits for the session description holding the key.
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - Our external IP address, found by IP discovery.
+    /// * `port` - Our external UDP port, found by IP discovery.
+    /// * `mode` - One of the encryption modes in [`VoiceReady::modes`].
+    ///
+    /// # Returns
+    ///
+    /// A result containing the selected mode and secret key.
+    pub async fn select_protocol(&mut self, address: &str, port: u16, mode: &str) -> Result<SessionDescription, VoiceError> {
+        if !self.ready.modes.iter().any(|supported| supported == mode) {
+            return Err(VoiceError::Protocol(format!("the voice server does not support the encryption mode {}", mode)));
+        }
+        self.send(op::SELECT_PROTOCOL, json!({ "protocol": "udp", "data": { "address": address, "port": port, "mode": mode } }))?;
+        let description = self.expect(op::SESSION_DESCRIPTION).await?;
+        serde_json::from_value(description).map_err(|error| VoiceError::Protocol(error.to_string()))
+    }
+
+    /// Sets the speaking flags (opcode 5), which must be set before sending
+    /// audio. Empty flags mark the end of the audio.
+    ///
+    /// # Arguments
+    ///
+    /// * `flags` - The kind of audio being sent.
+    /// * `ssrc` - Our SSRC, from [`VoiceReady::ssrc`].
+    pub fn speaking(&self, flags: SpeakingFlags, ssrc: u32) -> Result<(), VoiceError> {
+        self.send(op::SPEAKING, json!({ "speaking": flags.bits(), "delay": 0, "ssrc": ssrc }))
+    }
+
+    /// Sends a payload to the voice server.
+    pub fn send(&self, op: u8, data: Value) -> Result<(), VoiceError> {
+        self.commands.send(json!({ "op": op, "d": data })).map_err(|_| VoiceError::Protocol("the voice connection is closed".to_string()))
+    }
+
+    /// Returns the next payload from the voice server other than heartbeat
+    /// acknowledgements, or `None` once the connection is closed.
+    pub async fn next_payload(&mut self) -> Option<Result<Value, VoiceError>> {
+        self.payloads.recv().await
+    }
+
+    /// Waits for a payload with opcode `code` and returns its data, skipping other payloads.
+    async fn expect(&mut self, code: u8) -> Result<Value, VoiceError> {
+        loop {
+            let payload = match self.next_payload().await {
+                Some(payload) => payload?,
+                None => return Err(VoiceError::Protocol("the voice connection is closed".to_string())),
+            };
+            if payload["op"].as_u64() == Some(code as u64) {
+                return Ok(payload["d"].clone());
+            }
+        }
+    }
+}
+
+/// Owns the socket: sends heartbeats and commands, and forwards received payloads.
+async fn run_connection(mut socket: Socket, interval: Duration, mut commands: UnboundedReceiver<Value>, payloads: UnboundedSender<Result<Value, VoiceError>>) {
+    let mut heartbeat = interval_at(Instant::now() + interval, interval);
+    let mut acknowledged = true;
+    // The last sequence number received, acknowledged with each heartbeat.
+    let mut sequence: Option<u64> = None;
+
+    loop {
+        tokio::select! {
+            _ = heartbeat.tick() => {
+                if !acknowledged {
+                    let _ = payloads.send(Err(VoiceError::Protocol("heartbeat not acknowledged".to_string())));
+                    let _ = socket.close(Some(CloseFrame { code: CloseCode::from(4000), reason: "heartbeat timeout".into() })).await;
+                    return;
+                }
+                acknowledged = false;
+                let nonce = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).map(|time| time.as_millis() as u64).unwrap_or(0);
+                let heartbeat = json!({ "op": op::HEARTBEAT, "d": { "t": nonce, "seq_ack": sequence } });
+                if let Err(error) = socket.send(WsMessage::text(heartbeat.to_string())).await {
+                    let _ = payloads.send(Err(error.into()));
+                    return;
+                }
+            }
+            command = commands.recv() => {
+                let Some(command) = command else {
+                    // The VoiceGateway was dropped.
+                    let _ = socket.close(None).await;
+                    return;
+                };
+                if let Err(error) = socket.send(WsMessage::text(command.to_string())).await {
+                    let _ = payloads.send(Err(error.into()));
+                    return;
+                }
+            }
+            message = socket.next() => {
+                let text = match message {
+                    Some(Ok(WsMessage::Text(text))) => text,
+                    Some(Ok(WsMessage::Close(frame))) => {
+                        let _ = payloads.send(Err(closed(frame)));
+                        return;
+                    }
+                    Some(Ok(_)) => continue,
+                    Some(Err(error)) => {
+                        let _ = payloads.send(Err(error.into()));
+                        return;
+                    }
+                    None => return,
+                };
+                let payload: Value = match serde_json::from_str(&text) {
+                    Ok(payload) => payload,
+                    Err(error) => {
+                        eprintln!("Failed to parse voice payload: {}", error);
+                        continue;
+                    }
+                };
+                if let Some(seq) = payload["seq"].as_u64() {
+                    sequence = Some(seq);
+                }
+                if payload["op"].as_u64() == Some(op::HEARTBEAT_ACK as u64) {
+                    acknowledged = true;
+                } else if payloads.send(Ok(payload)).is_err() {
+                    let _ = socket.close(None).await;
+                    return;
+                }
+            }
+        }
+    }
+}
+
+fn closed(frame: Option<CloseFrame>) -> VoiceError {
+    match frame {
+        Some(frame) => VoiceError::Closed { code: u16::from(frame.code), reason: frame.reason.to_string() },
+        None => VoiceError::Closed { code: 1005, reason: String::new() },
+    }
+}
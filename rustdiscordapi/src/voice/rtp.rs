@@ -0,0 +1,76 @@
+/// The size of an RTP header without CSRCs or extension.
+pub const RTP_HEADER_SIZE: usize = 12;
+
+/// Version 2, no padding, no extension, no CSRCs.
+const VERSION: u8 = 0x80;
+
+/// The payload type Discord uses for Opus.
+pub const OPUS_PAYLOAD_TYPE: u8 = 0x78;
+
+/// The header of an RTP packet.
+///
+/// # Examples
+///
+/// ```
+/// use Rust_Discord_API::voice::RtpHeader;
+///
+/// let header = RtpHeader::new(1, 960, 42);
+/// let bytes = header.to_bytes();
+/// assert_eq!(bytes[..2], [0x80, 0x78]);
+/// assert_eq!(RtpHeader::parse(&bytes), Some(header));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RtpHeader {
+    pub payload_type: u8,
+    /// Whether a header extension follows the header.
+    pub extension: bool,
+    /// The number of contributing source IDs following the header.
+    pub csrc_count: u8,
+    /// Increments by one with each packet, wrapping around.
+    pub sequence: u16,
+    /// Increments by the samples in each packet, at 48 kHz.
+    pub timestamp: u32,
+    /// The synchronization source, from the voice server's Ready.
+    pub ssrc: u32,
+}
+
+impl RtpHeader {
+    /// Creates the header of an Opus packet.
+    pub fn new(sequence: u16, timestamp: u32, ssrc: u32) -> Self {
+        Self { payload_type: OPUS_PAYLOAD_TYPE, extension: false, csrc_count: 0, sequence, timestamp, ssrc }
+    }
+
+    /// Parses the header at the start of a packet, or returns `None` if the
+    /// packet is too short or isn't RTP version 2.
+    pub fn parse(packet: &[u8]) -> Option<Self> {
+        if packet.len() < RTP_HEADER_SIZE || packet[0] >> 6 != 2 {
+            return None;
+        }
+        let header = Self {
+            payload_type: packet[1] & 0x7F,
+            extension: packet[0] & 0x10 != 0,
+            csrc_count: packet[0] & 0x0F,
+            sequence: u16::from_be_bytes([packet[2], packet[3]]),
+            timestamp: u32::from_be_bytes(packet[4..8].try_into().unwrap()),
+            ssrc: u32::from_be_bytes(packet[8..12].try_into().unwrap()),
+        };
+        let extension = if header.extension { 4 } else { 0 };
+        (packet.len() >= header.size() + extension).then_some(header)
+    }
+
+    /// Returns the size of the header including its CSRCs.
+    pub fn size(&self) -> usize {
+        RTP_HEADER_SIZE + self.csrc_count as usize * 4
+    }
+
+    /// Serializes a header without CSRCs, as sent for our own audio.
+    pub fn to_bytes(&self) -> [u8; RTP_HEADER_SIZE] {
+        let mut bytes = [0; RTP_HEADER_SIZE];
+        bytes[0] = VERSION | if self.extension { 0x10 } else { 0 };
+        bytes[1] = self.payload_type;
+        bytes[2..4].copy_from_slice(&self.sequence.to_be_bytes());
+        bytes[4..8].copy_from_slice(&self.timestamp.to_be_bytes());
+        bytes[8..12].copy_from_slice(&self.ssrc.to_be_bytes());
+        bytes
+    }
+}
@@ -0,0 +1,211 @@
+use futures::{Stream, StreamExt};
+use std::time::Duration;
+use tokio::net::UdpSocket;
+use tokio::time::{sleep_until, timeout, Instant};
+
+use super::crypto::{Cipher, EncryptionMode};
+use super::rtp::RtpHeader;
+use super::{SessionDescription, SpeakingFlags, VoiceError, VoiceGateway, VoiceReady};
+
+/// The audio in each Opus packet sent.
+pub const FRAME_DURATION: Duration = Duration::from_millis(20);
+
+/// The samples in each 20 ms frame at 48 kHz, which the RTP timestamp advances by.
+pub const SAMPLES_PER_FRAME: u32 = 960;
+
+/// An Opus frame of silence.
+pub const SILENCE_FRAME: [u8; 3] = [0xF8, 0xFF, 0xFE];
+
+/// Silence frames sent when audio stops, so receivers don't interpolate.
+const SILENCE_FRAMES: usize = 5;
+
+/// IP discovery packets: type, length, SSRC, a 64-byte address and a port.
+const DISCOVERY_SIZE: usize = 74;
+const DISCOVERY_REQUEST: u16 = 1;
+const DISCOVERY_RESPONSE: u16 = 2;
+const DISCOVERY_ATTEMPTS: u32 = 3;
+const DISCOVERY_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// How far the sender may fall behind before it stops catching up with bursts.
+const MAX_LAG: Duration = Duration::from_millis(100);
+
+/// The UDP socket of a voice connection, after IP discovery.
+///
+/// # Examples
+///
+/// ```no_run
+/// use futures::stream;
+/// use Rust_Discord_API::voice::{EncryptionMode, VoiceGateway, VoiceServerInfo, VoiceUdp};
+///
+/// async fn play(info: &VoiceServerInfo, opus: Vec<Vec<u8>>) -> Result<(), Box<dyn std::error::Error>> {
+///     let mut voice = VoiceGateway::connect(info).await?;
+///     let udp = VoiceUdp::connect(voice.ready()).await?;
+///     let mode = EncryptionMode::preferred(&voice.ready().modes).ok_or("no supported encryption mode")?;
+///     let session = voice.select_protocol(udp.address(), udp.port(), mode.name()).await?;
+///     let mut sender = udp.start(&session)?;
+///     sender.play(&voice, stream::iter(opus)).await?;
+///     Ok(())
+/// }
+/// ```
+pub struct VoiceUdp {
+    socket: UdpSocket,
+    ssrc: u32,
+    address: String,
+    port: u16,
+}
+
+impl VoiceUdp {
+    /// Connects a UDP socket to the voice server and discovers our external
+    /// address, which Select Protocol needs.
+    ///
+    /// # Arguments
+    ///
+    /// * `ready` - The voice server's answer to Identify.
+    ///
+    /// # Returns
+    ///
+    /// A result containing the socket and our external address.
+    pub async fn connect(ready: &VoiceReady) -> Result<Self, VoiceError> {
+        let local = if ready.ip.contains(':') { "[::]:0" } else { "0.0.0.0:0" };
+        let socket = UdpSocket::bind(local).await?;
+        socket.connect((ready.ip.as_str(), ready.port)).await?;
+        let (address, port) = discover_ip(&socket, ready.ssrc).await?;
+        Ok(Self { socket, ssrc: ready.ssrc, address, port })
+    }
+
+    /// Returns our external IP address.
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    /// Returns our external UDP port.
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// Starts sending audio with the session from Select Protocol.
+    pub fn start(self, session: &SessionDescription) -> Result<AudioSender, VoiceError> {
+        let mode = EncryptionMode::from_name(&session.mode).ok_or_else(|| VoiceError::Protocol(format!("unsupported encryption mode {}", session.mode)))?;
+        let cipher = Cipher::new(mode, &session.secret_key)?;
+        Ok(AudioSender { socket: self.socket, ssrc: self.ssrc, cipher, sequence: 0, timestamp: 0, nonce: 0, next_frame: None })
+    }
+}
+
+/// Asks the voice server which address and port our packets come from,
+/// retrying as UDP packets can be lost.
+async fn discover_ip(socket: &UdpSocket, ssrc: u32) -> Result<(String, u16), VoiceError> {
+    let mut request = [0; DISCOVERY_SIZE];
+    request[0..2].copy_from_slice(&DISCOVERY_REQUEST.to_be_bytes());
+    request[2..4].copy_from_slice(&(DISCOVERY_SIZE as u16 - 4).to_be_bytes());
+    request[4..8].copy_from_slice(&ssrc.to_be_bytes());
+
+    let mut response = [0; DISCOVERY_SIZE];
+    for _ in 0..DISCOVERY_ATTEMPTS {
+        socket.send(&request).await?;
+        let deadline = Instant::now() + DISCOVERY_TIMEOUT;
+        loop {
+            let received = match timeout(deadline.saturating_duration_since(Instant::now()), socket.recv(&mut response)).await {
+                Ok(received) => received?,
+                Err(_) => break,
+            };
+            // Skip anything else, such as audio arriving early.
+            if received < DISCOVERY_SIZE || u16::from_be_bytes([response[0], response[1]]) != DISCOVERY_RESPONSE {
+                continue;
+            }
+            let address = &response[8..72];
+            let end = address.iter().position(|&byte| byte == 0).unwrap_or(address.len());
+            let address = String::from_utf8(address[..end].to_vec()).map_err(|_| VoiceError::Protocol("the discovered address is not UTF-8".to_string()))?;
+            let port = u16::from_be_bytes([response[72], response[73]]);
+            return Ok((address, port));
+        }
+    }
+    Err(VoiceError::Protocol("IP discovery got no response".to_string()))
+}
+
+/// Sends Opus audio over a voice connection, one packet every 20 ms.
+///
+/// Each packet is framed with an RTP header and encrypted with the session's
+/// mode. The sequence, timestamp and nonce carry on across calls, so several
+/// tracks can be played one after another.
+pub struct AudioSender {
+    socket: UdpSocket,
+    ssrc: u32,
+    cipher: Cipher,
+    sequence: u16,
+    timestamp: u32,
+    nonce: u32,
+    next_frame: Option<Instant>,
+}
+
+impl AudioSender {
+    /// Returns the SSRC identifying our audio.
+    pub fn ssrc(&self) -> u32 {
+        self.ssrc
+    }
+
+    /// Returns the socket, for receiving other users' audio with [`Cipher::decrypt`].
+    pub fn socket(&self) -> &UdpSocket {
+        &self.socket
+    }
+
+    /// Returns the cipher of the session.
+    pub fn cipher(&self) -> &Cipher {
+        &self.cipher
+    }
+
+    /// Sends one 20 ms Opus packet, waiting until its frame is due.
+    ///
+    /// The speaking flags must have been set with [`VoiceGateway::speaking`]
+    /// first; [`play`](Self::play) does that.
+    pub async fn send_opus(&mut self, opus: &[u8]) -> Result<(), VoiceError> {
+        let now = Instant::now();
+        let due = match self.next_frame {
+            // Resuming after a pause, or too far behind to catch up.
+            Some(due) if due + MAX_LAG >= now => due,
+            _ => now,
+        };
+        sleep_until(due).await;
+
+        let header = RtpHeader::new(self.sequence, self.timestamp, self.ssrc).to_bytes();
+        let packet = self.cipher.encrypt(&header, opus, self.nonce);
+        self.socket.send(&packet).await?;
+
+        self.sequence = self.sequence.wrapping_add(1);
+        self.timestamp = self.timestamp.wrapping_add(SAMPLES_PER_FRAME);
+        self.nonce = self.nonce.wrapping_add(1);
+        self.next_frame = Some(due + FRAME_DURATION);
+        Ok(())
+    }
+
+    /// Sends the silence frames that end a stretch of audio.
+    pub async fn send_silence(&mut self) -> Result<(), VoiceError> {
+        for _ in 0..SILENCE_FRAMES {
+            self.send_opus(&SILENCE_FRAME).await?;
+        }
+        Ok(())
+    }
+
+    /// Plays a stream of 20 ms Opus packets: sets the speaking flags, sends
+    /// each packet on time, then sends silence and clears the flags.
+    ///
+    /// # Arguments
+    ///
+    /// * `gateway` - The voice gateway of the connection, for the speaking flags.
+    /// * `packets` - The Opus packets to play.
+    ///
+    /// # Returns
+    ///
+    /// A result indicating whether every packet was sent.
+    pub async fn play<S>(&mut self, gateway: &VoiceGateway, packets: S) -> Result<(), VoiceError>
+    where
+        S: Stream<Item = Vec<u8>>,
+    {
+        gateway.speaking(SpeakingFlags::MICROPHONE, self.ssrc)?;
+        let mut packets = std::pin::pin!(packets);
+        while let Some(packet) = packets.next().await {
+            self.send_opus(&packet).await?;
+        }
+        self.send_silence().await?;
+        gateway.speaking(SpeakingFlags::empty(), self.ssrc)
+    }
+}
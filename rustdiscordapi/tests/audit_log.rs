@@ -0,0 +1,252 @@
+mod common;
+
+use futures::StreamExt;
+use reqwest::header::HeaderValue;
+use serde_json::{json, Value};
+
+use common::{mock_api, Request};
+use Rust_Discord_API::audit_log::{
+    encode_reason, stream_guild_audit_log, AuditLog, AuditLogEvent, AuditLogFilter, AuditLogTarget, ResolvedAuditLogEntry,
+    AUDIT_LOG_REASON_HEADER,
+};
+use Rust_Discord_API::event::Event;
+use Rust_Discord_API::pagination::{Direction, PageResult, PaginationOptions};
+
+/// Decodes the header the way Discord does.
+fn decode(encoded: &str) -> String {
+    let bytes = encoded.as_bytes();
+    let mut decoded = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            decoded.push(u8::from_str_radix(&encoded[i + 1..i + 3], 16).unwrap());
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(decoded).unwrap()
+}
+
+#[test]
+fn leaves_unreserved_characters_alone() {
+    assert_eq!(encode_reason("Raid-cleanup_2024.v2~"), "Raid-cleanup_2024.v2~");
+    assert_eq!(encode_reason(""), "");
+}
+
+#[test]
+fn encodes_reserved_and_non_ascii_characters() {
+    assert_eq!(encode_reason("spam & scams"), "spam%20%26%20scams");
+    assert_eq!(encode_reason("50%+1"), "50%25%2B1");
+    assert_eq!(encode_reason("a/b?c=d#e"), "a%2Fb%3Fc%3Dd%23e");
+    assert_eq!(encode_reason("Grüße"), "Gr%C3%BC%C3%9Fe");
+    assert_eq!(encode_reason("🔨"), "%F0%9F%94%A8");
+    assert_eq!(encode_reason("line\nbreak"), "line%0Abreak");
+}
+
+#[test]
+fn encoded_reasons_are_valid_headers_and_decode_back() {
+    assert_eq!(AUDIT_LOG_REASON_HEADER, "X-Audit-Log-Reason");
+    for reason in ["Banned for: \"raiding\" 🔨", "日本語の理由", "tab\tand\r\nnewlines", "%41 is not A"] {
+        let encoded = encode_reason(reason);
+        assert!(HeaderValue::from_str(&encoded).is_ok());
+        assert_eq!(decode(&encoded), reason);
+    }
+}
+
+fn audit_log() -> Value {
+    json!({
+        "audit_log_entries": [
+            {
+                "id": "1001",
+                "user_id": "1",
+                "target_id": "2",
+                "action_type": 22,
+                "changes": [{ "key": "delete_message_seconds", "new_value": 86400 }],
+                "reason": "raiding"
+            },
+            { "id": "1002", "user_id": "1", "target_id": "500", "action_type": 50, "changes": [{ "key": "name", "new_value": "alerts" }] },
+            { "id": "1003", "user_id": "2", "target_id": "600", "action_type": 110 },
+            { "id": "1004", "user_id": "1", "target_id": "700", "action_type": 82 },
+            {
+                "id": "1005",
+                "user_id": "1",
+                "target_id": "800",
+                "action_type": 31,
+                "changes": [{ "key": "color", "old_value": 0, "new_value": 16711680 }]
+            },
+            {
+                "id": "1006",
+                "user_id": "1",
+                "target_id": "2",
+                "action_type": 72,
+                "options": { "channel_id": "30", "count": "3" }
+            },
+            { "id": "1007", "user_id": "3", "target_id": null, "action_type": 999 }
+        ],
+        "users": [
+            { "id": "1", "username": "moderator" },
+            { "id": "2", "username": "raider" }
+        ],
+        "webhooks": [{ "id": "500", "type": 1, "name": "alerts", "channel_id": "30" }],
+        "threads": [{ "id": "600", "type": 11, "guild_id": "10", "name": "appeals", "parent_id": "30" }],
+        "integrations": [{ "id": "700", "name": "Twitch", "type": "twitch" }],
+        "application_commands": [],
+        "auto_moderation_rules": [],
+        "guild_scheduled_events": []
+    })
+}
+
+#[test]
+fn parses_typed_entries() {
+    let log: AuditLog = serde_json::from_value(audit_log()).unwrap();
+    let entries = &log.audit_log_entries;
+    assert_eq!(entries.len(), 7);
+
+    assert_eq!(entries[0].action_type, AuditLogEvent::MemberBanAdd);
+    assert_eq!(entries[0].reason.as_deref(), Some("raiding"));
+    assert_eq!(entries[0].change("delete_message_seconds").unwrap().new_value, Some(json!(86400)));
+    assert_eq!(entries[0].change("nick"), None);
+
+    let color = entries[4].change("color").unwrap();
+    assert_eq!((color.old_value.clone(), color.new_value.clone()), (Some(json!(0)), Some(json!(16711680))));
+
+    let options = entries[5].options.as_ref().unwrap();
+    assert_eq!((options.channel_id.as_deref(), options.count.as_deref()), (Some("30"), Some("3")));
+
+    // Action types this crate doesn't know yet don't fail the log.
+    assert_eq!(entries[6].action_type, AuditLogEvent::Unknown(999));
+    assert_eq!(entries[6].target_id, None);
+}
+
+#[test]
+fn joins_entries_with_referenced_entities() {
+    let log: AuditLog = serde_json::from_value(audit_log()).unwrap();
+    let resolved = log.resolve();
+
+    assert_eq!(resolved[0].user.as_ref().unwrap().username, "moderator");
+    assert!(matches!(&resolved[0].target, Some(AuditLogTarget::User(user)) if user.username == "raider"));
+    assert!(matches!(&resolved[1].target, Some(AuditLogTarget::Webhook(webhook)) if webhook["name"] == "alerts"));
+    assert!(matches!(&resolved[2].target, Some(AuditLogTarget::Thread(thread)) if thread.name.as_deref() == Some("appeals")));
+    assert_eq!(resolved[2].user.as_ref().unwrap().username, "raider");
+    assert!(matches!(&resolved[3].target, Some(AuditLogTarget::Integration(integration)) if integration["name"] == "Twitch"));
+    // Roles aren't included in the log, and a deleted message targets its author.
+    assert_eq!(resolved[4].target, None);
+    assert!(matches!(&resolved[5].target, Some(AuditLogTarget::User(user)) if user.id == "2"));
+    // Users missing from the log stay unresolved.
+    assert_eq!(resolved[6].user, None);
+}
+
+#[test]
+fn action_types_round_trip() {
+    for (event, value) in [(AuditLogEvent::GuildUpdate, 1), (AuditLogEvent::MemberKick, 20), (AuditLogEvent::AutoModerationBlockMessage, 143), (AuditLogEvent::HomeSettingsUpdate, 191), (AuditLogEvent::Unknown(7), 7)] {
+        assert_eq!(u16::from(event), value);
+        assert_eq!(AuditLogEvent::from(value), event);
+        assert_eq!(serde_json::to_value(event).unwrap(), json!(value));
+        assert_eq!(serde_json::from_value::<AuditLogEvent>(json!(value)).unwrap(), event);
+    }
+}
+
+#[test]
+fn builds_filter_queries() {
+    assert!(AuditLogFilter::new().query().is_empty());
+
+    let filter = AuditLogFilter::new().user_id("1").action_type(AuditLogEvent::MemberKick).before("1005").after("1000").limit(500);
+    let query = filter.query();
+    assert_eq!(
+        query,
+        vec![
+            ("user_id", "1".to_string()),
+            ("action_type", "20".to_string()),
+            ("before", "1005".to_string()),
+            ("after", "1000".to_string()),
+            ("limit", "100".to_string()),
+        ]
+    );
+    assert_eq!(AuditLogFilter::new().limit(0).query(), vec![("limit", "1".to_string())]);
+}
+
+/// Returns the value of a query parameter of a request.
+fn param<'a>(request: &'a Request, name: &str) -> Option<&'a str> {
+    let (_, query) = request.path.split_once('?')?;
+    query.split('&').find_map(|pair| pair.strip_prefix(name)?.strip_prefix('='))
+}
+
+/// Answers like the audit log endpoint, returning the page of `audit_log()`
+/// entries the cursor asks for, newest first.
+fn audit_log_page(request: &Request) -> (u16, Value) {
+    let limit: usize = param(request, "limit").unwrap().parse().unwrap();
+    let mut log = audit_log();
+    let mut entries = log["audit_log_entries"].as_array().unwrap().clone();
+    entries.reverse();
+    let id = |entry: &Value| entry["id"].as_str().unwrap().parse::<u64>().unwrap();
+    let page: Vec<Value> = match (param(request, "after"), param(request, "before")) {
+        (Some(after), _) => {
+            let after = after.parse().unwrap();
+            let mut page: Vec<Value> = entries.into_iter().rev().filter(|entry| id(entry) > after).take(limit).collect();
+            page.reverse();
+            page
+        }
+        (_, Some(before)) => {
+            let before = before.parse().unwrap();
+            entries.into_iter().filter(|entry| id(entry) < before).take(limit).collect()
+        }
+        _ => panic!("no cursor in {}", request.path),
+    };
+    log["audit_log_entries"] = json!(page);
+    (200, log)
+}
+
+fn ids(entries: Vec<PageResult<ResolvedAuditLogEntry>>) -> Vec<String> {
+    entries.into_iter().map(|entry| entry.unwrap().entry.id).collect()
+}
+
+#[tokio::test]
+async fn streams_resolved_entries_in_both_directions() {
+    let api = mock_api();
+    api.route("/guilds/10/audit-logs", audit_log_page);
+    let client = api.client();
+
+    let forward: Vec<_> = stream_guild_audit_log(&client, "token", "10", AuditLogFilter::new(), PaginationOptions::new().page_size(3)).collect().await;
+    assert_eq!(forward[0].as_ref().unwrap().user.as_ref().unwrap().username, "moderator");
+    assert_eq!(ids(forward), ["1001", "1002", "1003", "1004", "1005", "1006", "1007"]);
+    let cursors: Vec<_> = api.requests("/guilds/10/audit-logs").iter().map(|request| param(request, "after").unwrap().to_string()).collect();
+    assert_eq!(cursors, ["0", "1003", "1006"]);
+
+    let options = PaginationOptions::new().direction(Direction::Before).page_size(3);
+    let backward: Vec<_> = stream_guild_audit_log(&client, "token", "10", AuditLogFilter::new(), options).collect().await;
+    assert_eq!(ids(backward), ["1007", "1006", "1005", "1004", "1003", "1002", "1001"]);
+    let cursors: Vec<_> = api.requests("/guilds/10/audit-logs")[3..].iter().map(|request| param(request, "before").unwrap().to_string()).collect();
+    assert_eq!(cursors, ["9223372036854775807", "1005", "1002"]);
+}
+
+#[tokio::test]
+async fn streams_at_most_max_items_entries() {
+    let api = mock_api();
+    api.route("/guilds/10/audit-logs", audit_log_page);
+
+    // The filter's own cursors and limit are replaced by the stream's.
+    let filter = AuditLogFilter::new().user_id("1").before("1002").limit(1);
+    let options = PaginationOptions::new().direction(Direction::Before).page_size(2).max_items(3);
+    let entries: Vec<_> = stream_guild_audit_log(&api.client(), "token", "10", filter, options).collect().await;
+    assert_eq!(ids(entries), ["1007", "1006", "1005"]);
+
+    let requests = api.requests("/guilds/10/audit-logs");
+    assert_eq!(requests.len(), 2);
+    assert!(requests.iter().all(|request| param(request, "user_id") == Some("1")));
+    let limits: Vec<_> = requests.iter().map(|request| param(request, "limit").unwrap()).collect();
+    assert_eq!(limits, ["2", "1"]);
+    assert_eq!(param(&requests[0], "before"), Some("9223372036854775807"));
+}
+
+#[test]
+fn parses_audit_log_entry_events() {
+    let data = json!({ "id": "1001", "guild_id": "10", "user_id": "1", "target_id": "2", "action_type": 20, "reason": "spam" });
+    let Event::GuildAuditLogEntryCreate(entry) = Event::from_dispatch("GUILD_AUDIT_LOG_ENTRY_CREATE", data).unwrap() else {
+        panic!("expected an audit log entry event");
+    };
+    assert_eq!(entry.guild_id.as_deref(), Some("10"));
+    assert_eq!(entry.action_type, AuditLogEvent::MemberKick);
+    assert!(entry.changes.is_empty());
+}
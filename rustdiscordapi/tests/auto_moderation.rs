@@ -0,0 +1,193 @@
+use serde_json::json;
+
+use Rust_Discord_API::auto_moderation::{
+    AutoModerationAction, AutoModerationRule, AutoModerationRuleBuilder, EventType, KeywordPresetType, TriggerMetadata, TriggerType,
+};
+use Rust_Discord_API::event::Event;
+
+fn keyword_rule() -> serde_json::Value {
+    json!({
+        "id": "969707018069872670",
+        "guild_id": "613425648685547541",
+        "name": "Keyword Filter 1",
+        "creator_id": "423457898095789043",
+        "trigger_type": 1,
+        "event_type": 1,
+        "actions": [
+            { "type": 1, "metadata": { "custom_message": "Please keep financial discussions limited to the #finance channel" } },
+            { "type": 2, "metadata": { "channel_id": "123456789123456789" } },
+            { "type": 3, "metadata": { "duration_seconds": 60 } },
+            { "type": 1, "metadata": {} }
+        ],
+        "trigger_metadata": {
+            "keyword_filter": ["cat*", "*dog", "*ana*", "i like c++"],
+            "regex_patterns": ["(b|c)at", "^(?:[0-9]{1,3}\\.){3}[0-9]{1,3}$"]
+        },
+        "enabled": true,
+        "exempt_roles": ["323456789123456789", "423456789123456789"],
+        "exempt_channels": ["523456789123456789"]
+    })
+}
+
+#[test]
+fn parses_rules_with_typed_triggers_and_actions() {
+    let rule: AutoModerationRule = serde_json::from_value(keyword_rule()).unwrap();
+    assert_eq!(rule.trigger_type, TriggerType::Keyword);
+    assert_eq!(rule.event_type, EventType::MessageSend);
+    assert_eq!(rule.trigger_metadata.keyword_filter.len(), 4);
+    assert_eq!(rule.trigger_metadata.regex_patterns[0], "(b|c)at");
+    assert_eq!(rule.trigger_metadata.mention_total_limit, None);
+    assert_eq!(rule.exempt_roles.len(), 2);
+    assert!(rule.enabled);
+
+    assert_eq!(
+        rule.actions,
+        vec![
+            AutoModerationAction::BlockMessage { custom_message: Some("Please keep financial discussions limited to the #finance channel".to_string()) },
+            AutoModerationAction::SendAlertMessage { channel_id: "123456789123456789".to_string() },
+            AutoModerationAction::Timeout { duration_seconds: 60 },
+            AutoModerationAction::BlockMessage { custom_message: None },
+        ]
+    );
+}
+
+#[test]
+fn parses_other_trigger_types() {
+    let mut rule = keyword_rule();
+    rule["trigger_type"] = json!(4);
+    rule["trigger_metadata"] = json!({ "presets": [1, 3], "allow_list": ["scunthorpe"] });
+    let rule: AutoModerationRule = serde_json::from_value(rule).unwrap();
+    assert_eq!(rule.trigger_type, TriggerType::KeywordPreset);
+    assert_eq!(rule.trigger_metadata.presets, vec![KeywordPresetType::Profanity, KeywordPresetType::Slurs]);
+
+    let mut rule = keyword_rule();
+    rule["trigger_type"] = json!(5);
+    rule["trigger_metadata"] = json!({ "mention_total_limit": 10, "mention_raid_protection_enabled": true });
+    let rule: AutoModerationRule = serde_json::from_value(rule).unwrap();
+    assert_eq!(rule.trigger_type, TriggerType::MentionSpam);
+    assert_eq!(rule.trigger_metadata.mention_total_limit, Some(10));
+    assert_eq!(rule.trigger_metadata.mention_raid_protection_enabled, Some(true));
+
+    let mut rule = keyword_rule();
+    rule["trigger_type"] = json!(6);
+    rule["event_type"] = json!(2);
+    rule["actions"] = json!([{ "type": 4 }, { "type": 9, "metadata": { "future": true } }]);
+    let rule: AutoModerationRule = serde_json::from_value(rule).unwrap();
+    assert_eq!((rule.trigger_type, rule.event_type), (TriggerType::MemberProfile, EventType::MemberUpdate));
+    assert_eq!(rule.actions[0], AutoModerationAction::BlockMemberInteraction);
+    assert_eq!(rule.actions[1], AutoModerationAction::Unknown { kind: 9, metadata: json!({ "future": true }) });
+}
+
+#[test]
+fn serializes_actions_with_their_metadata() {
+    let actions = [
+        (AutoModerationAction::BlockMessage { custom_message: None }, json!({ "type": 1 })),
+        (AutoModerationAction::BlockMessage { custom_message: Some("No.".to_string()) }, json!({ "type": 1, "metadata": { "custom_message": "No." } })),
+        (AutoModerationAction::SendAlertMessage { channel_id: "30".to_string() }, json!({ "type": 2, "metadata": { "channel_id": "30" } })),
+        (AutoModerationAction::Timeout { duration_seconds: 2419200 }, json!({ "type": 3, "metadata": { "duration_seconds": 2419200 } })),
+        (AutoModerationAction::BlockMemberInteraction, json!({ "type": 4 })),
+    ];
+    for (action, expected) in actions {
+        assert_eq!(serde_json::to_value(&action).unwrap(), expected);
+        assert_eq!(serde_json::from_value::<AutoModerationAction>(expected).unwrap(), action);
+    }
+}
+
+#[test]
+fn keeps_unknown_event_and_trigger_types() {
+    let mut data = keyword_rule();
+    data["event_type"] = json!(9);
+    data["trigger_type"] = json!(42);
+    let rule: AutoModerationRule = serde_json::from_value(data).unwrap();
+    assert_eq!((rule.event_type, rule.trigger_type), (EventType::Unknown(9), TriggerType::Unknown(42)));
+
+    let value = serde_json::to_value(&rule).unwrap();
+    assert_eq!((value["event_type"].clone(), value["trigger_type"].clone()), (json!(9), json!(42)));
+
+    for (trigger_type, value) in [(TriggerType::Keyword, 1), (TriggerType::Spam, 3), (TriggerType::MemberProfile, 6), (TriggerType::Unknown(2), 2)] {
+        assert_eq!(u8::from(trigger_type), value);
+        assert_eq!(TriggerType::from(value), trigger_type);
+    }
+    assert_eq!(serde_json::to_value(EventType::MemberUpdate).unwrap(), json!(2));
+}
+
+#[test]
+fn keeps_unknown_keyword_presets() {
+    let metadata: TriggerMetadata = serde_json::from_value(json!({ "presets": [1, 7, 3] })).unwrap();
+    assert_eq!(metadata.presets, [KeywordPresetType::Profanity, KeywordPresetType::Unknown(7), KeywordPresetType::Slurs]);
+    assert_eq!(serde_json::to_value(&metadata).unwrap(), json!({ "presets": [1, 7, 3] }));
+
+    for (preset, value) in [(KeywordPresetType::SexualContent, 2), (KeywordPresetType::Unknown(4), 4)] {
+        assert_eq!(u8::from(preset), value);
+        assert_eq!(KeywordPresetType::from(value), preset);
+    }
+}
+
+#[test]
+fn builds_rule_bodies() {
+    let rule = AutoModerationRuleBuilder::new("Mention spam", EventType::MessageSend, TriggerType::MentionSpam)
+        .trigger_metadata(TriggerMetadata { mention_total_limit: Some(5), ..Default::default() })
+        .action(AutoModerationAction::BlockMessage { custom_message: None })
+        .action(AutoModerationAction::Timeout { duration_seconds: 300 })
+        .exempt_role("1")
+        .exempt_channel("2")
+        .exempt_channel("3")
+        .enabled(true);
+    assert_eq!(
+        rule.build(),
+        json!({
+            "name": "Mention spam",
+            "event_type": 1,
+            "trigger_type": 5,
+            "trigger_metadata": { "mention_total_limit": 5 },
+            "actions": [{ "type": 1 }, { "type": 3, "metadata": { "duration_seconds": 300 } }],
+            "enabled": true,
+            "exempt_roles": ["1"],
+            "exempt_channels": ["2", "3"]
+        })
+    );
+
+    let changes = AutoModerationRuleBuilder::edit()
+        .name("Renamed")
+        .trigger_metadata(TriggerMetadata { keyword_filter: vec!["spam".to_string()], ..Default::default() });
+    assert_eq!(changes.build(), json!({ "name": "Renamed", "trigger_metadata": { "keyword_filter": ["spam"] } }));
+    assert_eq!(AutoModerationRuleBuilder::edit().build(), json!({}));
+}
+
+#[test]
+fn parses_auto_moderation_events() {
+    let data = json!({
+        "guild_id": "10",
+        "action": { "type": 2, "metadata": { "channel_id": "40" } },
+        "rule_id": "969707018069872670",
+        "rule_trigger_type": 1,
+        "user_id": "2",
+        "channel_id": "30",
+        "message_id": "1000",
+        "alert_system_message_id": "1001",
+        "content": "buy cheap cat food",
+        "matched_keyword": "cat*",
+        "matched_content": "cat"
+    });
+    let Event::AutoModerationActionExecution(execution) = Event::from_dispatch("AUTO_MODERATION_ACTION_EXECUTION", data).unwrap() else {
+        panic!("expected an action execution");
+    };
+    assert_eq!(execution.action, AutoModerationAction::SendAlertMessage { channel_id: "40".to_string() });
+    assert_eq!(execution.rule_trigger_type, TriggerType::Keyword);
+    assert_eq!(execution.matched_keyword.as_deref(), Some("cat*"));
+    assert_eq!(execution.alert_system_message_id.as_deref(), Some("1001"));
+
+    // Without MESSAGE_CONTENT the content fields are empty, and blocked messages have no ID.
+    let data = json!({ "guild_id": "10", "action": { "type": 1 }, "rule_id": "1", "rule_trigger_type": 3, "user_id": "2", "channel_id": "30", "content": "", "matched_keyword": null, "matched_content": null });
+    let Event::AutoModerationActionExecution(execution) = Event::from_dispatch("AUTO_MODERATION_ACTION_EXECUTION", data).unwrap() else {
+        panic!("expected an action execution");
+    };
+    assert_eq!((execution.message_id, execution.content.as_str()), (None, ""));
+
+    for name in ["AUTO_MODERATION_RULE_CREATE", "AUTO_MODERATION_RULE_UPDATE", "AUTO_MODERATION_RULE_DELETE"] {
+        match Event::from_dispatch(name, keyword_rule()).unwrap() {
+            Event::AutoModerationRuleCreate(rule) | Event::AutoModerationRuleUpdate(rule) | Event::AutoModerationRuleDelete(rule) => assert_eq!(rule.name, "Keyword Filter 1"),
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+}
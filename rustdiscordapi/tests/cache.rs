@@ -0,0 +1,144 @@
+mod common;
+
+use serde_json::{json, Value};
+
+use common::mock_api;
+use Rust_Discord_API::cache::{Cache, CacheConfig};
+use Rust_Discord_API::event::Event;
+use Rust_Discord_API::utils::permissions::{check_permission_cached, ADMINISTRATOR};
+
+fn guild() -> Value {
+    json!({
+        "id": "10",
+        "name": "Guild",
+        "owner_id": "1",
+        "roles": [{"id": "10", "name": "@everyone", "permissions": "1024"}],
+        "channels": [{"id": "20", "type": 0, "name": "general"}],
+        "members": [{"user": {"id": "1", "username": "owner"}, "roles": []}],
+    })
+}
+
+fn message(id: &str) -> Value {
+    json!({"id": id, "channel_id": "20", "author": {"id": "1", "username": "owner"}, "content": id})
+}
+
+async fn apply(cache: &Cache, name: &str, data: Value) {
+    cache.update(&Event::from_dispatch(name, data).unwrap()).await.unwrap();
+}
+
+#[tokio::test]
+async fn skips_disabled_resources() {
+    let cache = Cache::new(CacheConfig::new().members(false).roles(false).messages(false));
+    apply(&cache, "GUILD_CREATE", guild()).await;
+    apply(&cache, "MESSAGE_CREATE", message("30")).await;
+
+    assert!(cache.guild("10").await.unwrap().is_some());
+    assert!(cache.channel("20").await.unwrap().is_some());
+    assert!(cache.guild_members("10").await.unwrap().is_empty());
+    assert!(cache.guild_roles("10").await.unwrap().is_empty());
+    assert!(cache.channel_messages("20").await.unwrap().is_empty());
+
+    let cache = Cache::new(CacheConfig::new().guilds(false).channels(false).message_capacity(0));
+    apply(&cache, "GUILD_CREATE", guild()).await;
+    apply(&cache, "MESSAGE_CREATE", message("30")).await;
+
+    assert!(cache.guild("10").await.unwrap().is_none());
+    assert!(cache.channel("20").await.unwrap().is_none());
+    assert_eq!(cache.guild_members("10").await.unwrap().len(), 1);
+    assert_eq!(cache.guild_roles("10").await.unwrap().len(), 1);
+    assert!(cache.channel_messages("20").await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn keeps_guilds_that_become_unavailable() {
+    let cache = Cache::default();
+    apply(&cache, "GUILD_CREATE", guild()).await;
+
+    // An outage keeps the guild and what it contains.
+    apply(&cache, "GUILD_DELETE", json!({"id": "10", "unavailable": true})).await;
+    assert!(cache.guild("10").await.unwrap().unwrap().unavailable);
+    assert!(cache.channel("20").await.unwrap().is_some());
+    assert!(cache.member("10", "1").await.unwrap().is_some());
+
+    // An outage of a guild that was never cached doesn't make one up.
+    apply(&cache, "GUILD_DELETE", json!({"id": "11", "unavailable": true})).await;
+    assert!(cache.guild("11").await.unwrap().is_none());
+
+    // Being removed from the guild drops everything.
+    apply(&cache, "GUILD_DELETE", json!({"id": "10"})).await;
+    assert!(cache.guild("10").await.unwrap().is_none());
+    assert!(cache.channel("20").await.unwrap().is_none());
+    assert!(cache.guild_members("10").await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn member_updates_keep_voice_flags() {
+    let cache = Cache::default();
+    let joined = json!({"guild_id": "10", "user": {"id": "2", "username": "user"}, "roles": [], "deaf": true, "mute": true});
+    apply(&cache, "GUILD_MEMBER_ADD", joined).await;
+
+    let updated = json!({"guild_id": "10", "user": {"id": "2", "username": "user"}, "roles": ["40"], "nick": "nick"});
+    apply(&cache, "GUILD_MEMBER_UPDATE", updated).await;
+
+    let member = cache.member("10", "2").await.unwrap().unwrap();
+    assert_eq!(member.nick.as_deref(), Some("nick"));
+    assert_eq!(member.roles, ["40"]);
+    assert!(member.deaf);
+    assert!(member.mute);
+}
+
+#[tokio::test]
+async fn fetches_and_caches_misses() {
+    let api = mock_api();
+    api.route("/guilds/10", |_| (200, json!({"id": "10", "name": "Guild", "owner_id": "1"})));
+    api.route("/guilds/10/members/2", |_| (200, json!({"user": {"id": "2", "username": "user"}, "roles": []})));
+    api.route("/guilds/10/roles", |_| (200, json!([{"id": "10", "name": "@everyone", "permissions": "0"}])));
+    api.route("/channels/20", |_| (200, json!({"id": "20", "type": 0, "guild_id": "10", "name": "general"})));
+    api.route("/channels/20/messages/30", |_| (200, message("30")));
+    let client = api.client();
+    let cache = Cache::default();
+
+    for _ in 0..2 {
+        assert_eq!(cache.guild_or_fetch(&client, "token", "10").await.unwrap().name, "Guild");
+        assert_eq!(cache.channel_or_fetch(&client, "token", "20").await.unwrap().name.as_deref(), Some("general"));
+        assert!(cache.member_or_fetch(&client, "token", "10", "2").await.unwrap().user.is_some());
+        assert_eq!(cache.guild_roles_or_fetch(&client, "token", "10").await.unwrap().len(), 1);
+        assert_eq!(cache.message_or_fetch(&client, "token", "20", "30").await.unwrap().content, "30");
+    }
+
+    // The second round is answered from the cache.
+    assert_eq!(api.requests("/").len(), 5);
+    assert!(cache.member("10", "2").await.unwrap().is_some());
+    assert!(cache.message("20", "30").await.unwrap().is_some());
+
+    api.route("/channels/21", |_| (404, json!({"message": "Unknown Channel", "code": 10003})));
+    assert!(cache.channel_or_fetch(&client, "token", "21").await.is_err());
+    assert!(cache.channel("21").await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn checks_permissions_from_the_cache() {
+    const BAN_MEMBERS: u64 = 1 << 2;
+    const VIEW_CHANNEL: u64 = 1 << 10;
+
+    let api = mock_api();
+    let client = api.client();
+    let cache = Cache::default();
+    let mut guild = guild();
+    guild["roles"].as_array_mut().unwrap().push(json!({"id": "40", "name": "Admin", "permissions": ADMINISTRATOR.to_string()}));
+    guild["members"].as_array_mut().unwrap().extend([
+        json!({"user": {"id": "2", "username": "user"}, "roles": []}),
+        json!({"user": {"id": "3", "username": "admin"}, "roles": ["40"]}),
+    ]);
+    apply(&cache, "GUILD_CREATE", guild).await;
+
+    // The owner has every permission.
+    assert!(check_permission_cached(&cache, &client, "token", "10", "1", BAN_MEMBERS).await.unwrap());
+    // Everyone gets the permissions of @everyone, and nothing more.
+    assert!(check_permission_cached(&cache, &client, "token", "10", "2", VIEW_CHANNEL).await.unwrap());
+    assert!(!check_permission_cached(&cache, &client, "token", "10", "2", VIEW_CHANNEL | BAN_MEMBERS).await.unwrap());
+    // Administrators have every permission.
+    assert!(check_permission_cached(&cache, &client, "token", "10", "3", BAN_MEMBERS).await.unwrap());
+
+    assert!(api.requests("/").is_empty());
+}
@@ -0,0 +1,143 @@
+use futures::StreamExt;
+use serde_json::json;
+use std::time::Duration;
+use Rust_Discord_API::collector::Collector;
+use Rust_Discord_API::event::{Event, EventBus};
+
+fn message(id: &str, channel_id: &str, author_id: &str, content: &str) -> Event {
+    Event::from_dispatch("MESSAGE_CREATE", json!({
+        "id": id,
+        "channel_id": channel_id,
+        "author": { "id": author_id, "username": "user" },
+        "content": content
+    })).unwrap()
+}
+
+fn reaction(message_id: &str, user_id: &str, emoji: serde_json::Value) -> Event {
+    Event::from_dispatch("MESSAGE_REACTION_ADD", json!({
+        "user_id": user_id,
+        "channel_id": "10",
+        "message_id": message_id,
+        "emoji": emoji
+    })).unwrap()
+}
+
+fn click(message_id: &str, user_id: &str, custom_id: &str) -> Event {
+    Event::from_dispatch("INTERACTION_CREATE", json!({
+        "id": "900",
+        "application_id": "1",
+        "type": 3,
+        "token": "interaction-token",
+        "channel_id": "10",
+        "member": { "user": { "id": user_id, "username": "user" } },
+        "message": { "id": message_id },
+        "data": { "custom_id": custom_id, "component_type": 2 }
+    })).unwrap()
+}
+
+#[tokio::test]
+async fn message_collector_filters_by_author_and_channel() {
+    let bus = EventBus::default();
+    let collector = Collector::messages(&bus).author("42").channel("10").timeout(Duration::from_secs(5));
+
+    bus.publish(message("1", "10", "7", "not the author"));
+    bus.publish(message("2", "11", "42", "wrong channel"));
+    bus.publish(message("3", "10", "42", "the reason"));
+
+    let found = collector.first().await.expect("a matching message");
+    assert_eq!(found.id, "3");
+    assert_eq!(found.content, "the reason");
+}
+
+#[tokio::test]
+async fn reaction_collector_matches_unicode_and_custom_emoji() {
+    let bus = EventBus::default();
+    let confirm = Collector::reactions(&bus).message("500").author("42").emoji("✅").timeout(Duration::from_secs(5));
+    let custom = Collector::reactions(&bus).emoji("1234").timeout(Duration::from_secs(5));
+
+    bus.publish(reaction("500", "42", json!({ "id": null, "name": "❌" })));
+    bus.publish(reaction("501", "42", json!({ "id": null, "name": "✅" })));
+    bus.publish(reaction("500", "42", json!({ "id": "1234", "name": "party" })));
+    bus.publish(reaction("500", "42", json!({ "id": null, "name": "✅" })));
+
+    let found = confirm.first().await.expect("a matching reaction");
+    assert_eq!(found.message_id, "500");
+    assert_eq!(found.emoji.name.as_deref(), Some("✅"));
+
+    let found = custom.first().await.expect("a matching custom reaction");
+    assert_eq!(found.emoji.name.as_deref(), Some("party"));
+}
+
+#[tokio::test]
+async fn component_collector_filters_by_custom_id() {
+    let bus = EventBus::default();
+    let collector = Collector::components(&bus).message("500").custom_id("confirm").timeout(Duration::from_secs(5));
+
+    bus.publish(click("500", "42", "cancel"));
+    bus.publish(click("501", "42", "confirm"));
+    bus.publish(message("1", "10", "42", "noise"));
+    bus.publish(click("500", "43", "confirm"));
+
+    let found = collector.first().await.expect("a matching click");
+    assert_eq!(found.user_id(), Some("43"));
+}
+
+#[tokio::test]
+async fn collect_stops_at_max() {
+    let bus = EventBus::default();
+    let collector = Collector::messages(&bus).channel("10").max(2).timeout(Duration::from_secs(5));
+
+    for id in ["1", "2", "3"] {
+        bus.publish(message(id, "10", "42", "hi"));
+    }
+
+    let ids: Vec<String> = collector.collect().await.into_iter().map(|message| message.id).collect();
+    assert_eq!(ids, ["1", "2"]);
+}
+
+#[tokio::test]
+async fn collect_stops_at_timeout() {
+    let bus = EventBus::default();
+    let collector = Collector::messages(&bus).timeout(Duration::from_millis(50));
+
+    bus.publish(message("1", "10", "42", "hi"));
+
+    let collected = collector.collect().await;
+    assert_eq!(collected.len(), 1);
+}
+
+#[tokio::test]
+async fn first_returns_none_on_timeout() {
+    let bus = EventBus::default();
+    let collector = Collector::messages(&bus).author("42").timeout(Duration::from_millis(50));
+
+    bus.publish(message("1", "10", "7", "someone else"));
+
+    assert!(collector.first().await.is_none());
+}
+
+#[tokio::test]
+async fn stream_yields_events_published_while_waiting() {
+    let bus = EventBus::default();
+    let mut stream = Collector::messages(&bus).max(2).timeout(Duration::from_secs(5)).stream();
+
+    let publisher = bus.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        publisher.publish(message("1", "10", "42", "first"));
+        publisher.publish(message("2", "10", "42", "second"));
+    });
+
+    assert_eq!(stream.next().await.map(|message| message.content), Some("first".to_string()));
+    assert_eq!(stream.next().await.map(|message| message.content), Some("second".to_string()));
+    assert!(stream.next().await.is_none());
+}
+
+#[tokio::test]
+async fn stream_ends_when_bus_is_dropped() {
+    let bus = EventBus::default();
+    let collector = Collector::messages(&bus);
+    drop(bus);
+
+    assert!(collector.first().await.is_none());
+}
@@ -0,0 +1,139 @@
+#![allow(dead_code)]
+
+use native_tls::{Identity, TlsAcceptor};
+use reqwest::Client;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// A request received by the mock REST API.
+#[derive(Clone, Debug)]
+pub struct Request {
+    pub method: String,
+    /// The path below the API base, including any query string.
+    pub path: String,
+    /// Header values keyed by lowercase name.
+    pub headers: HashMap<String, String>,
+    pub body: Value,
+}
+
+type Responder = Arc<dyn Fn(&Request) -> (u16, Value) + Send + Sync>;
+
+#[derive(Default)]
+struct Routes {
+    routes: Mutex<Vec<(String, Responder)>>,
+    requests: Mutex<Vec<Request>>,
+}
+
+/// A stand-in for Discord's REST API, listening on loopback with a
+/// self-signed certificate for `discord.com`.
+///
+/// Every test starts its own server and sends requests to it through
+/// [`MockApi::client`], which resolves `discord.com` to the server.
+pub struct MockApi {
+    addr: SocketAddr,
+    state: Arc<Routes>,
+}
+
+impl MockApi {
+    /// Answers requests whose path starts with `prefix`.
+    pub fn route(&self, prefix: &str, responder: impl Fn(&Request) -> (u16, Value) + Send + Sync + 'static) {
+        self.state.routes.lock().unwrap().push((prefix.to_string(), Arc::new(responder)));
+    }
+
+    /// The requests received so far whose path starts with `prefix`.
+    pub fn requests(&self, prefix: &str) -> Vec<Request> {
+        self.state.requests.lock().unwrap().iter().filter(|request| request.path.starts_with(prefix)).cloned().collect()
+    }
+
+    /// A client that sends requests for `discord.com` to this server.
+    pub fn client(&self) -> Client {
+        Client::builder()
+            .resolve("discord.com", self.addr)
+            .danger_accept_invalid_certs(true)
+            .build()
+            .unwrap()
+    }
+}
+
+impl Routes {
+    fn respond(&self, request: &Request) -> (u16, Value) {
+        self.requests.lock().unwrap().push(request.clone());
+        // Responders may be slow, so don't hold up other requests while they run.
+        let responder = self.routes.lock().unwrap()
+            .iter()
+            .filter(|(prefix, _)| request.path.starts_with(prefix.as_str()))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(_, responder)| Arc::clone(responder));
+        match responder {
+            Some(responder) => responder(request),
+            None => (404, serde_json::json!({ "message": "Unknown route", "code": 0 })),
+        }
+    }
+}
+
+/// Starts a mock API for one test.
+pub fn mock_api() -> MockApi {
+    let identity = Identity::from_pkcs8(include_bytes!("../fixtures/mock_api_cert.pem"), include_bytes!("../fixtures/mock_api_key.pem")).unwrap();
+    let acceptor = Arc::new(TlsAcceptor::new(identity).unwrap());
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let state = Arc::new(Routes::default());
+
+    let routes = Arc::clone(&state);
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let (acceptor, routes) = (Arc::clone(&acceptor), Arc::clone(&routes));
+            thread::spawn(move || serve(&acceptor, &routes, stream));
+        }
+    });
+    MockApi { addr, state }
+}
+
+fn serve(acceptor: &TlsAcceptor, routes: &Routes, stream: TcpStream) {
+    let Ok(stream) = acceptor.accept(stream) else { return };
+    let mut reader = BufReader::new(stream);
+    // Clients may keep the connection alive for several requests.
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).unwrap_or(0) == 0 {
+            return;
+        }
+        let mut parts = line.split_whitespace();
+        let method = parts.next().unwrap_or_default().to_string();
+        let path = parts.next().unwrap_or_default().trim_start_matches("/api/v9").to_string();
+
+        let mut headers = HashMap::new();
+        loop {
+            line.clear();
+            if reader.read_line(&mut line).unwrap_or(0) == 0 || line.trim().is_empty() {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':') {
+                headers.insert(name.trim().to_ascii_lowercase(), value.trim().to_string());
+            }
+        }
+        let length = headers.get("content-length").and_then(|length| length.parse().ok()).unwrap_or(0);
+        let mut body = vec![0; length];
+        if reader.read_exact(&mut body).is_err() {
+            return;
+        }
+        let body = serde_json::from_slice(&body).unwrap_or(Value::Null);
+
+        let (status, response) = routes.respond(&Request { method, path, headers, body });
+        let response = if status == 204 { String::new() } else { response.to_string() };
+        let written = write!(
+            reader.get_mut(),
+            "HTTP/1.1 {} Mock\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+            status,
+            response.len(),
+            response
+        );
+        if written.and_then(|_| reader.get_mut().flush()).is_err() {
+            return;
+        }
+    }
+}
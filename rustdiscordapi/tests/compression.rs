@@ -0,0 +1,158 @@
+#![cfg(any(feature = "zlib", feature = "zstd"))]
+
+use futures::{SinkExt, StreamExt};
+use reqwest::Client;
+use serde_json::Value;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio::time::timeout;
+use tokio_tungstenite::accept_async;
+use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+use tokio_tungstenite::tungstenite::protocol::CloseFrame;
+use tokio_tungstenite::tungstenite::Message as WsMessage;
+
+use Rust_Discord_API::cache::Cache;
+use Rust_Discord_API::gateway::{Compression, Decompressor};
+use Rust_Discord_API::{GatewayClient, GatewayError, Intents};
+
+/// Reads a recorded stream: each WebSocket message is a big-endian `u32`
+/// length followed by the message bytes.
+fn recorded_frames(name: &str) -> Vec<Vec<u8>> {
+    let data = std::fs::read(format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)).unwrap();
+    let mut frames = Vec::new();
+    let mut rest = &data[..];
+    while !rest.is_empty() {
+        let (length, tail) = rest.split_at(4);
+        let length = u32::from_be_bytes(length.try_into().unwrap()) as usize;
+        frames.push(tail[..length].to_vec());
+        rest = &tail[length..];
+    }
+    frames
+}
+
+/// The payloads the recorded streams carry, one JSON document per line.
+fn expected_payloads() -> Vec<Value> {
+    let data = std::fs::read_to_string(format!("{}/tests/fixtures/gateway_payloads.jsonl", env!("CARGO_MANIFEST_DIR"))).unwrap();
+    data.lines().map(|line| serde_json::from_str(line).unwrap()).collect()
+}
+
+fn replay(compression: Compression, frames: &[Vec<u8>]) -> (Vec<Value>, usize) {
+    let mut decompressor = Decompressor::new(compression).unwrap().unwrap();
+    let mut payloads = Vec::new();
+    let mut incomplete = 0;
+    for frame in frames {
+        match decompressor.push(frame).unwrap() {
+            Some(data) => payloads.push(serde_json::from_slice(&data).unwrap()),
+            None => incomplete += 1,
+        }
+    }
+    (payloads, incomplete)
+}
+
+#[test]
+fn no_decompressor_without_compression() {
+    assert!(Decompressor::new(Compression::None).unwrap().is_none());
+}
+
+#[cfg(feature = "zlib")]
+#[test]
+fn replays_zlib_stream() {
+    let frames = recorded_frames("zlib_stream.bin");
+    let (payloads, incomplete) = replay(Compression::Zlib, &frames);
+
+    // GUILD_CREATE is split across three messages; only the last one ends
+    // with the Z_SYNC_FLUSH suffix.
+    assert_eq!(incomplete, 2);
+    assert_eq!(payloads, expected_payloads());
+    assert_eq!(payloads[2]["d"]["members"].as_array().unwrap().len(), 300);
+}
+
+#[cfg(feature = "zlib")]
+#[test]
+fn zlib_context_spans_the_connection() {
+    // Messages continue one stream, so a later message can't be decoded on its own.
+    let frames = recorded_frames("zlib_stream.bin");
+    let mut decompressor = Decompressor::new(Compression::Zlib).unwrap().unwrap();
+    assert!(decompressor.push(frames.last().unwrap()).is_err());
+}
+
+#[cfg(feature = "zlib")]
+#[test]
+fn rejects_data_after_the_end_of_the_zlib_stream() {
+    // An empty stream closed with a final block, then a stray sync flush that
+    // the finished stream can never consume.
+    let header = [0x78, 0x9c];
+    let final_block = [0x01, 0x00, 0x00, 0xff, 0xff];
+    let checksum = [0x00, 0x00, 0x00, 0x01];
+    let data = [&header[..], &final_block, &checksum, &[0x00, 0x00, 0xff, 0xff]].concat();
+
+    let mut decompressor = Decompressor::new(Compression::Zlib).unwrap().unwrap();
+    let error = decompressor.push(&data).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[cfg(feature = "zstd")]
+#[test]
+fn replays_zstd_stream() {
+    let frames = recorded_frames("zstd_stream.bin");
+    let (payloads, incomplete) = replay(Compression::Zstd, &frames);
+
+    assert_eq!(incomplete, 0);
+    assert_eq!(payloads, expected_payloads());
+}
+
+/// Serves a recorded stream to the gateway client, then closes with 4004.
+/// Returns the query string the client connected with.
+async fn serve_recording(name: &'static str, compression: Compression) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    let query = Arc::new(Mutex::new(String::new()));
+
+    let requested = query.clone();
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        // The request line looks like `GET /?v=10&... HTTP/1.1`.
+        let mut request = [0; 1024];
+        let length = stream.peek(&mut request).await.unwrap();
+        let line = String::from_utf8_lossy(&request[..length]).lines().next().unwrap_or_default().to_string();
+        *requested.lock().unwrap() = line.split(['?', ' ']).nth(2).unwrap_or_default().to_string();
+        let mut socket = accept_async(stream).await.unwrap();
+
+        let frames = recorded_frames(name);
+        socket.send(WsMessage::binary(frames[0].clone())).await.unwrap();
+        // Wait for Identify before sending the rest of the recording.
+        socket.next().await.unwrap().unwrap();
+        for frame in &frames[1..] {
+            socket.send(WsMessage::binary(frame.clone())).await.unwrap();
+        }
+        let frame = CloseFrame { code: CloseCode::from(4004), reason: "done".into() };
+        let _ = socket.close(Some(frame)).await;
+    });
+
+    let cache = Arc::new(Cache::default());
+    let gateway = GatewayClient::new(Client::new(), "token", Intents::GUILDS).url(&url).compression(compression).cache(cache.clone());
+    let error = timeout(Duration::from_secs(5), gateway.run()).await.unwrap().unwrap_err();
+    assert!(matches!(error, GatewayError::Closed { code: 4004, .. }));
+
+    assert_eq!(cache.guild("10").await.unwrap().unwrap().name, "Large Guild");
+    assert_eq!(cache.guild_members("10").await.unwrap().len(), 300);
+    assert_eq!(cache.channel_messages("20").await.unwrap()[0].content, "hello");
+
+    let query = query.lock().unwrap().clone();
+    query
+}
+
+#[cfg(feature = "zlib")]
+#[tokio::test]
+async fn gateway_reads_zlib_stream() {
+    let query = serve_recording("zlib_stream.bin", Compression::Zlib).await;
+    assert_eq!(query, "v=10&encoding=json&compress=zlib-stream");
+}
+
+#[cfg(feature = "zstd")]
+#[tokio::test]
+async fn gateway_reads_zstd_stream() {
+    let query = serve_recording("zstd_stream.bin", Compression::Zstd).await;
+    assert_eq!(query, "v=10&encoding=json&compress=zstd-stream");
+}
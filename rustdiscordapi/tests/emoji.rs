@@ -0,0 +1,214 @@
+mod common;
+
+use futures::StreamExt;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+use common::{mock_api, Request};
+use Rust_Discord_API::channel::{
+    create_reaction, delete_all_reactions_for_emoji, delete_own_reaction, delete_user_reaction, get_reactions, stream_reactions,
+};
+use Rust_Discord_API::component::PartialEmoji;
+use Rust_Discord_API::emoji::{
+    create_application_emoji, create_guild_emoji, delete_guild_emoji, image_data_uri, list_application_emojis, modify_guild_emoji, Emoji,
+    ReactionKind, ReactionType, MAX_EMOJI_SIZE,
+};
+use Rust_Discord_API::event::Event;
+use Rust_Discord_API::pagination::PaginationOptions;
+
+const PNG: &[u8] = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+
+/// Echoes the created or modified emoji back.
+fn echo_emoji(request: &Request) -> (u16, Value) {
+    let mut emoji = json!({ "id": "900", "name": "blob", "roles": [], "animated": false });
+    if let Some(body) = request.body.as_object() {
+        for (key, value) in body {
+            emoji[key] = value.clone();
+        }
+    }
+    (200, emoji)
+}
+
+#[tokio::test]
+async fn uploads_guild_emojis_as_data_uris_with_roles() {
+    let api = mock_api();
+    api.route("/guilds/4501/", echo_emoji);
+
+    let emoji = create_guild_emoji(&api.client(), "token", "4501", "blob", PNG, &["10", "11"], Some("new emoji")).await.unwrap();
+    assert_eq!(emoji.roles, ["10", "11"]);
+    assert!(emoji.available);
+
+    let requests = api.requests("/guilds/4501/");
+    assert_eq!((requests[0].method.as_str(), requests[0].path.as_str()), ("POST", "/guilds/4501/emojis"));
+    assert_eq!(requests[0].headers["x-audit-log-reason"], "new%20emoji");
+    assert_eq!(requests[0].body["image"], image_data_uri(PNG).unwrap());
+    assert!(requests[0].body["image"].as_str().unwrap().starts_with("data:image/png;base64,iVBORw0KGgo"));
+}
+
+#[tokio::test]
+async fn rejects_unusable_images_without_a_request() {
+    let api = mock_api();
+    api.route("/guilds/4502/", echo_emoji);
+
+    let client = api.client();
+    let mut huge = PNG.to_vec();
+    huge.resize(MAX_EMOJI_SIZE + 1, 0);
+    assert!(create_guild_emoji(&client, "token", "4502", "blob", &huge, &[], None).await.is_err());
+    assert!(create_guild_emoji(&client, "token", "4502", "blob", b"<svg/>", &[], None).await.is_err());
+    assert!(api.requests("/guilds/4502/").is_empty());
+
+    assert!(image_data_uri(b"GIF89a").unwrap().starts_with("data:image/gif;base64,"));
+    assert!(image_data_uri(&[0xFF, 0xD8, 0xFF, 0xE0]).unwrap().starts_with("data:image/jpeg;base64,"));
+    assert!(image_data_uri(b"RIFF\0\0\0\0WEBPVP8 ").unwrap().starts_with("data:image/webp;base64,"));
+}
+
+#[tokio::test]
+async fn modifies_and_deletes_guild_emojis() {
+    let api = mock_api();
+    api.route("/guilds/4503/", echo_emoji);
+
+    let client = api.client();
+    let emoji = modify_guild_emoji(&client, "token", "4503", "900", None, Some(&[]), None).await.unwrap();
+    assert!(emoji.roles.is_empty());
+    modify_guild_emoji(&client, "token", "4503", "900", Some("blob2"), None, None).await.unwrap();
+    delete_guild_emoji(&client, "token", "4503", "900", Some("unused")).await.unwrap();
+
+    let requests = api.requests("/guilds/4503/");
+    // Only the given fields are sent, so roles are cleared or kept as asked.
+    assert_eq!(requests[0].body, json!({ "roles": [] }));
+    assert_eq!(requests[1].body, json!({ "name": "blob2" }));
+    assert_eq!((requests[2].method.as_str(), requests[2].path.as_str()), ("DELETE", "/guilds/4503/emojis/900"));
+    assert_eq!(requests[2].headers["x-audit-log-reason"], "unused");
+}
+
+#[tokio::test]
+async fn manages_application_emojis() {
+    let api = mock_api();
+    api.route("/applications/4504/", |request| match request.method.as_str() {
+        "GET" => (200, json!({ "items": [{ "id": "1", "name": "wave", "animated": true, "available": true }] })),
+        _ => echo_emoji(request),
+    });
+
+    let client = api.client();
+    let emojis = list_application_emojis(&client, "token", "4504").await.unwrap();
+    assert_eq!(emojis.len(), 1);
+    assert_eq!(emojis[0].mention(), "<a:wave:1>");
+
+    create_application_emoji(&client, "token", "4504", "wave", PNG).await.unwrap();
+    let requests = api.requests("/applications/4504/");
+    assert_eq!(requests[1].path, "/applications/4504/emojis");
+    assert!(requests[1].body.get("roles").is_none());
+    assert!(!requests[1].headers.contains_key("x-audit-log-reason"));
+}
+
+#[tokio::test]
+async fn reaction_endpoints_encode_the_emoji() {
+    let api = mock_api();
+    api.route("/channels/4505/", |_| (204, Value::Null));
+
+    let client = api.client();
+    let custom: Emoji = serde_json::from_value(json!({ "id": "41771983429993937", "name": "blob" })).unwrap();
+    create_reaction(&client, "token", "4505", "1", &ReactionType::unicode("👍")).await.unwrap();
+    create_reaction(&client, "token", "4505", "1", &custom.reaction()).await.unwrap();
+    // Keycap emoji start with characters that mean something in a URL.
+    delete_own_reaction(&client, "token", "4505", "1", &ReactionType::unicode("#️⃣")).await.unwrap();
+    delete_user_reaction(&client, "token", "4505", "1", &ReactionType::custom("blob", "41771983429993937"), "7").await.unwrap();
+    delete_all_reactions_for_emoji(&client, "token", "4505", "1", &ReactionType::unicode("👍")).await.unwrap();
+
+    let paths: Vec<String> = api.requests("/channels/4505/").into_iter().map(|request| request.path).collect();
+    assert_eq!(
+        paths,
+        [
+            "/channels/4505/messages/1/reactions/%F0%9F%91%8D/@me",
+            "/channels/4505/messages/1/reactions/blob%3A41771983429993937/@me",
+            "/channels/4505/messages/1/reactions/%23%EF%B8%8F%E2%83%A3/@me",
+            "/channels/4505/messages/1/reactions/blob%3A41771983429993937/7",
+            "/channels/4505/messages/1/reactions/%F0%9F%91%8D",
+        ]
+    );
+}
+
+/// Serves reactors 1 to 5 in pages, honouring `after` and `limit`.
+fn reactors(request: &Request) -> (u16, Value) {
+    let (_, query) = request.path.split_once('?').unwrap();
+    let query: HashMap<&str, &str> = query.split('&').filter_map(|pair| pair.split_once('=')).collect();
+    let after: u64 = query.get("after").map_or(0, |after| after.parse().unwrap());
+    let limit: u64 = query["limit"].parse().unwrap();
+    let users: Vec<Value> = (after + 1..=5).take(limit as usize).map(|id| json!({ "id": id.to_string(), "username": format!("user{}", id) })).collect();
+    (200, json!(users))
+}
+
+#[tokio::test]
+async fn lists_normal_and_burst_reactors() {
+    let api = mock_api();
+    api.route("/channels/4506/", reactors);
+
+    let client = api.client();
+    let emoji: ReactionType = "<:blob:41771983429993937>".parse().unwrap();
+    let users = get_reactions(&client, "token", "4506", "1", &emoji, ReactionKind::Normal, None).await.unwrap();
+    assert_eq!(users.len(), 5);
+    let users = get_reactions(&client, "token", "4506", "1", &ReactionType::unicode("🔥"), ReactionKind::Burst, Some("3")).await.unwrap();
+    assert_eq!(users.iter().map(|user| user.id.as_str()).collect::<Vec<_>>(), ["4", "5"]);
+
+    let requests = api.requests("/channels/4506/");
+    assert_eq!(requests[0].path, "/channels/4506/messages/1/reactions/blob%3A41771983429993937?type=0&limit=100");
+    assert_eq!(requests[1].path, "/channels/4506/messages/1/reactions/%F0%9F%94%A5?type=1&limit=100&after=3");
+}
+
+#[tokio::test]
+async fn streams_burst_reactors_across_pages() {
+    let api = mock_api();
+    api.route("/channels/4507/", reactors);
+
+    let options = PaginationOptions::new().page_size(2);
+    let users: Vec<Value> = stream_reactions(&api.client(), "token", "4507", "1", &ReactionType::unicode("🔥"), ReactionKind::Burst, options)
+        .map(Result::unwrap)
+        .collect()
+        .await;
+    assert_eq!(users.len(), 5);
+
+    let requests = api.requests("/channels/4507/");
+    assert_eq!(requests.len(), 3);
+    assert!(requests.iter().all(|request| request.path.contains("type=1")));
+    assert!(requests[2].path.ends_with("after=4&limit=2"), "{}", requests[2].path);
+}
+
+#[test]
+fn parses_reaction_emojis() {
+    assert_eq!("👍".parse(), Ok(ReactionType::unicode("👍")));
+    assert_eq!(" 🏳️‍🌈 ".parse(), Ok(ReactionType::unicode("🏳️‍🌈")));
+    assert_eq!("1️⃣".parse(), Ok(ReactionType::unicode("1️⃣")));
+    assert_eq!("blob:5".parse(), Ok(ReactionType::custom("blob", "5")));
+    assert_eq!("<:blob:5>".parse(), Ok(ReactionType::custom("blob", "5")));
+    assert_eq!("<a:dance:6>".parse(), Ok(ReactionType::custom("dance", "6")));
+
+    for invalid in ["", "thumbsup", ":thumbsup:", "blob:", ":5", "blob:five", "<blob:5>", "<:blob:5", "a:b:5"] {
+        assert!(invalid.parse::<ReactionType>().is_err(), "{:?}", invalid);
+    }
+    // Parsed emojis round-trip through the URL form.
+    let emoji: ReactionType = "<a:dance:6>".parse().unwrap();
+    assert_eq!(emoji.to_string().parse(), Ok(emoji));
+}
+
+#[test]
+fn converts_emojis_to_reactions() {
+    assert_eq!(ReactionType::from(&PartialEmoji::unicode("✅")), ReactionType::unicode("✅"));
+    assert_eq!(ReactionType::from(&PartialEmoji::custom("blob", "5", true)), ReactionType::custom("blob", "5"));
+    assert_eq!(ReactionType::custom("blob", "5").to_string(), "blob:5");
+
+    // A deleted emoji has no name, but the ID is all Discord needs.
+    let deleted: Emoji = serde_json::from_value(json!({ "id": "5", "name": null, "available": false })).unwrap();
+    assert!(!deleted.available);
+    assert_eq!(deleted.reaction(), ReactionType::custom("_", "5"));
+}
+
+#[test]
+fn parses_typed_emoji_updates() {
+    let data = json!({ "guild_id": "10", "emojis": [{ "id": "5", "name": "blob", "roles": ["11"], "require_colons": true, "managed": false }] });
+    let Event::GuildEmojisUpdate(update) = Event::from_dispatch("GUILD_EMOJIS_UPDATE", data).unwrap() else {
+        panic!("expected an emoji update");
+    };
+    assert_eq!(update.emojis[0].roles, ["11"]);
+    assert!(update.emojis[0].require_colons);
+    assert_eq!(update.emojis[0].mention(), "<:blob:5>");
+}
@@ -0,0 +1,174 @@
+use futures::{SinkExt, StreamExt};
+use reqwest::Client;
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio::time::timeout;
+use tokio_tungstenite::accept_async;
+use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+use tokio_tungstenite::tungstenite::protocol::CloseFrame;
+use tokio_tungstenite::tungstenite::Message as WsMessage;
+
+use Rust_Discord_API::cache::Cache;
+use Rust_Discord_API::event::Event;
+use Rust_Discord_API::gateway::{etf, Encoding};
+use Rust_Discord_API::{GatewayClient, GatewayError, Intents};
+
+/// Reads the recorded ETF payloads: each is a big-endian `u32` length
+/// followed by the payload bytes, encoded as Discord sends them with atom
+/// keys and integer snowflakes.
+fn recorded_payloads() -> Vec<Vec<u8>> {
+    let data = std::fs::read(format!("{}/tests/fixtures/etf_payloads.bin", env!("CARGO_MANIFEST_DIR"))).unwrap();
+    let mut payloads = Vec::new();
+    let mut rest = &data[..];
+    while !rest.is_empty() {
+        let (length, tail) = rest.split_at(4);
+        let length = u32::from_be_bytes(length.try_into().unwrap()) as usize;
+        payloads.push(tail[..length].to_vec());
+        rest = &tail[length..];
+    }
+    payloads
+}
+
+/// The same payloads as JSON, one document per line.
+fn json_payloads() -> Vec<Value> {
+    let data = std::fs::read_to_string(format!("{}/tests/fixtures/gateway_payloads.jsonl", env!("CARGO_MANIFEST_DIR"))).unwrap();
+    data.lines().map(|line| serde_json::from_str(line).unwrap()).collect()
+}
+
+#[test]
+fn decodes_like_json() {
+    let decoded: Vec<Value> = recorded_payloads().iter().map(|payload| etf::decode(payload).unwrap()).collect();
+    assert_eq!(decoded, json_payloads());
+
+    // Snowflakes beyond 32 bits arrive as big integers and decode to strings.
+    assert_eq!(decoded[2]["d"]["members"][0]["user"]["id"], "100000000000000000");
+}
+
+#[test]
+fn events_parse_the_same_as_json() {
+    for (etf_payload, json_payload) in recorded_payloads().iter().zip(json_payloads()) {
+        let etf_payload = etf::decode(etf_payload).unwrap();
+        let Some(name) = json_payload["t"].as_str() else { continue };
+        let from_etf = Event::from_dispatch(name, etf_payload["d"].clone()).unwrap();
+        let from_json = Event::from_dispatch(name, json_payload["d"].clone()).unwrap();
+        assert_eq!(format!("{:?}", from_etf), format!("{:?}", from_json));
+    }
+}
+
+#[test]
+fn round_trips_payloads() {
+    let mut payloads = json_payloads();
+    payloads.push(json!({
+        "op": 8,
+        "d": {
+            "guild_id": "81384788765712384",
+            "user_ids": ["1", "300", "81384788765712384"],
+            "query": "",
+            "limit": 0,
+            "presences": false,
+            "nonce": "42",
+            "ratio": -1.5,
+            "offset": -70000,
+            "large": 18446744073709551615u64,
+            "negative": -9223372036854775808i64,
+            "name": "Großes Gildchen ✨",
+            "nested": [[], [null, true]],
+            "empty": {},
+        },
+    }));
+
+    for payload in payloads {
+        assert_eq!(etf::decode(&etf::encode(&payload)).unwrap(), payload);
+    }
+}
+
+#[test]
+fn encodes_snowflakes_as_integers() {
+    let encoded = etf::encode(&json!({ "guild_id": "10", "query": "10" }));
+    let mut expected = vec![131, 116, 0, 0, 0, 2];
+    expected.extend([109, 0, 0, 0, 8]);
+    expected.extend(b"guild_id");
+    expected.extend([97, 10]);
+    expected.extend([109, 0, 0, 0, 5]);
+    expected.extend(b"query");
+    expected.extend([109, 0, 0, 0, 2]);
+    expected.extend(b"10");
+    assert_eq!(encoded, expected);
+
+    let encoded = etf::encode(&json!({ "id": "81384788765712384" }));
+    assert_eq!(&encoded[encoded.len() - 11..], &[110, 8, 0, 0, 32, 128, 192, 8, 35, 33, 1][..]);
+}
+
+#[test]
+fn decodes_other_term_types() {
+    // Latin-1 atoms, a charlist, a tuple and a large big integer.
+    let mut data = vec![131, 108, 0, 0, 0, 4];
+    data.extend([100, 0, 5]);
+    data.extend(b"false");
+    data.extend([107, 0, 2]);
+    data.extend(b"hi");
+    data.extend([104, 2, 97, 1, 115, 3]);
+    data.extend(b"nil");
+    data.extend([111, 0, 0, 0, 2, 1, 0, 1]);
+    data.push(106);
+    assert_eq!(etf::decode(&data).unwrap(), json!([false, "hi", [1, null], -256]));
+}
+
+#[test]
+fn rejects_malformed_payloads() {
+    let payload = etf::encode(&json!({ "op": 11 }));
+    assert!(etf::decode(&payload[..payload.len() - 1]).is_err());
+    assert!(etf::decode(&[130, 97, 1]).is_err());
+    assert!(etf::decode(&[131, 97, 1, 0]).is_err());
+    // An improper list, whose tail is not an empty list.
+    assert!(etf::decode(&[131, 108, 0, 0, 0, 1, 97, 1, 97, 2]).is_err());
+}
+
+#[tokio::test]
+async fn gateway_connects_with_etf() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    let query = Arc::new(Mutex::new(String::new()));
+    let identify = Arc::new(Mutex::new(Value::Null));
+
+    let (requested, identified) = (query.clone(), identify.clone());
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        // The request line looks like `GET /?v=10&... HTTP/1.1`.
+        let mut request = [0; 1024];
+        let length = stream.peek(&mut request).await.unwrap();
+        let line = String::from_utf8_lossy(&request[..length]).lines().next().unwrap_or_default().to_string();
+        *requested.lock().unwrap() = line.split(['?', ' ']).nth(2).unwrap_or_default().to_string();
+        let mut socket = accept_async(stream).await.unwrap();
+
+        let payloads = recorded_payloads();
+        socket.send(WsMessage::binary(payloads[0].clone())).await.unwrap();
+        // Identify must come back as ETF too.
+        match socket.next().await.unwrap().unwrap() {
+            WsMessage::Binary(data) => *identified.lock().unwrap() = etf::decode(&data).unwrap(),
+            other => panic!("expected an ETF payload, got {:?}", other),
+        }
+        for payload in &payloads[1..] {
+            socket.send(WsMessage::binary(payload.clone())).await.unwrap();
+        }
+        let frame = CloseFrame { code: CloseCode::from(4004), reason: "done".into() };
+        let _ = socket.close(Some(frame)).await;
+    });
+
+    let cache = Arc::new(Cache::default());
+    let gateway = GatewayClient::new(Client::new(), "token", Intents::GUILDS).url(&url).encoding(Encoding::Etf).cache(cache.clone());
+    let error = timeout(Duration::from_secs(5), gateway.run()).await.unwrap().unwrap_err();
+    assert!(matches!(error, GatewayError::Closed { code: 4004, .. }));
+
+    assert_eq!(*query.lock().unwrap(), "v=10&encoding=etf");
+    let identify = identify.lock().unwrap().clone();
+    assert_eq!(identify["op"], 2);
+    assert_eq!(identify["d"]["token"], "token");
+    assert_eq!(identify["d"]["intents"], Intents::GUILDS.bits());
+
+    assert_eq!(cache.guild("10").await.unwrap().unwrap().name, "Large Guild");
+    assert_eq!(cache.guild_members("10").await.unwrap().len(), 300);
+    assert_eq!(cache.channel_messages("20").await.unwrap()[0].content, "hello");
+}
@@ -0,0 +1,637 @@
+use async_trait::async_trait;
+use futures::{SinkExt, StreamExt};
+use reqwest::Client;
+use serde_json::{json, Value};
+use std::error::Error;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tokio::time::timeout;
+use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+use tokio_tungstenite::tungstenite::protocol::CloseFrame;
+use tokio_tungstenite::tungstenite::Message as WsMessage;
+use tokio_tungstenite::{accept_async, WebSocketStream};
+
+use Rust_Discord_API::cache::Cache;
+use Rust_Discord_API::event::{Event, EventBus, MemberAdd, Ready};
+use Rust_Discord_API::model::Message;
+use Rust_Discord_API::gateway::{Activity, ActivityType, MemberRequest, Presence, Status};
+use Rust_Discord_API::{Command, CommandRouter, EventHandler, GatewayClient, GatewayError, Intents};
+
+type Socket = WebSocketStream<TcpStream>;
+
+async fn listen() -> (TcpListener, String) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    (listener, url)
+}
+
+async fn accept(listener: &TcpListener) -> Socket {
+    let (stream, _) = listener.accept().await.unwrap();
+    accept_async(stream).await.unwrap()
+}
+
+async fn send(socket: &mut Socket, payload: Value) {
+    socket.send(WsMessage::text(payload.to_string())).await.unwrap();
+}
+
+async fn receive(socket: &mut Socket) -> Value {
+    loop {
+        match socket.next().await.unwrap().unwrap() {
+            WsMessage::Text(text) => return serde_json::from_str(&text).unwrap(),
+            WsMessage::Close(frame) => panic!("client closed the connection: {:?}", frame),
+            _ => {}
+        }
+    }
+}
+
+/// Receives the next payload that is not a heartbeat.
+async fn receive_command(socket: &mut Socket) -> Value {
+    loop {
+        let payload = receive(socket).await;
+        if payload["op"] != 1 {
+            return payload;
+        }
+    }
+}
+
+async fn close(socket: &mut Socket, code: u16) {
+    let frame = CloseFrame { code: CloseCode::from(code), reason: "test".into() };
+    let _ = socket.close(Some(frame)).await;
+}
+
+fn hello(interval: u64) -> Value {
+    json!({ "op": 10, "d": { "heartbeat_interval": interval } })
+}
+
+fn dispatch(name: &str, sequence: u64, data: Value) -> Value {
+    json!({ "op": 0, "s": sequence, "t": name, "d": data })
+}
+
+fn ready(resume_url: &str) -> Value {
+    json!({
+        "v": 10,
+        "user": { "id": "1", "username": "bot", "bot": true },
+        "guilds": [{ "id": "10", "unavailable": true }],
+        "session_id": "session",
+        "resume_gateway_url": resume_url,
+    })
+}
+
+fn message(content: &str, bot: bool) -> Value {
+    json!({
+        "id": "30",
+        "channel_id": "20",
+        "author": { "id": "2", "username": "user", "bot": bot },
+        "content": content,
+    })
+}
+
+async fn run(gateway: GatewayClient) -> GatewayError {
+    timeout(Duration::from_secs(5), gateway.run()).await.expect("gateway did not stop").unwrap_err()
+}
+
+async fn next(receiver: &mut UnboundedReceiver<String>) -> String {
+    timeout(Duration::from_secs(5), receiver.recv()).await.expect("handler was not called").unwrap()
+}
+
+struct Recorder(UnboundedSender<String>);
+
+#[async_trait]
+impl EventHandler for Recorder {
+    async fn ready(&self, _client: &Client, _token: &str, event: &Ready) -> Result<(), Box<dyn Error>> {
+        self.0.send(format!("ready {}", event.session_id))?;
+        Ok(())
+    }
+
+    async fn resumed(&self, _client: &Client, _token: &str) -> Result<(), Box<dyn Error>> {
+        self.0.send("resumed".to_string())?;
+        Ok(())
+    }
+
+    async fn guild_member_add(&self, _client: &Client, _token: &str, event: &MemberAdd) -> Result<(), Box<dyn Error>> {
+        self.0.send(format!("member {}", event.guild_id))?;
+        Ok(())
+    }
+
+    async fn message_create(&self, _client: &Client, _token: &str, event: &Message) -> Result<(), Box<dyn Error>> {
+        if event.content == "panic" {
+            panic!("handler failure");
+        }
+        Err("handler error".into())
+    }
+
+    async fn unknown(&self, _client: &Client, _token: &str, name: &str, _data: &Value) -> Result<(), Box<dyn Error>> {
+        self.0.send(format!("unknown {}", name))?;
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn identifies_and_dispatches_events() {
+    let (listener, url) = listen().await;
+    let (sender, mut receiver) = unbounded_channel();
+    let bus = EventBus::default();
+    let mut events = bus.subscribe();
+
+    let server = tokio::spawn(async move {
+        let mut socket = accept(&listener).await;
+        send(&mut socket, hello(45_000)).await;
+        let identify = receive(&mut socket).await;
+        send(&mut socket, dispatch("READY", 1, ready("ws://unused"))).await;
+        send(&mut socket, dispatch("SOMETHING_NEW", 2, json!({}))).await;
+        close(&mut socket, 4004).await;
+        identify
+    });
+
+    let gateway = GatewayClient::new(Client::new(), "token", Intents::GUILDS | Intents::GUILD_MESSAGES)
+        .shard(1, 4)
+        .url(&url)
+        .event_handler(Arc::new(Recorder(sender)))
+        .event_bus(bus);
+    assert!(matches!(run(gateway).await, GatewayError::Closed { code: 4004, .. }));
+
+    let identify = server.await.unwrap();
+    assert_eq!(identify["op"], 2);
+    assert_eq!(identify["d"]["token"], "token");
+    assert_eq!(identify["d"]["intents"], 513);
+    assert_eq!(identify["d"]["shard"], json!([1, 4]));
+
+    let mut calls = vec![next(&mut receiver).await, next(&mut receiver).await];
+    calls.sort();
+    assert_eq!(calls, ["ready session", "unknown SOMETHING_NEW"]);
+    assert!(matches!(&*events.recv().await.unwrap(), Event::Ready(ready) if ready.guilds.len() == 1));
+}
+
+#[tokio::test]
+async fn handler_errors_and_panics_are_isolated() {
+    let (listener, url) = listen().await;
+    let (sender, mut receiver) = unbounded_channel();
+
+    tokio::spawn(async move {
+        let mut socket = accept(&listener).await;
+        send(&mut socket, hello(45_000)).await;
+        receive(&mut socket).await;
+        send(&mut socket, dispatch("MESSAGE_CREATE", 1, message("panic", false))).await;
+        send(&mut socket, dispatch("MESSAGE_CREATE", 2, message("error", false))).await;
+        let member = json!({ "guild_id": "10", "user": { "id": "2", "username": "user" }, "roles": [] });
+        send(&mut socket, dispatch("GUILD_MEMBER_ADD", 3, member)).await;
+        close(&mut socket, 4004).await;
+    });
+
+    let gateway = GatewayClient::new(Client::new(), "token", Intents::empty()).url(&url).event_handler(Arc::new(Recorder(sender)));
+    assert!(matches!(run(gateway).await, GatewayError::Closed { code: 4004, .. }));
+    assert_eq!(next(&mut receiver).await, "member 10");
+}
+
+#[tokio::test]
+async fn ignores_unknown_opcodes() {
+    let (listener, url) = listen().await;
+    let (sender, mut receiver) = unbounded_channel();
+
+    tokio::spawn(async move {
+        let mut socket = accept(&listener).await;
+        send(&mut socket, hello(45_000)).await;
+        receive(&mut socket).await;
+        // 263 and 265 would read as RECONNECT and INVALID_SESSION if truncated to a byte.
+        send(&mut socket, json!({ "op": 263, "d": null })).await;
+        send(&mut socket, json!({ "op": 265, "d": false })).await;
+        send(&mut socket, json!({ "op": "7", "d": null })).await;
+        send(&mut socket, dispatch("SOMETHING_NEW", 1, json!({}))).await;
+        close(&mut socket, 4004).await;
+    });
+
+    let gateway = GatewayClient::new(Client::new(), "token", Intents::empty()).url(&url).event_handler(Arc::new(Recorder(sender)));
+    assert!(matches!(run(gateway).await, GatewayError::Closed { code: 4004, .. }));
+    assert_eq!(next(&mut receiver).await, "unknown SOMETHING_NEW");
+}
+
+#[tokio::test]
+async fn resumes_after_reconnect_request() {
+    let (listener, url) = listen().await;
+    let (sender, mut receiver) = unbounded_channel();
+
+    let resume_url = url.clone();
+    let server = tokio::spawn(async move {
+        let mut socket = accept(&listener).await;
+        send(&mut socket, hello(45_000)).await;
+        receive(&mut socket).await;
+        send(&mut socket, dispatch("READY", 1, ready(&resume_url))).await;
+        send(&mut socket, dispatch("TYPING_START", 5, json!({ "channel_id": "20", "user_id": "2", "timestamp": 0 }))).await;
+        send(&mut socket, json!({ "op": 7, "d": null })).await;
+
+        let mut socket = accept(&listener).await;
+        send(&mut socket, hello(45_000)).await;
+        let resume = receive(&mut socket).await;
+        send(&mut socket, dispatch("RESUMED", 6, json!(null))).await;
+        close(&mut socket, 4004).await;
+        resume
+    });
+
+    let gateway = GatewayClient::new(Client::new(), "token", Intents::empty()).url(&url).event_handler(Arc::new(Recorder(sender)));
+    assert!(matches!(run(gateway).await, GatewayError::Closed { code: 4004, .. }));
+
+    let resume = server.await.unwrap();
+    assert_eq!(resume, json!({ "op": 6, "d": { "token": "token", "session_id": "session", "seq": 5 } }));
+    assert_eq!(next(&mut receiver).await, "ready session");
+    assert_eq!(next(&mut receiver).await, "resumed");
+}
+
+#[tokio::test]
+async fn heartbeats_with_the_last_sequence() {
+    let (listener, url) = listen().await;
+
+    let server = tokio::spawn(async move {
+        let mut socket = accept(&listener).await;
+        send(&mut socket, hello(20)).await;
+        receive(&mut socket).await;
+        send(&mut socket, dispatch("SOMETHING_NEW", 3, json!({}))).await;
+
+        // Skip heartbeats sent before the dispatch arrived.
+        let mut heartbeat = receive(&mut socket).await;
+        while heartbeat["d"] != 3 {
+            assert_eq!(heartbeat["op"], 1);
+            send(&mut socket, json!({ "op": 11 })).await;
+            heartbeat = receive(&mut socket).await;
+        }
+
+        // Heartbeats requested by Discord are answered immediately.
+        send(&mut socket, json!({ "op": 1, "d": null })).await;
+        let requested = receive(&mut socket).await;
+        close(&mut socket, 4004).await;
+        (heartbeat, requested)
+    });
+
+    let gateway = GatewayClient::new(Client::new(), "token", Intents::empty()).url(&url);
+    assert!(matches!(run(gateway).await, GatewayError::Closed { code: 4004, .. }));
+
+    let (heartbeat, requested) = server.await.unwrap();
+    assert_eq!(heartbeat, json!({ "op": 1, "d": 3 }));
+    assert_eq!(requested["op"], 1);
+}
+
+#[tokio::test]
+async fn reconnects_when_heartbeats_are_not_acknowledged() {
+    let (listener, url) = listen().await;
+
+    let server = tokio::spawn(async move {
+        let mut socket = accept(&listener).await;
+        send(&mut socket, hello(20)).await;
+        receive(&mut socket).await;
+        // Never acknowledge, so the client treats the connection as dead.
+        while let Some(Ok(message)) = socket.next().await {
+            if message.is_close() {
+                break;
+            }
+        }
+
+        let mut socket = accept(&listener).await;
+        send(&mut socket, hello(45_000)).await;
+        let handshake = receive(&mut socket).await;
+        close(&mut socket, 4004).await;
+        handshake
+    });
+
+    let gateway = GatewayClient::new(Client::new(), "token", Intents::empty()).url(&url);
+    assert!(matches!(run(gateway).await, GatewayError::Closed { code: 4004, .. }));
+    // No READY was received, so there is no session to resume.
+    assert_eq!(server.await.unwrap()["op"], 2);
+}
+
+struct Echo(UnboundedSender<String>);
+
+#[async_trait]
+impl Command for Echo {
+    async fn execute(&self, _client: &Client, _token: &str, channel_id: &str, args: &str) -> Result<(), Box<dyn Error>> {
+        self.0.send(format!("{} {}", channel_id, args))?;
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn routes_prefix_commands() {
+    let (listener, url) = listen().await;
+    let (sender, mut receiver) = unbounded_channel();
+
+    tokio::spawn(async move {
+        let mut socket = accept(&listener).await;
+        send(&mut socket, hello(45_000)).await;
+        receive(&mut socket).await;
+        send(&mut socket, dispatch("MESSAGE_CREATE", 1, message("!echo from bot", true))).await;
+        send(&mut socket, dispatch("MESSAGE_CREATE", 2, message("just chatting", false))).await;
+        send(&mut socket, dispatch("MESSAGE_CREATE", 3, message("!echo hello", false))).await;
+        close(&mut socket, 4004).await;
+    });
+
+    let mut router = CommandRouter::new();
+    router.register_command("!echo", Arc::new(Echo(sender)));
+    let gateway = GatewayClient::new(Client::new(), "token", Intents::empty()).url(&url).router(Arc::new(router));
+    assert!(matches!(run(gateway).await, GatewayError::Closed { code: 4004, .. }));
+
+    assert_eq!(next(&mut receiver).await, "20 hello");
+    assert!(!matches!(timeout(Duration::from_millis(100), receiver.recv()).await, Ok(Some(_))));
+}
+
+struct MemberLogger;
+
+#[async_trait]
+impl EventHandler for MemberLogger {
+    fn intents(&self) -> Intents {
+        Intents::GUILDS | Intents::GUILD_MEMBERS
+    }
+}
+
+#[test]
+fn warns_about_missing_intents() {
+    let gateway = GatewayClient::new(Client::new(), "token", Intents::GUILDS).event_handler(Arc::new(MemberLogger));
+    assert_eq!(gateway.intent_warnings(), ["The event handler needs intents that are not enabled: GUILD_MEMBERS"]);
+
+    let (sender, _) = unbounded_channel();
+    let mut router = CommandRouter::new();
+    router.register_command("!echo", Arc::new(Echo(sender)));
+    let router = Arc::new(router);
+
+    let gateway = GatewayClient::new(Client::new(), "token", Intents::GUILDS | Intents::GUILD_MESSAGES).router(router.clone());
+    let warnings = gateway.intent_warnings();
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings[0].contains("MESSAGE_CONTENT"));
+
+    let gateway = GatewayClient::new(Client::new(), "token", Intents::GUILDS).router(router.clone());
+    assert!(gateway.intent_warnings()[0].contains("GUILD_MESSAGES"));
+
+    let intents = Intents::non_privileged() | Intents::PRIVILEGED;
+    let gateway = GatewayClient::new(Client::new(), "token", intents).router(router).event_handler(Arc::new(MemberLogger));
+    assert!(gateway.intent_warnings().is_empty());
+
+    // Component and modal handlers work without any intents.
+    let gateway = GatewayClient::new(Client::new(), "token", Intents::empty()).router(Arc::new(CommandRouter::new()));
+    assert!(gateway.intent_warnings().is_empty());
+}
+
+#[tokio::test]
+async fn surfaces_disallowed_intents() {
+    let (listener, url) = listen().await;
+
+    tokio::spawn(async move {
+        let mut socket = accept(&listener).await;
+        send(&mut socket, hello(45_000)).await;
+        receive(&mut socket).await;
+        close(&mut socket, 4014).await;
+    });
+
+    let intents = Intents::GUILDS | Intents::GUILD_MEMBERS | Intents::MESSAGE_CONTENT;
+    let error = run(GatewayClient::new(Client::new(), "token", intents).url(&url)).await;
+    match &error {
+        GatewayError::DisallowedIntents(privileged) => assert_eq!(*privileged, Intents::GUILD_MEMBERS | Intents::MESSAGE_CONTENT),
+        other => panic!("unexpected error: {:?}", other),
+    }
+    assert!(error.to_string().contains("GUILD_MEMBERS, MESSAGE_CONTENT"));
+}
+
+#[tokio::test]
+async fn sets_presence_at_identify_and_at_runtime() {
+    let (listener, url) = listen().await;
+    let (identified, mut identify) = unbounded_channel();
+    let bus = EventBus::default();
+    let mut events = bus.subscribe();
+
+    let server = tokio::spawn(async move {
+        let mut socket = accept(&listener).await;
+        send(&mut socket, hello(45_000)).await;
+        identified.send(receive(&mut socket).await).unwrap();
+        let update = receive_command(&mut socket).await;
+        let presence = json!({
+            "user": { "id": "2" },
+            "guild_id": "10",
+            "status": "idle",
+            "activities": [{ "name": "Custom Status", "type": 4, "state": "Away", "emoji": { "name": "🌙" }, "created_at": 1 }],
+            "client_status": { "desktop": "idle" },
+        });
+        send(&mut socket, dispatch("PRESENCE_UPDATE", 1, presence)).await;
+        close(&mut socket, 4004).await;
+        update
+    });
+
+    let gateway = GatewayClient::new(Client::new(), "token", Intents::GUILD_PRESENCES)
+        .url(&url)
+        .shard(0, 2)
+        .presence(Presence::new(Status::Dnd).activity(Activity::playing("chess")))
+        .event_bus(bus);
+    let handle = gateway.handle();
+    let running = tokio::spawn(async move { run(gateway).await });
+
+    let identify = timeout(Duration::from_secs(5), identify.recv()).await.unwrap().unwrap();
+    assert_eq!(identify["d"]["presence"], json!({ "since": null, "activities": [{ "name": "chess", "type": 0 }], "status": "dnd", "afk": false }));
+
+    assert_eq!(handle.shard(), Some([0, 2]));
+    let streaming = Activity::streaming("Live", "https://twitch.tv/example");
+    handle.update_presence(Presence::new(Status::Idle).activity(streaming).afk(1_700_000_000_000)).unwrap();
+    let update = server.await.unwrap();
+    assert_eq!(update["op"], 3);
+    assert_eq!(
+        update["d"],
+        json!({
+            "since": 1_700_000_000_000u64,
+            "activities": [{ "name": "Live", "type": 1, "url": "https://twitch.tv/example" }],
+            "status": "idle",
+            "afk": true,
+        })
+    );
+
+    assert!(matches!(running.await.unwrap(), GatewayError::Closed { code: 4004, .. }));
+    match &*events.recv().await.unwrap() {
+        Event::PresenceUpdate(presence) => {
+            assert_eq!(presence.status, Status::Idle);
+            assert_eq!(presence.activities[0].kind, ActivityType::Custom);
+            assert_eq!(presence.activities[0].state.as_deref(), Some("Away"));
+        }
+        other => panic!("unexpected event: {:?}", other),
+    }
+
+    // The client is gone, so the handle can no longer reach it.
+    assert!(matches!(handle.update_presence(Presence::default()), Err(GatewayError::Stopped)));
+}
+
+#[test]
+fn builds_activities() {
+    let custom = serde_json::to_value(Activity::custom("Fixing bugs")).unwrap();
+    assert_eq!(custom, json!({ "name": "Custom Status", "type": 4, "state": "Fixing bugs" }));
+    assert_eq!(Activity::listening("a podcast").kind, ActivityType::Listening);
+    assert_eq!(Activity::watching("the logs").kind, ActivityType::Watching);
+    assert_eq!(Activity::competing("a tournament").kind, ActivityType::Competing);
+    assert_eq!(serde_json::to_value(Status::Invisible).unwrap(), "invisible");
+}
+
+#[test]
+fn keeps_unknown_activity_types() {
+    let presence = json!({
+        "user": { "id": "2" },
+        "guild_id": "10",
+        "status": "online",
+        "activities": [{ "name": "Hang Status", "type": 6 }, { "name": "chess", "type": 0 }],
+    });
+    let Event::PresenceUpdate(presence) = Event::from_dispatch("PRESENCE_UPDATE", presence).unwrap() else {
+        panic!("expected a presence update");
+    };
+    assert_eq!(presence.activities[0].kind, ActivityType::Unknown(6));
+    assert_eq!(presence.activities[1].kind, ActivityType::Playing);
+    assert_eq!(serde_json::to_value(&presence.activities[0]).unwrap()["type"], 6);
+}
+
+fn member(id: &str) -> Value {
+    json!({ "user": { "id": id, "username": format!("user{}", id) }, "roles": [] })
+}
+
+fn chunk(nonce: &Value, index: u32, count: u32, members: Vec<Value>) -> Value {
+    json!({ "guild_id": "10", "members": members, "chunk_index": index, "chunk_count": count, "nonce": nonce })
+}
+
+#[tokio::test]
+async fn requests_guild_members() {
+    let (listener, url) = listen().await;
+    let (requested, mut requests) = unbounded_channel();
+
+    tokio::spawn(async move {
+        let mut socket = accept(&listener).await;
+        send(&mut socket, hello(45_000)).await;
+        receive(&mut socket).await;
+
+        // A query answered in two chunks, interleaved with another request's chunk.
+        let request = receive_command(&mut socket).await;
+        let nonce = request["d"]["nonce"].clone();
+        requested.send(request).unwrap();
+        send(&mut socket, dispatch("GUILD_MEMBERS_CHUNK", 1, chunk(&nonce, 0, 2, vec![member("2"), member("3")]))).await;
+        send(&mut socket, dispatch("GUILD_MEMBERS_CHUNK", 2, chunk(&json!("other"), 0, 1, vec![member("9")]))).await;
+        send(&mut socket, dispatch("GUILD_MEMBERS_CHUNK", 3, chunk(&nonce, 1, 2, vec![member("4")]))).await;
+
+        // User IDs with presences, read as a stream.
+        let request = receive_command(&mut socket).await;
+        let mut reply = chunk(&request["d"]["nonce"], 0, 1, vec![member("2")]);
+        reply["not_found"] = json!(["5"]);
+        reply["presences"] = json!([{ "user": { "id": "2" }, "status": "online", "activities": [] }]);
+        requested.send(request).unwrap();
+        send(&mut socket, dispatch("GUILD_MEMBERS_CHUNK", 4, reply)).await;
+
+        // The connection closes before the last request is answered.
+        let request = receive_command(&mut socket).await;
+        send(&mut socket, dispatch("GUILD_MEMBERS_CHUNK", 5, chunk(&request["d"]["nonce"], 0, 2, vec![member("2")]))).await;
+        requested.send(request).unwrap();
+        close(&mut socket, 4004).await;
+    });
+
+    let cache = Arc::new(Cache::default());
+    let intents = Intents::GUILDS | Intents::GUILD_MEMBERS | Intents::GUILD_PRESENCES;
+    let gateway = GatewayClient::new(Client::new(), "token", intents).url(&url).cache(cache.clone());
+    let handle = gateway.handle();
+    let running = tokio::spawn(async move { run(gateway).await });
+
+    let members = handle.request_guild_members(MemberRequest::query("10", "user", 5)).unwrap().collect_members().await.unwrap();
+    let request = requests.recv().await.unwrap();
+    assert_eq!(request["op"], 8);
+    assert_eq!(request["d"], json!({ "guild_id": "10", "query": "user", "limit": 5, "presences": false, "nonce": request["d"]["nonce"] }));
+    let ids: Vec<_> = members.members.iter().map(|member| member.user.as_ref().unwrap().id.as_str()).collect();
+    assert_eq!(ids, ["2", "3", "4"]);
+    assert_eq!(cache.guild_members("10").await.unwrap().len(), 4);
+
+    let request = MemberRequest::user_ids("10", &["2", "5"]).presences(true).nonce("lookup");
+    let mut chunks = handle.request_guild_members(request).unwrap();
+    let chunk = chunks.next().await.unwrap();
+    assert!(chunks.next().await.is_none());
+    assert_eq!(chunk.not_found, ["5"]);
+    assert_eq!(chunk.presences[0].status, Status::Online);
+    let request = requests.recv().await.unwrap();
+    assert_eq!(request["d"]["user_ids"], json!(["2", "5"]));
+    assert_eq!(request["d"]["nonce"], "lookup");
+    assert_eq!(request["d"]["presences"], true);
+
+    let incomplete = handle.request_guild_members(MemberRequest::all("10")).unwrap();
+    assert_eq!(requests.recv().await.unwrap()["d"]["query"], "");
+    assert!(matches!(incomplete.collect_members().await, Err(GatewayError::Protocol(_))));
+    assert!(matches!(running.await.unwrap(), GatewayError::Closed { code: 4004, .. }));
+}
+
+#[tokio::test]
+async fn fails_member_requests_with_unreadable_chunks() {
+    let (listener, url) = listen().await;
+    let (done, finished) = tokio::sync::oneshot::channel::<()>();
+
+    tokio::spawn(async move {
+        let mut socket = accept(&listener).await;
+        send(&mut socket, hello(45_000)).await;
+        receive(&mut socket).await;
+
+        let request = receive_command(&mut socket).await;
+        let mut broken = chunk(&request["d"]["nonce"], 0, 2, vec![member("2")]);
+        broken["chunk_count"] = json!("two");
+        send(&mut socket, dispatch("GUILD_MEMBERS_CHUNK", 1, broken)).await;
+
+        // This request is never answered.
+        receive_command(&mut socket).await;
+        let _ = finished.await;
+        close(&mut socket, 4004).await;
+    });
+
+    let gateway = GatewayClient::new(Client::new(), "token", Intents::GUILDS | Intents::GUILD_MEMBERS).url(&url);
+    let handle = gateway.handle();
+    let running = tokio::spawn(async move { run(gateway).await });
+
+    let chunks = handle.request_guild_members(MemberRequest::query("10", "user", 5)).unwrap();
+    let result = timeout(Duration::from_secs(2), chunks.collect_members()).await.expect("request was not failed");
+    assert!(matches!(result, Err(GatewayError::Protocol(message)) if message.contains("could not be parsed")));
+
+    let chunks = handle.request_guild_members(MemberRequest::query("10", "other", 5)).unwrap();
+    let result = chunks.timeout(Duration::from_millis(200)).collect_members().await;
+    assert!(matches!(result, Err(GatewayError::Timeout(_))));
+
+    done.send(()).unwrap();
+    assert!(matches!(running.await.unwrap(), GatewayError::Closed { code: 4004, .. }));
+}
+
+#[tokio::test]
+async fn member_requests_survive_resumes() {
+    let (listener, url) = listen().await;
+
+    let resume_url = url.clone();
+    tokio::spawn(async move {
+        let mut socket = accept(&listener).await;
+        send(&mut socket, hello(45_000)).await;
+        receive(&mut socket).await;
+        send(&mut socket, dispatch("READY", 1, ready(&resume_url))).await;
+        let request = receive_command(&mut socket).await;
+        send(&mut socket, json!({ "op": 7, "d": null })).await;
+
+        // The chunk is replayed after resuming.
+        let mut socket = accept(&listener).await;
+        send(&mut socket, hello(45_000)).await;
+        receive(&mut socket).await;
+        send(&mut socket, dispatch("GUILD_MEMBERS_CHUNK", 2, chunk(&request["d"]["nonce"], 0, 1, vec![member("2")]))).await;
+        send(&mut socket, dispatch("RESUMED", 3, json!(null))).await;
+        close(&mut socket, 4004).await;
+    });
+
+    let gateway = GatewayClient::new(Client::new(), "token", Intents::GUILDS | Intents::GUILD_MEMBERS).url(&url);
+    let handle = gateway.handle();
+    let running = tokio::spawn(async move { run(gateway).await });
+
+    let chunks = handle.request_guild_members(MemberRequest::query("10", "user", 5)).unwrap();
+    let members = timeout(Duration::from_secs(5), chunks.collect_members()).await.unwrap().unwrap();
+    assert_eq!(members.members.len(), 1);
+    assert!(matches!(running.await.unwrap(), GatewayError::Closed { code: 4004, .. }));
+}
+
+#[test]
+fn rejects_invalid_member_requests() {
+    let gateway = GatewayClient::new(Client::new(), "token", Intents::GUILDS);
+    let handle = gateway.handle();
+    let invalid = |request| matches!(handle.request_guild_members(request), Err(GatewayError::InvalidRequest(_)));
+
+    assert!(invalid(MemberRequest::all("10")));
+    assert!(invalid(MemberRequest::query("10", "a", 1).presences(true)));
+    let ids: Vec<String> = (0..101).map(|id| id.to_string()).collect();
+    assert!(invalid(MemberRequest::user_ids("10", &ids.iter().map(String::as_str).collect::<Vec<_>>())));
+    assert!(invalid(MemberRequest::query("10", "a", 1).nonce(&"n".repeat(33))));
+
+    assert!(handle.request_guild_members(MemberRequest::query("10", "a", 1).nonce("same")).is_ok());
+    assert!(invalid(MemberRequest::query("10", "a", 1).nonce("same")));
+}
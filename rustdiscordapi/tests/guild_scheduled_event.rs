@@ -0,0 +1,221 @@
+mod common;
+
+use serde_json::{json, Value};
+use std::time::{Duration, UNIX_EPOCH};
+
+use common::mock_api;
+use Rust_Discord_API::event::Event;
+use Rust_Discord_API::guild_scheduled_event::{
+    create_scheduled_event, get_scheduled_event, list_scheduled_events, modify_scheduled_event, update_scheduled_event_status, EntityType, EventEntity,
+    Month, RecurrenceFrequency, RecurrenceRule, ScheduledEvent, ScheduledEventBuilder, ScheduledEventStatus, Weekday,
+};
+
+fn external_event(guild_id: &str, status: u8) -> Value {
+    json!({
+        "id": "5000",
+        "guild_id": guild_id,
+        "channel_id": null,
+        "creator_id": "1",
+        "name": "Meetup",
+        "description": null,
+        "scheduled_start_time": "2025-01-01T09:00:00+00:00",
+        "scheduled_end_time": "2025-01-01T11:00:00+00:00",
+        "privacy_level": 2,
+        "status": status,
+        "entity_type": 3,
+        "entity_id": null,
+        "entity_metadata": { "location": "Town hall" },
+        "user_count": 12,
+        "image": null,
+        "recurrence_rule": {
+            "start": "2025-01-01T09:00:00+00:00",
+            "end": null,
+            "frequency": 2,
+            "interval": 2,
+            "by_weekday": [2],
+            "by_n_weekday": null,
+            "by_month": null,
+            "by_month_day": null,
+            "by_year_day": null,
+            "count": null,
+        },
+    })
+}
+
+fn start() -> std::time::SystemTime {
+    UNIX_EPOCH + Duration::from_secs(1_735_722_000)
+}
+
+#[tokio::test]
+async fn lists_events_with_user_counts() {
+    let api = mock_api();
+    api.route("/guilds/5001/scheduled-events", |_| (200, json!([external_event("5001", 1)])));
+
+    let events = list_scheduled_events(&api.client(), "token", "5001", true).await.unwrap();
+    assert_eq!(events[0].user_count, Some(12));
+    assert_eq!(events[0].entity(), Some(EventEntity::External { location: "Town hall".to_string() }));
+    assert_eq!(events[0].ends().unwrap().duration_since(events[0].starts().unwrap()).unwrap(), Duration::from_secs(7200));
+    let rule = events[0].recurrence_rule.as_ref().unwrap();
+    assert_eq!((rule.frequency, rule.interval, rule.by_weekday.as_deref()), (RecurrenceFrequency::Weekly, 2, Some(&[Weekday::Wednesday][..])));
+    assert!(rule.validate().is_ok());
+
+    let requests = api.requests("/guilds/5001/scheduled-events");
+    assert_eq!((requests[0].method.as_str(), requests[0].path.as_str()), ("GET", "/guilds/5001/scheduled-events?with_user_count=true"));
+}
+
+#[tokio::test]
+async fn creates_external_events_with_recurrence() {
+    let api = mock_api();
+    api.route("/guilds/5002/scheduled-events", |_| (200, external_event("5002", 1)));
+
+    let event = ScheduledEventBuilder::new("Meetup", start(), EventEntity::External { location: "Town hall".to_string() })
+        .end_time(start() + Duration::from_secs(7200))
+        .recurrence_rule(RecurrenceRule::every_other_week(start(), Weekday::Wednesday));
+    let created = create_scheduled_event(&api.client(), "token", "5002", &event, Some("community night")).await.unwrap();
+    assert_eq!(created.entity_type, EntityType::External);
+
+    let requests = api.requests("/guilds/5002/scheduled-events");
+    assert_eq!(requests[0].method, "POST");
+    assert_eq!(requests[0].headers["x-audit-log-reason"], "community%20night");
+    let body = &requests[0].body;
+    assert_eq!(body["privacy_level"], 2);
+    assert_eq!(body["entity_type"], 3);
+    assert_eq!(body["channel_id"], Value::Null);
+    assert_eq!(body["entity_metadata"], json!({ "location": "Town hall" }));
+    assert_eq!(body["scheduled_start_time"], "2025-01-01T09:00:00.000+00:00");
+    assert_eq!(body["scheduled_end_time"], "2025-01-01T11:00:00.000+00:00");
+    assert_eq!((&body["recurrence_rule"]["frequency"], &body["recurrence_rule"]["interval"]), (&json!(2), &json!(2)));
+    assert_eq!(body["recurrence_rule"]["by_weekday"], json!([2]));
+}
+
+#[tokio::test]
+async fn moves_events_into_channels() {
+    let api = mock_api();
+    api.route("/guilds/5003/scheduled-events/5000", |_| {
+        let mut event = external_event("5003", 1);
+        event["entity_type"] = json!(1);
+        event["channel_id"] = json!("5004");
+        event["entity_metadata"] = Value::Null;
+        (200, event)
+    });
+    let client = api.client();
+
+    let changes = ScheduledEventBuilder::edit().entity(EventEntity::StageInstance { channel_id: "5004".to_string() });
+    let event = modify_scheduled_event(&client, "token", "5003", "5000", &changes, None).await.unwrap();
+    assert_eq!(event.entity(), Some(EventEntity::StageInstance { channel_id: "5004".to_string() }));
+    get_scheduled_event(&client, "token", "5003", "5000", false).await.unwrap();
+
+    let requests = api.requests("/guilds/5003/scheduled-events/5000");
+    assert_eq!(requests[0].body, json!({ "entity_type": 1, "channel_id": "5004", "entity_metadata": null }));
+    assert_eq!(requests[1].path, "/guilds/5003/scheduled-events/5000?with_user_count=false");
+}
+
+#[tokio::test]
+async fn rejects_invalid_events_without_a_request() {
+    let api = mock_api();
+    let client = api.client();
+    let voice = || EventEntity::Voice { channel_id: "1".to_string() };
+
+    let invalid = [
+        // External events need an end time and a location.
+        ScheduledEventBuilder::new("Meetup", start(), EventEntity::External { location: "Town hall".to_string() }),
+        ScheduledEventBuilder::new("Meetup", start(), EventEntity::External { location: String::new() }).end_time(start() + Duration::from_secs(60)),
+        ScheduledEventBuilder::new("", start(), voice()),
+        ScheduledEventBuilder::new(&"x".repeat(101), start(), voice()),
+        ScheduledEventBuilder::new("Meetup", start(), voice()).end_time(start()),
+        ScheduledEventBuilder::new("Meetup", start(), voice()).image(b"not an image"),
+        ScheduledEventBuilder::new("Meetup", start(), voice()).recurrence_rule(RecurrenceRule::monthly(start(), 6, Weekday::Monday)),
+    ];
+    for event in &invalid {
+        assert!(create_scheduled_event(&client, "token", "5005", event, None).await.is_err(), "{:?}", event);
+    }
+
+    // Moving an event outside Discord needs an end time too.
+    let moved = ScheduledEventBuilder::edit().entity(EventEntity::External { location: "Town hall".to_string() });
+    assert!(modify_scheduled_event(&client, "token", "5005", "5000", &moved, None).await.is_err());
+    assert!(api.requests("/guilds/5005/").is_empty());
+}
+
+#[tokio::test]
+async fn updates_status_only_along_valid_transitions() {
+    let api = mock_api();
+    api.route("/guilds/5006/scheduled-events/5000", |request| (200, external_event("5006", request.body["status"].as_u64().unwrap() as u8)));
+    let client = api.client();
+
+    let scheduled: ScheduledEvent = serde_json::from_value(external_event("5006", 1)).unwrap();
+    let active = update_scheduled_event_status(&client, "token", &scheduled, ScheduledEventStatus::Active, Some("starting")).await.unwrap();
+    assert_eq!(active.status, ScheduledEventStatus::Active);
+    assert!(update_scheduled_event_status(&client, "token", &active, ScheduledEventStatus::Canceled, None).await.is_err());
+    assert!(update_scheduled_event_status(&client, "token", &active, ScheduledEventStatus::Scheduled, None).await.is_err());
+    let completed = update_scheduled_event_status(&client, "token", &active, ScheduledEventStatus::Completed, None).await.unwrap();
+    assert!(update_scheduled_event_status(&client, "token", &completed, ScheduledEventStatus::Active, None).await.is_err());
+
+    let requests = api.requests("/guilds/5006/scheduled-events/5000");
+    let statuses: Vec<&Value> = requests.iter().map(|request| &request.body["status"]).collect();
+    assert_eq!(statuses, [&json!(2), &json!(3)]);
+    assert_eq!(requests[0].headers["x-audit-log-reason"], "starting");
+}
+
+#[test]
+fn validates_recurrence_rules() {
+    use Weekday::*;
+
+    for valid in [
+        RecurrenceRule::daily(start()),
+        RecurrenceRule::daily_on(start(), &[Friday, Thursday, Wednesday, Tuesday, Monday]),
+        RecurrenceRule::daily_on(start(), &[Sunday, Monday]),
+        RecurrenceRule::weekly(start(), Friday),
+        RecurrenceRule::every_other_week(start(), Friday),
+        RecurrenceRule::monthly(start(), 5, Sunday),
+        RecurrenceRule::yearly(start(), Month::February, 29),
+    ] {
+        assert!(valid.validate().is_ok(), "{:?}", valid);
+    }
+
+    let mut three_weeks = RecurrenceRule::weekly(start(), Friday);
+    three_weeks.interval = 3;
+    let mut every_other_day = RecurrenceRule::daily(start());
+    every_other_day.interval = 2;
+    let mut two_days = RecurrenceRule::weekly(start(), Friday);
+    two_days.by_weekday = Some(vec![Monday, Friday]);
+    let mut counted = RecurrenceRule::daily(start());
+    counted.count = Some(3);
+    let mut mixed = RecurrenceRule::monthly(start(), 1, Monday);
+    mixed.by_weekday = Some(vec![Monday]);
+    let mut bad_start = RecurrenceRule::daily(start());
+    bad_start.start = "tomorrow".to_string();
+    for invalid in [
+        RecurrenceRule::daily_on(start(), &[Monday, Wednesday]),
+        RecurrenceRule::monthly(start(), 0, Monday),
+        RecurrenceRule::yearly(start(), Month::March, 32),
+        three_weeks,
+        every_other_day,
+        two_days,
+        counted,
+        mixed,
+        bad_start,
+    ] {
+        assert!(invalid.validate().is_err(), "{:?}", invalid);
+    }
+}
+
+#[test]
+fn parses_typed_scheduled_event_events() {
+    let mut data = external_event("5007", 2);
+    data["entity_type"] = json!(2);
+    data["channel_id"] = json!("5008");
+    data["entity_metadata"] = Value::Null;
+    data["recurrence_rule"] = Value::Null;
+    data.as_object_mut().unwrap().remove("user_count");
+
+    for (name, status) in [("GUILD_SCHEDULED_EVENT_CREATE", 1), ("GUILD_SCHEDULED_EVENT_UPDATE", 2), ("GUILD_SCHEDULED_EVENT_DELETE", 4)] {
+        data["status"] = json!(status);
+        let event = match Event::from_dispatch(name, data.clone()).unwrap() {
+            Event::GuildScheduledEventCreate(event) | Event::GuildScheduledEventUpdate(event) | Event::GuildScheduledEventDelete(event) => event,
+            other => panic!("unexpected {:?}", other),
+        };
+        assert_eq!(event.entity(), Some(EventEntity::Voice { channel_id: "5008".to_string() }));
+        assert_eq!(event.status as u8, status);
+        assert_eq!(event.user_count, None);
+    }
+}
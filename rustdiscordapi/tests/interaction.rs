@@ -0,0 +1,54 @@
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use Rust_Discord_API::interaction::ModalSubmitData;
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Report {
+    reason: String,
+    details: Option<String>,
+    user: String,
+}
+
+fn submitted(components: Value) -> ModalSubmitData {
+    serde_json::from_value(json!({ "custom_id": "report:1234", "components": components })).unwrap()
+}
+
+#[test]
+fn extracts_text_inputs_from_nested_rows() {
+    let data = submitted(json!([
+        { "type": 1, "components": [{ "type": 4, "custom_id": "reason", "value": "spam" }] },
+        { "type": 1, "components": [
+            { "type": 1, "components": [{ "type": 4, "custom_id": "details", "value": "links in every channel" }] },
+        ] },
+        { "type": 18, "component": { "type": 4, "custom_id": "user", "value": "raider" } },
+    ]));
+
+    assert_eq!(data.values().len(), 3);
+    assert_eq!(data.value("details").as_deref(), Some("links in every channel"));
+    assert_eq!(data.value("user").as_deref(), Some("raider"));
+    assert_eq!(
+        data.parse::<Report>().unwrap(),
+        Report { reason: "spam".to_string(), details: Some("links in every channel".to_string()), user: "raider".to_string() }
+    );
+}
+
+#[test]
+fn treats_empty_inputs_as_missing() {
+    let data = submitted(json!([
+        { "type": 1, "components": [{ "type": 4, "custom_id": "reason", "value": "spam" }] },
+        { "type": 1, "components": [{ "type": 4, "custom_id": "details", "value": "" }] },
+    ]));
+
+    assert_eq!(data.value("details").as_deref(), Some(""));
+    assert_eq!(data.value("user"), None);
+    // A required field that wasn't submitted fails to parse, naming the field.
+    let error = data.parse::<Report>().unwrap_err();
+    assert!(error.to_string().contains("missing field `user`"), "{}", error);
+
+    #[derive(Deserialize)]
+    struct Optional {
+        details: Option<String>,
+    }
+    assert_eq!(data.parse::<Optional>().unwrap().details, None);
+}
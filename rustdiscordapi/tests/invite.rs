@@ -0,0 +1,138 @@
+mod common;
+
+use serde_json::{json, Value};
+use std::time::{Duration, UNIX_EPOCH};
+
+use common::mock_api;
+use Rust_Discord_API::event::Event;
+use Rust_Discord_API::invite::{delete_invite, get_invite, parse_invite_code, Invite, InviteTargetType, InviteType};
+
+fn stream_invite() -> Value {
+    json!({
+        "type": 0,
+        "code": "0vCdhLbwjZZTWZLD",
+        "guild": { "id": "165176875973476352", "name": "CS:GO Fraggers Only", "icon": null, "features": ["NEWS"] },
+        "channel": { "id": "165176875973476352", "name": "illuminati", "type": 2 },
+        "inviter": { "id": "115590097100865541", "username": "speed", "discriminator": "7690", "avatar": null },
+        "target_type": 1,
+        "target_user": { "id": "80351110224678912", "username": "Nelly" },
+        "approximate_member_count": 120,
+        "approximate_presence_count": 33,
+        "expires_at": "2021-12-15T01:27:32.590000+00:00",
+    })
+}
+
+#[tokio::test]
+async fn resolves_invites_from_links_with_counts() {
+    let api = mock_api();
+    api.route("/invites/0vCdhLbwjZZTWZLD", |_| (200, stream_invite()));
+
+    let invite = get_invite(&api.client(), "token", "https://discord.gg/0vCdhLbwjZZTWZLD", true, Some("99")).await.unwrap();
+    assert_eq!(invite.kind, Some(InviteType::Guild));
+    assert_eq!(invite.target_type, Some(InviteTargetType::Stream));
+    assert_eq!(invite.target_user.as_ref().unwrap().username, "Nelly");
+    assert_eq!((invite.approximate_member_count, invite.approximate_presence_count), (Some(120), Some(33)));
+    assert_eq!(invite.guild_id(), Some("165176875973476352"));
+    assert_eq!(invite.channel.unwrap().kind, 2);
+
+    let requests = api.requests("/invites/0vCdhLbwjZZTWZLD");
+    assert_eq!(requests[0].method, "GET");
+    assert_eq!(requests[0].path, "/invites/0vCdhLbwjZZTWZLD?with_counts=true&with_expiration=true&guild_scheduled_event_id=99");
+}
+
+#[tokio::test]
+async fn deletes_invites_with_a_reason() {
+    let api = mock_api();
+    api.route("/invites/revoked", |_| (200, json!({ "code": "revoked", "type": 0, "channel": { "id": "1", "type": 0 }, "uses": 4, "max_uses": 0 })));
+
+    let invite = delete_invite(&api.client(), "token", "discord.com/invite/revoked/", Some("leaked")).await.unwrap();
+    assert_eq!(invite.uses, Some(4));
+
+    let requests = api.requests("/invites/revoked");
+    assert_eq!((requests[0].method.as_str(), requests[0].path.as_str()), ("DELETE", "/invites/revoked"));
+    assert_eq!(requests[0].headers["x-audit-log-reason"], "leaked");
+}
+
+#[tokio::test]
+async fn rejects_malformed_codes_without_a_request() {
+    let api = mock_api();
+    let client = api.client();
+    assert!(get_invite(&client, "token", "../users/@me", false, None).await.is_err());
+    assert!(delete_invite(&client, "token", "https://evil.example/invite/abc", None).await.is_err());
+    assert!(api.requests("/invites/abc").is_empty());
+    assert!(api.requests("/users/").is_empty());
+}
+
+#[tokio::test]
+async fn surfaces_unknown_invites() {
+    let api = mock_api();
+    api.route("/invites/gone", |_| (404, json!({ "message": "Unknown Invite", "code": 10006 })));
+    assert!(get_invite(&api.client(), "token", "gone", false, None).await.is_err());
+}
+
+#[test]
+fn parses_invite_codes() {
+    for link in [
+        "abc-123",
+        "discord.gg/abc-123",
+        "https://discord.gg/abc-123",
+        "http://discord.gg/abc-123/",
+        "https://www.discord.gg/abc-123",
+        "https://discord.com/invite/abc-123",
+        "https://discordapp.com/invite/abc-123?event=1",
+        "https://canary.discord.com/invite/abc-123#top",
+        "  discord.com/invite/abc-123  ",
+    ] {
+        assert_eq!(parse_invite_code(link), Some("abc-123"), "{}", link);
+    }
+
+    for invalid in [
+        "",
+        "discord.gg/",
+        "https://discord.com/abc",
+        "https://discord.com/invite/",
+        "https://discord.gg/a/b",
+        "https://example.com/invite/abc",
+        "discord.gg.evil.example/abc",
+        "abc def",
+        "abc?x",
+    ] {
+        assert_eq!(parse_invite_code(invalid), None, "{}", invalid);
+    }
+}
+
+#[test]
+fn computes_invite_expiry() {
+    let invite: Invite = serde_json::from_value(stream_invite()).unwrap();
+    let expires = UNIX_EPOCH + Duration::from_millis(1_639_531_652_590);
+    assert_eq!(invite.expires(), Some(expires));
+    assert!(!invite.is_expired_at(expires - Duration::from_secs(1)));
+    assert!(invite.is_expired_at(expires));
+    assert_eq!(invite.url(), "https://discord.gg/0vCdhLbwjZZTWZLD");
+
+    let permanent: Invite = serde_json::from_value(json!({ "code": "forever", "expires_at": null })).unwrap();
+    assert!(!permanent.is_expired_at(UNIX_EPOCH + Duration::from_secs(u32::MAX as u64)));
+}
+
+#[test]
+fn parses_typed_invite_create_events() {
+    let data = json!({
+        "channel_id": "30",
+        "guild_id": "10",
+        "code": "abc",
+        "created_at": "2024-01-01T00:00:00+00:00",
+        "inviter": { "id": "1", "username": "someone" },
+        "max_age": 86400,
+        "max_uses": 10,
+        "target_type": 2,
+        "target_application": { "id": "880218394199220334", "name": "Watch Together" },
+        "temporary": false,
+        "uses": 0,
+    });
+    let Event::InviteCreate(invite) = Event::from_dispatch("INVITE_CREATE", data).unwrap() else {
+        panic!("expected an invite");
+    };
+    assert_eq!(invite.guild_id(), Some("10"));
+    assert_eq!(invite.target_type, Some(InviteTargetType::EmbeddedApplication));
+    assert_eq!((invite.uses, invite.max_uses, invite.max_age, invite.temporary), (Some(0), Some(10), Some(86400), Some(false)));
+}
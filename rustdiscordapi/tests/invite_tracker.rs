@@ -0,0 +1,306 @@
+mod common;
+
+use futures::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio::time::timeout;
+use tokio_tungstenite::accept_async;
+use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+use tokio_tungstenite::tungstenite::protocol::CloseFrame;
+use tokio_tungstenite::tungstenite::Message as WsMessage;
+
+use common::mock_api;
+use Rust_Discord_API::event::Event;
+use Rust_Discord_API::invite::VanityUrl;
+use Rust_Discord_API::invite_tracker::{attribute_join, InviteSnapshot, InviteTracker, JoinSource, TrackedInvite};
+use Rust_Discord_API::{GatewayClient, Intents};
+
+fn invite(code: &str, uses: u64, max_uses: u64) -> TrackedInvite {
+    TrackedInvite { code: code.to_string(), uses, max_uses, inviter: None, channel_id: None }
+}
+
+fn snapshot(invites: &[TrackedInvite], vanity: Option<(&str, u64)>) -> InviteSnapshot {
+    InviteSnapshot {
+        invites: invites.iter().map(|invite| (invite.code.clone(), invite.clone())).collect(),
+        vanity: vanity.map(|(code, uses)| (code.to_string(), uses)),
+    }
+}
+
+#[test]
+fn attributes_the_invite_whose_count_went_up() {
+    let before = snapshot(&[invite("a", 3, 0), invite("b", 0, 10)], None);
+    let after = snapshot(&[invite("a", 3, 0), invite("b", 1, 10)], None);
+    assert_eq!(attribute_join(&before, &after), JoinSource::Invite(invite("b", 1, 10)));
+}
+
+#[test]
+fn attributes_invites_created_since_the_snapshot() {
+    let before = snapshot(&[invite("a", 3, 0)], None);
+    let after = snapshot(&[invite("a", 3, 0), invite("new", 1, 0)], None);
+    assert_eq!(attribute_join(&before, &after), JoinSource::Invite(invite("new", 1, 0)));
+
+    // Created but not used yet.
+    let after = snapshot(&[invite("a", 3, 0), invite("new", 0, 0)], None);
+    assert_eq!(attribute_join(&before, &after), JoinSource::Unknown);
+}
+
+#[test]
+fn attributes_invites_deleted_on_reaching_their_maximum() {
+    let before = snapshot(&[invite("a", 3, 0), invite("single", 0, 1), invite("manual", 2, 0)], None);
+    // "single" was used up and deleted; "manual" was deleted by a moderator.
+    let after = snapshot(&[invite("a", 3, 0)], None);
+    assert_eq!(attribute_join(&before, &after), JoinSource::Invite(invite("single", 0, 1)));
+
+    // A counted use wins over an exhausted invite that may have been deleted by hand.
+    let after = snapshot(&[invite("a", 4, 0)], None);
+    assert_eq!(attribute_join(&before, &after), JoinSource::Invite(invite("a", 4, 0)));
+}
+
+#[test]
+fn attributes_the_vanity_invite() {
+    let before = snapshot(&[invite("a", 3, 0)], Some(("rust", 41)));
+    let after = snapshot(&[invite("a", 3, 0)], Some(("rust", 42)));
+    assert_eq!(attribute_join(&before, &after), JoinSource::Vanity("rust".to_string()));
+
+    // Without an earlier vanity count there is nothing to compare.
+    let before = snapshot(&[invite("a", 3, 0)], None);
+    assert_eq!(attribute_join(&before, &after), JoinSource::Unknown);
+}
+
+#[test]
+fn reports_ambiguous_and_unknown_joins() {
+    let before = snapshot(&[invite("a", 3, 0), invite("b", 0, 0)], Some(("rust", 41)));
+    let after = snapshot(&[invite("a", 4, 0), invite("b", 1, 0)], Some(("rust", 41)));
+    assert_eq!(attribute_join(&before, &after), JoinSource::Ambiguous { invites: vec![invite("a", 4, 0), invite("b", 1, 0)], vanity: None });
+
+    let after = snapshot(&[invite("a", 4, 0), invite("b", 0, 0)], Some(("rust", 42)));
+    assert_eq!(attribute_join(&before, &after), JoinSource::Ambiguous { invites: vec![invite("a", 4, 0)], vanity: Some("rust".to_string()) });
+
+    assert_eq!(attribute_join(&before, &before), JoinSource::Unknown);
+    assert_eq!(attribute_join(&InviteSnapshot::default(), &InviteSnapshot::default()), JoinSource::Unknown);
+}
+
+#[test]
+fn builds_snapshots_from_invites() {
+    let invites = serde_json::from_value::<Vec<_>>(json!([
+        { "code": "a", "uses": 2, "max_uses": 5, "channel": { "id": "30", "type": 0 }, "inviter": { "id": "1", "username": "someone" } },
+        { "code": "b" },
+    ]))
+    .unwrap();
+    let snapshot = InviteSnapshot::new(&invites, Some(&VanityUrl { code: None, uses: 0 }));
+    assert_eq!(snapshot.invites["a"].uses, 2);
+    assert_eq!(snapshot.invites["a"].channel_id.as_deref(), Some("30"));
+    assert_eq!(snapshot.invites["b"], invite("b", 0, 0));
+    assert_eq!(snapshot.vanity, None);
+}
+
+fn member_add(guild_id: &str, user_id: &str) -> Event {
+    Event::from_dispatch("GUILD_MEMBER_ADD", json!({ "guild_id": guild_id, "user": { "id": user_id, "username": "newcomer" }, "roles": [] })).unwrap()
+}
+
+#[tokio::test]
+async fn tracks_joins_from_gateway_events() {
+    let api = mock_api();
+    // Invite "a" gains a use on every fetch after the first; the vanity invite never does.
+    let fetches = AtomicU64::new(0);
+    api.route("/guilds/4801/invites", move |_| {
+        let uses = fetches.fetch_add(1, Ordering::SeqCst);
+        (200, json!([{ "code": "a", "uses": uses, "max_uses": 0, "inviter": { "id": "1", "username": "recruiter" } }]))
+    });
+    api.route("/guilds/4801/vanity-url", |_| (200, json!({ "code": "rust", "uses": 7 })));
+
+    let client = api.client();
+    let tracker = InviteTracker::new();
+    let mut joins = tracker.subscribe();
+    let guild = Event::from_dispatch("GUILD_CREATE", json!({ "id": "4801", "name": "Guild" })).unwrap();
+    assert_eq!(tracker.update(&client, "token", &guild).await.unwrap(), None);
+    assert_eq!(tracker.snapshot("4801").unwrap().vanity, Some(("rust".to_string(), 7)));
+
+    let join = tracker.update(&client, "token", &member_add("4801", "20")).await.unwrap().unwrap();
+    let JoinSource::Invite(used) = &join.source else { panic!("unexpected {:?}", join.source) };
+    assert_eq!((used.code.as_str(), used.uses), ("a", 1));
+    assert_eq!(used.inviter.as_ref().unwrap().username, "recruiter");
+    assert_eq!(join.member.user.as_ref().unwrap().id, "20");
+    assert_eq!(joins.recv().await.unwrap(), join);
+
+    // Invite events keep the snapshot current between joins.
+    let created = json!({ "guild_id": "4801", "channel_id": "30", "code": "fresh", "uses": 0, "max_uses": 1 });
+    tracker.update(&client, "token", &Event::from_dispatch("INVITE_CREATE", created).unwrap()).await.unwrap();
+    assert_eq!(tracker.snapshot("4801").unwrap().invites["fresh"].max_uses, 1);
+    // Exhausted invites are kept so the join they caused can still be attributed.
+    tracker.update(&client, "token", &Event::from_dispatch("INVITE_DELETE", json!({ "guild_id": "4801", "channel_id": "30", "code": "fresh" })).unwrap()).await.unwrap();
+    assert!(tracker.snapshot("4801").unwrap().invites.contains_key("fresh"));
+
+    tracker.update(&client, "token", &Event::from_dispatch("GUILD_DELETE", json!({ "id": "4801" })).unwrap()).await.unwrap();
+    assert_eq!(tracker.snapshot("4801"), None);
+}
+
+#[tokio::test]
+async fn joins_in_untracked_guilds_start_tracking() {
+    let api = mock_api();
+    api.route("/guilds/4802/invites", |_| (200, json!([{ "code": "a", "uses": 5 }])));
+    api.route("/guilds/4802/vanity-url", |_| (403, json!({ "message": "Missing Access", "code": 50001 })));
+    api.route("/guilds/4802", |_| (200, json!({ "id": "4802", "name": "Guild", "features": ["COMMUNITY"] })));
+
+    let tracker = InviteTracker::new();
+    let join = tracker.update(&api.client(), "token", &member_add("4802", "21")).await.unwrap().unwrap();
+    assert_eq!(join.source, JoinSource::Unknown);
+    // The guild has no vanity invite, so later joins only fetch invites.
+    assert_eq!(tracker.snapshot("4802").unwrap().vanity, None);
+    tracker.update(&api.client(), "token", &member_add("4802", "22")).await.unwrap();
+    assert_eq!(api.requests("/guilds/4802/vanity-url").len(), 1);
+    assert_eq!(api.requests("/guilds/4802/invites").len(), 2);
+}
+
+#[tokio::test]
+async fn surfaces_missing_permissions() {
+    let api = mock_api();
+    api.route("/guilds/4803/", |_| (403, json!({ "message": "Missing Permissions", "code": 50013 })));
+
+    let tracker = InviteTracker::new();
+    let mut joins = tracker.subscribe();
+    assert!(tracker.update(&api.client(), "token", &member_add("4803", "23")).await.is_err());
+    assert!(joins.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn surfaces_missing_access_to_a_vanity_invite() {
+    let api = mock_api();
+    api.route("/guilds/4809/invites", |_| (200, json!([])));
+    api.route("/guilds/4809/vanity-url", |_| (403, json!({ "message": "Missing Access", "code": 50001 })));
+    // The guild has a vanity invite, so the bot is just not allowed to see it.
+    api.route("/guilds/4809", |_| (200, json!({ "id": "4809", "name": "Guild", "features": ["VANITY_URL"] })));
+
+    let tracker = InviteTracker::new();
+    assert!(tracker.update(&api.client(), "token", &member_add("4809", "28")).await.is_err());
+    assert_eq!(tracker.snapshot("4809"), None);
+}
+
+#[tokio::test]
+async fn retries_the_vanity_invite_after_transient_errors() {
+    let api = mock_api();
+    api.route("/guilds/4804/invites", |_| (200, json!([])));
+    // The first vanity request is rate limited.
+    let vanity_fetches = AtomicU64::new(0);
+    api.route("/guilds/4804/vanity-url", move |_| match vanity_fetches.fetch_add(1, Ordering::SeqCst) {
+        0 => (429, json!({ "message": "You are being rate limited.", "retry_after": 0.1, "global": false })),
+        uses => (200, json!({ "code": "rust", "uses": uses })),
+    });
+
+    let tracker = InviteTracker::new();
+    let client = api.client();
+    assert!(tracker.update(&client, "token", &member_add("4804", "24")).await.is_err());
+    assert_eq!(tracker.snapshot("4804"), None);
+
+    tracker.update(&client, "token", &member_add("4804", "25")).await.unwrap();
+    let join = tracker.update(&client, "token", &member_add("4804", "26")).await.unwrap().unwrap();
+    assert_eq!(join.source, JoinSource::Vanity("rust".to_string()));
+}
+
+#[tokio::test]
+async fn refetches_the_vanity_invite_when_its_code_changes() {
+    let api = mock_api();
+    api.route("/guilds/4805/invites", |_| (200, json!([])));
+    let vanity_fetches = AtomicU64::new(0);
+    api.route("/guilds/4805/vanity-url", move |_| match vanity_fetches.fetch_add(1, Ordering::SeqCst) {
+        0 => (200, json!({ "code": null, "uses": 0 })),
+        _ => (200, json!({ "code": "rust", "uses": 3 })),
+    });
+
+    let tracker = InviteTracker::new();
+    let client = api.client();
+    let guild = |vanity: Value| json!({ "id": "4805", "name": "Guild", "vanity_url_code": vanity });
+    tracker.update(&client, "token", &Event::from_dispatch("GUILD_CREATE", guild(Value::Null)).unwrap()).await.unwrap();
+    assert_eq!(tracker.snapshot("4805").unwrap().vanity, None);
+
+    // Unrelated guild updates do not refetch the vanity invite.
+    tracker.update(&client, "token", &Event::from_dispatch("GUILD_UPDATE", guild(Value::Null)).unwrap()).await.unwrap();
+    assert_eq!(api.requests("/guilds/4805/vanity-url").len(), 1);
+
+    tracker.update(&client, "token", &Event::from_dispatch("GUILD_UPDATE", guild(json!("rust"))).unwrap()).await.unwrap();
+    assert_eq!(tracker.snapshot("4805").unwrap().vanity, Some(("rust".to_string(), 3)));
+    assert_eq!(api.requests("/guilds/4805/invites").len(), 1);
+}
+
+#[tokio::test]
+async fn applies_gateway_events_in_order() {
+    let api = mock_api();
+    // The snapshot taken at GUILD_CREATE is slow; the join's fetch is not.
+    let fetches = AtomicU64::new(0);
+    api.route("/guilds/4806/invites", move |_| {
+        let uses = fetches.fetch_add(1, Ordering::SeqCst);
+        if uses == 0 {
+            std::thread::sleep(Duration::from_millis(300));
+        }
+        (200, json!([{ "code": "a", "uses": uses }]))
+    });
+    api.route("/guilds/4806/vanity-url", |_| (200, json!({ "code": null, "uses": 0 })));
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    let server = tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut socket = accept_async(stream).await.unwrap();
+        let send = |payload: Value| WsMessage::text(payload.to_string());
+        socket.send(send(json!({ "op": 10, "d": { "heartbeat_interval": 45_000 } }))).await.unwrap();
+        socket.next().await.unwrap().unwrap();
+        let ready = json!({ "v": 10, "user": { "id": "1", "username": "bot" }, "guilds": [], "session_id": "session", "resume_gateway_url": "ws://unused" });
+        socket.send(send(json!({ "op": 0, "s": 1, "t": "READY", "d": ready }))).await.unwrap();
+        socket.send(send(json!({ "op": 0, "s": 2, "t": "GUILD_CREATE", "d": { "id": "4806", "name": "Guild" } }))).await.unwrap();
+        let added = json!({ "guild_id": "4806", "user": { "id": "27", "username": "newcomer" }, "roles": [] });
+        socket.send(send(json!({ "op": 0, "s": 3, "t": "GUILD_MEMBER_ADD", "d": added }))).await.unwrap();
+        socket.send(send(json!({ "op": 0, "s": 4, "t": "GUILD_DELETE", "d": { "id": "4806" } }))).await.unwrap();
+        let frame = CloseFrame { code: CloseCode::from(4004), reason: "test".into() };
+        let _ = socket.close(Some(frame)).await;
+    });
+
+    let tracker = Arc::new(InviteTracker::new());
+    let mut joins = tracker.subscribe();
+    let gateway = GatewayClient::new(api.client(), "token", Intents::GUILDS | Intents::GUILD_MEMBERS | Intents::GUILD_INVITES)
+        .url(&url)
+        .invite_tracker(tracker.clone());
+    timeout(Duration::from_secs(5), gateway.run()).await.expect("gateway did not stop").unwrap_err();
+    server.await.unwrap();
+
+    let join = timeout(Duration::from_secs(5), joins.recv()).await.expect("join was not attributed").unwrap();
+    let JoinSource::Invite(used) = &join.source else { panic!("unexpected {:?}", join.source) };
+    assert_eq!((used.code.as_str(), used.uses), ("a", 1));
+    // Applied after the join, so the guild is no longer tracked.
+    timeout(Duration::from_secs(5), async {
+        while tracker.snapshot("4806").is_some() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("GUILD_DELETE was applied out of order");
+}
+
+#[tokio::test]
+async fn slow_fetches_do_not_hold_up_other_guilds() {
+    let api = mock_api();
+    api.route("/guilds/4807/invites", |_| {
+        std::thread::sleep(Duration::from_secs(2));
+        (200, json!([]))
+    });
+    api.route("/guilds/4808/invites", |_| (200, json!([{ "code": "b", "uses": 1 }])));
+    api.route("/guilds/", |_| (200, json!({ "code": null, "uses": 0 })));
+
+    let tracker = Arc::new(InviteTracker::new());
+    let client = api.client();
+    let slow = {
+        let (tracker, client) = (tracker.clone(), client.clone());
+        let guild = Event::from_dispatch("GUILD_CREATE", json!({ "id": "4807", "name": "Slow" })).unwrap();
+        tokio::spawn(async move { tracker.update(&client, "token", &guild).await.map(|_| ()).map_err(|error| error.to_string()) })
+    };
+    while api.requests("/guilds/4807/invites").is_empty() {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    let join = timeout(Duration::from_secs(1), tracker.update(&client, "token", &member_add("4808", "27"))).await.expect("join waited for another guild");
+    assert_eq!(join.unwrap().unwrap().source, JoinSource::Unknown);
+    assert!(!slow.is_finished());
+    slow.await.unwrap().unwrap();
+}
@@ -0,0 +1,119 @@
+mod common;
+
+use futures::StreamExt;
+use serde_json::{json, Value};
+
+use common::{mock_api, Request};
+use Rust_Discord_API::channel::stream_channel_messages;
+use Rust_Discord_API::guild::stream_guild_members;
+use Rust_Discord_API::pagination::{Direction, PaginationOptions};
+
+/// Returns the value of a query parameter of a request.
+fn param<'a>(request: &'a Request, name: &str) -> Option<&'a str> {
+    let (_, query) = request.path.split_once('?')?;
+    query.split('&').find_map(|pair| pair.strip_prefix(name)?.strip_prefix('='))
+}
+
+/// Answers like the messages endpoint of a channel holding messages 1 to 7,
+/// newest first.
+fn messages(request: &Request) -> (u16, Value) {
+    let limit: u64 = param(request, "limit").unwrap().parse().unwrap();
+    let mut ids: Vec<u64> = match (param(request, "after"), param(request, "before")) {
+        (Some(after), _) => (after.parse::<u64>().unwrap() + 1..=7).take(limit as usize).collect(),
+        (_, Some(before)) => (1..=7u64.min(before.parse::<u64>().unwrap() - 1)).rev().take(limit as usize).collect(),
+        _ => panic!("no cursor in {}", request.path),
+    };
+    ids.reverse();
+    (200, json!(ids.iter().map(|id| json!({ "id": id.to_string() })).collect::<Vec<_>>()))
+}
+
+async fn ids(options: PaginationOptions, api: &common::MockApi) -> Vec<String> {
+    stream_channel_messages(&api.client(), "token", "3001", options)
+        .map(|message| message.unwrap()["id"].as_str().unwrap().to_string())
+        .collect()
+        .await
+}
+
+#[tokio::test]
+async fn walks_forward_from_the_oldest_item() {
+    let api = mock_api();
+    api.route("/channels/3001/messages", messages);
+
+    assert_eq!(ids(PaginationOptions::new().page_size(3), &api).await, ["1", "2", "3", "4", "5", "6", "7"]);
+
+    let cursors: Vec<_> = api.requests("/channels/3001/messages").iter().map(|request| param(request, "after").unwrap().to_string()).collect();
+    // The third page is short, so the list is known to be exhausted.
+    assert_eq!(cursors, ["0", "3", "6"]);
+}
+
+#[tokio::test]
+async fn walks_backward_from_the_newest_item() {
+    let api = mock_api();
+    api.route("/channels/3001/messages", messages);
+
+    let options = PaginationOptions::new().direction(Direction::Before).page_size(3);
+    assert_eq!(ids(options, &api).await, ["7", "6", "5", "4", "3", "2", "1"]);
+
+    let requests = api.requests("/channels/3001/messages");
+    let cursors: Vec<_> = requests.iter().map(|request| param(request, "before").unwrap()).collect();
+    assert_eq!(cursors, ["9223372036854775807", "5", "2"]);
+    assert!(requests.iter().all(|request| param(request, "after").is_none()));
+}
+
+#[tokio::test]
+async fn starts_at_the_given_item() {
+    let api = mock_api();
+    api.route("/channels/3001/messages", messages);
+
+    let options = PaginationOptions::new().direction(Direction::Before).start("4");
+    assert_eq!(ids(options, &api).await, ["3", "2", "1"]);
+    assert_eq!(param(&api.requests("/channels/3001/messages")[0], "before"), Some("4"));
+}
+
+#[tokio::test]
+async fn stops_after_max_items() {
+    let api = mock_api();
+    api.route("/channels/3001/messages", messages);
+
+    assert_eq!(ids(PaginationOptions::new().page_size(3).max_items(4), &api).await, ["1", "2", "3", "4"]);
+
+    // The last page only asks for the items still wanted.
+    let limits: Vec<_> = api.requests("/channels/3001/messages").iter().map(|request| param(request, "limit").unwrap().to_string()).collect();
+    assert_eq!(limits, ["3", "1"]);
+}
+
+#[tokio::test]
+async fn caps_the_page_size_at_huge_max_items() {
+    let api = mock_api();
+    api.route("/channels/3001/messages", messages);
+
+    assert_eq!(ids(PaginationOptions::new().max_items((1 << 32) + 1), &api).await.len(), 7);
+    assert_eq!(param(&api.requests("/channels/3001/messages")[0], "limit"), Some("100"));
+}
+
+#[tokio::test]
+async fn ends_after_a_failed_request() {
+    let api = mock_api();
+    api.route("/channels/3001/messages", |request| match param(request, "after") {
+        Some("0") => messages(request),
+        _ => (500, json!({ "message": "Internal Server Error", "code": 0 })),
+    });
+
+    let results: Vec<_> = stream_channel_messages(&api.client(), "token", "3001", PaginationOptions::new().page_size(2)).collect().await;
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0].as_ref().unwrap()["id"], "1");
+    assert_eq!(results[1].as_ref().unwrap()["id"], "2");
+    assert!(results[2].is_err());
+    assert_eq!(api.requests("/channels/3001/messages").len(), 2);
+}
+
+#[tokio::test]
+async fn rejects_backward_paging_where_unsupported() {
+    let api = mock_api();
+
+    let options = PaginationOptions::new().direction(Direction::Before);
+    let results: Vec<_> = stream_guild_members(&api.client(), "token", "3002", options).collect().await;
+    assert_eq!(results.len(), 1);
+    assert!(results[0].is_err());
+    assert!(api.requests("/guilds/3002").is_empty());
+}
@@ -0,0 +1,110 @@
+mod common;
+
+use serde_json::{json, Value};
+use std::time::{Duration, Instant};
+use tokio::time::sleep;
+
+use common::mock_api;
+use Rust_Discord_API::event::{Event, EventBus};
+use Rust_Discord_API::paginator::{PageAction, Paginator, PaginatorControls};
+
+#[test]
+fn steps_between_pages() {
+    assert_eq!(PageAction::First.apply(3, 5), 0);
+    assert_eq!(PageAction::Previous.apply(3, 5), 2);
+    assert_eq!(PageAction::Next.apply(3, 5), 4);
+    assert_eq!(PageAction::Last.apply(3, 5), 4);
+}
+
+#[test]
+fn stays_within_the_first_and_last_page() {
+    assert_eq!(PageAction::Previous.apply(0, 5), 0);
+    assert_eq!(PageAction::First.apply(0, 5), 0);
+    assert_eq!(PageAction::Next.apply(4, 5), 4);
+    assert_eq!(PageAction::Last.apply(4, 5), 4);
+}
+
+#[test]
+fn stays_on_a_single_page() {
+    for action in [PageAction::First, PageAction::Previous, PageAction::Next, PageAction::Last] {
+        assert_eq!(action.apply(0, 1), 0, "{:?}", action);
+        // Nothing to page through without pages either.
+        assert_eq!(action.apply(0, 0), 0, "{:?}", action);
+    }
+}
+
+#[test]
+fn round_trips_button_ids_and_emojis() {
+    for action in [PageAction::First, PageAction::Previous, PageAction::Next, PageAction::Last] {
+        assert!(action.custom_id().starts_with("paginator:"));
+        assert_eq!(PageAction::from_custom_id(action.custom_id()), Some(action));
+        assert_eq!(PageAction::from_emoji(action.emoji()), Some(action));
+        // Reactions may arrive without the variation selector.
+        assert_eq!(PageAction::from_emoji(&action.emoji().replace('\u{FE0F}', "")), Some(action));
+    }
+    assert_eq!(PageAction::from_custom_id("paginator:"), None);
+    assert_eq!(PageAction::from_custom_id("ban:confirm"), None);
+    assert_eq!(PageAction::from_emoji("👍"), None);
+}
+
+#[test]
+fn splits_items_into_pages_with_footers() {
+    let items: Vec<u32> = (1..=5).collect();
+    let paginator = Paginator::from_items(&items, 2, |chunk, index| json!({ "title": format!("Page {}", index), "description": format!("{:?}", chunk) }));
+    assert_eq!(paginator.page_count(), 3);
+    assert_eq!(paginator.page(2)["description"], "[5]");
+    assert_eq!(paginator.page(0)["footer"]["text"], "Page 1/3");
+
+    let single = Paginator::new(vec![json!({ "title": "Only" })]);
+    assert!(single.page(0).get("footer").is_none());
+}
+
+fn click(user_id: &str, custom_id: &str) -> Event {
+    Event::from_dispatch("INTERACTION_CREATE", json!({
+        "id": "900",
+        "application_id": "1",
+        "type": 3,
+        "token": "expired-token",
+        "channel_id": "10",
+        "member": { "user": { "id": user_id, "username": "user" } },
+        "message": { "id": "700" },
+        "data": { "custom_id": custom_id, "component_type": 2 }
+    })).unwrap()
+}
+
+#[tokio::test]
+async fn only_the_author_keeps_buttons_alive_and_they_are_always_removed() {
+    let api = mock_api();
+    api.route("/channels/10/messages", |_| (200, json!({ "id": "700", "channel_id": "10" })));
+    // Every click response fails, as it would once the interaction token expired.
+    api.route("/interactions/", |_| (404, json!({ "message": "Unknown interaction", "code": 10062 })));
+    let client = api.client();
+    let bus = EventBus::default();
+
+    let paginator = Paginator::new(vec![json!({ "title": "One" }), json!({ "title": "Two" })])
+        .controls(PaginatorControls::Buttons)
+        .timeout(Duration::from_millis(400));
+    let pages = {
+        let (client, bus) = (client.clone(), bus.clone());
+        tokio::spawn(async move { paginator.send(&client, "token", &bus, "10", "1").await.map_err(|error| error.to_string()) })
+    };
+    while api.requests("/channels/10/messages").is_empty() {
+        sleep(Duration::from_millis(10)).await;
+    }
+
+    bus.publish(click("1", PageAction::Next.custom_id()));
+    let navigated = Instant::now();
+    // Someone else clicking doesn't keep the paginator open.
+    while !pages.is_finished() && navigated.elapsed() < Duration::from_secs(2) {
+        bus.publish(click("2", PageAction::Previous.custom_id()));
+        sleep(Duration::from_millis(50)).await;
+    }
+    assert!(navigated.elapsed() < Duration::from_secs(1), "paginator stayed open for {:?}", navigated.elapsed());
+    pages.await.unwrap().unwrap();
+
+    assert!(api.requests("/interactions/").len() > 1);
+    let edits: Vec<Value> = api.requests("/channels/10/messages/700").into_iter().map(|request| request.body).collect();
+    assert_eq!(edits.len(), 1);
+    assert_eq!(edits[0]["components"], json!([]));
+    assert_eq!(edits[0]["embeds"][0]["title"], "Two");
+}
@@ -0,0 +1,253 @@
+#![cfg(feature = "redis")]
+
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::time::{sleep, timeout};
+
+use Rust_Discord_API::cache::{encode_command, Cache, CacheBackend, CacheConfig, CacheError, RedisBackend};
+use Rust_Discord_API::event::Event;
+
+type Store = Arc<Mutex<HashMap<String, HashMap<String, String>>>>;
+
+/// Starts an in-process server that understands the subset of RESP the
+/// backend uses. Requires the password `secret` for the users `default` and
+/// `bot` when `password` is set.
+/// Replies to commands on the `slow` hash are delayed.
+async fn stand_in(password: bool) -> (String, Store) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    let store: Store = Arc::default();
+
+    let shared = store.clone();
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            tokio::spawn(serve(stream, shared.clone(), password));
+        }
+    });
+    (address, store)
+}
+
+async fn read_command(stream: &mut BufReader<TcpStream>) -> Option<Vec<String>> {
+    let mut line = String::new();
+    stream.read_line(&mut line).await.ok()?;
+    let count: usize = line.trim_end().strip_prefix('*')?.parse().ok()?;
+
+    let mut args = Vec::with_capacity(count);
+    for _ in 0..count {
+        line.clear();
+        stream.read_line(&mut line).await.ok()?;
+        let length: usize = line.trim_end().strip_prefix('$')?.parse().ok()?;
+        let mut data = vec![0; length + 2];
+        stream.read_exact(&mut data).await.ok()?;
+        data.truncate(length);
+        args.push(String::from_utf8(data).ok()?);
+    }
+    Some(args)
+}
+
+fn bulk(value: Option<&String>) -> String {
+    match value {
+        Some(value) => format!("${}\r\n{}\r\n", value.len(), value),
+        None => "$-1\r\n".to_string(),
+    }
+}
+
+fn array<'a>(values: impl Iterator<Item = &'a String>) -> String {
+    let values: Vec<_> = values.collect();
+    let mut reply = format!("*{}\r\n", values.len());
+    for value in values {
+        reply.push_str(&bulk(Some(value)));
+    }
+    reply
+}
+
+async fn serve(stream: TcpStream, store: Store, password: bool) {
+    let mut stream = BufReader::new(stream);
+    let mut authenticated = !password;
+
+    while let Some(args) = read_command(&mut stream).await {
+        let slow = args.get(1).is_some_and(|key| key == "slow");
+        let reply = {
+            let mut store = store.lock().unwrap();
+            let args: Vec<&str> = args.iter().map(String::as_str).collect();
+            match args.as_slice() {
+                ["AUTH", "secret"] | ["AUTH", "default" | "bot", "secret"] => {
+                    authenticated = true;
+                    "+OK\r\n".to_string()
+                }
+                ["AUTH", ..] => "-WRONGPASS invalid password\r\n".to_string(),
+                _ if !authenticated => "-NOAUTH Authentication required.\r\n".to_string(),
+                ["PING"] => "+PONG\r\n".to_string(),
+                ["SELECT", _] => "+OK\r\n".to_string(),
+                ["HSET", key, field, value] => {
+                    let added = store.entry(key.to_string()).or_default().insert(field.to_string(), value.to_string()).is_none();
+                    format!(":{}\r\n", added as i64)
+                }
+                ["HGET", key, field] => bulk(store.get(*key).and_then(|hash| hash.get(*field))),
+                ["HDEL", key, field] => {
+                    let removed = store.get_mut(*key).and_then(|hash| hash.remove(*field)).is_some();
+                    format!(":{}\r\n", removed as i64)
+                }
+                ["HKEYS", key] => array(store.get(*key).into_iter().flat_map(|hash| hash.keys())),
+                ["HVALS", key] => array(store.get(*key).into_iter().flat_map(|hash| hash.values())),
+                ["DEL", key] => format!(":{}\r\n", store.remove(*key).is_some() as i64),
+                _ => "-ERR unknown command\r\n".to_string(),
+            }
+        };
+        if slow {
+            sleep(Duration::from_millis(200)).await;
+        }
+        if stream.get_mut().write_all(reply.as_bytes()).await.is_err() {
+            return;
+        }
+    }
+}
+
+#[test]
+fn encodes_commands_as_bulk_string_arrays() {
+    assert_eq!(encode_command(&["HGET", "guilds", "1"]), b"*3\r\n$4\r\nHGET\r\n$6\r\nguilds\r\n$1\r\n1\r\n".to_vec());
+}
+
+#[tokio::test]
+async fn stores_values_in_prefixed_hashes() {
+    let (address, store) = stand_in(false).await;
+    let backend = RedisBackend::connect(&format!("redis://{}/2", address)).await.unwrap().prefix("bot:");
+
+    backend.insert("guilds", "1", json!({"id": "1"})).await.unwrap();
+    backend.insert("guilds", "2", json!({"id": "2"})).await.unwrap();
+    assert_eq!(backend.get("guilds", "1").await.unwrap(), Some(json!({"id": "1"})));
+    assert_eq!(backend.get("guilds", "3").await.unwrap(), None);
+    assert!(store.lock().unwrap().contains_key("bot:guilds"));
+
+    let mut fields = backend.fields("guilds").await.unwrap();
+    fields.sort();
+    assert_eq!(fields, ["1", "2"]);
+    assert_eq!(backend.values("guilds").await.unwrap().len(), 2);
+
+    backend.remove("guilds", "1").await.unwrap();
+    assert_eq!(backend.fields("guilds").await.unwrap(), ["2"]);
+    backend.clear("guilds").await.unwrap();
+    assert!(backend.values("guilds").await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn authenticates_with_the_url_password() {
+    let (address, _) = stand_in(true).await;
+
+    let error = RedisBackend::connect(&format!("redis://:wrong@{}", address)).await.unwrap_err();
+    assert!(matches!(error, CacheError::Backend(message) if message.starts_with("WRONGPASS")));
+
+    let backend = RedisBackend::connect(&format!("redis://default:secret@{}", address)).await.unwrap();
+    backend.insert("roles:1", "2", json!(true)).await.unwrap();
+    assert_eq!(backend.get("roles:1", "2").await.unwrap(), Some(json!(true)));
+}
+
+#[tokio::test]
+async fn authenticates_as_the_url_user() {
+    let (address, _) = stand_in(true).await;
+
+    let error = RedisBackend::connect(&format!("redis://other:secret@{}", address)).await.unwrap_err();
+    assert!(matches!(error, CacheError::Backend(message) if message.starts_with("WRONGPASS")));
+
+    let backend = RedisBackend::connect(&format!("redis://bot:secret@{}", address)).await.unwrap();
+    backend.insert("roles:1", "2", json!(true)).await.unwrap();
+    assert_eq!(backend.get("roles:1", "2").await.unwrap(), Some(json!(true)));
+
+    // A password alone authenticates as the default user.
+    assert!(RedisBackend::connect(&format!("redis://secret@{}", address)).await.is_ok());
+}
+
+#[tokio::test]
+async fn caches_share_state_through_one_server() {
+    let (address, _) = stand_in(false).await;
+    let writer = Cache::with_backend(CacheConfig::new().message_capacity(2), Arc::new(RedisBackend::connect(&address).await.unwrap()));
+    let reader = Cache::with_backend(CacheConfig::new(), Arc::new(RedisBackend::connect(&address).await.unwrap()));
+
+    let guild = json!({
+        "id": "10",
+        "name": "Guild",
+        "owner_id": "1",
+        "roles": [{"id": "10", "name": "@everyone", "permissions": "1024"}],
+        "channels": [{"id": "20", "type": 0, "name": "general"}],
+        "members": [{"user": {"id": "1", "username": "owner"}, "roles": []}],
+    });
+    writer.update(&Event::from_dispatch("GUILD_CREATE", guild).unwrap()).await.unwrap();
+
+    assert_eq!(reader.guild("10").await.unwrap().unwrap().name, "Guild");
+    assert_eq!(reader.channel("20").await.unwrap().unwrap().guild_id.as_deref(), Some("10"));
+    assert_eq!(reader.guild_roles("10").await.unwrap().len(), 1);
+    assert!(reader.member("10", "1").await.unwrap().is_some());
+
+    for id in ["31", "32", "33"] {
+        let message = json!({"id": id, "channel_id": "20", "author": {"id": "1", "username": "owner"}, "content": id});
+        writer.update(&Event::from_dispatch("MESSAGE_CREATE", message).unwrap()).await.unwrap();
+    }
+    let update = json!({"id": "33", "channel_id": "20", "content": "edited"});
+    writer.update(&Event::from_dispatch("MESSAGE_UPDATE", update).unwrap()).await.unwrap();
+
+    let messages = reader.channel_messages("20").await.unwrap();
+    let contents: Vec<_> = messages.iter().map(|message| message.content.as_str()).collect();
+    assert_eq!(contents, ["32", "edited"]);
+
+    writer.update(&Event::from_dispatch("GUILD_DELETE", json!({"id": "10"})).unwrap()).await.unwrap();
+    assert!(reader.guild("10").await.unwrap().is_none());
+    assert!(reader.channel("20").await.unwrap().is_none());
+    assert!(reader.channel_messages("20").await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn reconnects_after_the_connection_drops() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    let store: Store = Arc::default();
+
+    // The first connection answers one command and then hangs up.
+    let shared = store.clone();
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut stream = BufReader::new(stream);
+        read_command(&mut stream).await.unwrap();
+        stream.get_mut().write_all(b"+PONG\r\n").await.unwrap();
+        drop(stream);
+        while let Ok((stream, _)) = listener.accept().await {
+            tokio::spawn(serve(stream, shared.clone(), false));
+        }
+    });
+
+    let backend = RedisBackend::connect(&address).await.unwrap();
+    assert!(matches!(backend.get("guilds", "1").await, Err(CacheError::Io(_))));
+    backend.insert("guilds", "1", json!(1)).await.unwrap();
+    assert_eq!(backend.get("guilds", "1").await.unwrap(), Some(json!(1)));
+}
+
+#[tokio::test]
+async fn drops_the_connection_of_a_cancelled_command() {
+    let (address, store) = stand_in(false).await;
+    let backend = RedisBackend::connect(&address).await.unwrap();
+    backend.insert("guilds", "1", json!(1)).await.unwrap();
+    store.lock().unwrap().entry("slow".to_string()).or_default().insert("1".to_string(), "\"late\"".to_string());
+
+    assert!(timeout(Duration::from_millis(50), backend.get("slow", "1")).await.is_err());
+    // The late reply must not be read as the answer to the next command.
+    assert_eq!(backend.get("guilds", "1").await.unwrap(), Some(json!(1)));
+}
+
+/// Runs against a real server when `REDIS_URL` is set, e.g. `redis://127.0.0.1:6379/15`.
+#[tokio::test]
+async fn real_server_round_trip() {
+    let Ok(url) = std::env::var("REDIS_URL") else {
+        return;
+    };
+    let prefix = format!("rust-discord-api-test:{}:", std::process::id());
+    let backend = RedisBackend::connect(&url).await.unwrap().prefix(&prefix);
+
+    backend.insert("guilds", "1", json!({"id": "1"})).await.unwrap();
+    assert_eq!(backend.get("guilds", "1").await.unwrap(), Some(json!({"id": "1"})));
+    assert_eq!(backend.fields("guilds").await.unwrap(), ["1"]);
+    backend.clear("guilds").await.unwrap();
+    assert!(backend.values("guilds").await.unwrap().is_empty());
+}
@@ -0,0 +1,115 @@
+use async_trait::async_trait;
+use reqwest::Client;
+use serde_json::{json, Value};
+use std::error::Error;
+use std::sync::{Arc, Mutex};
+
+use Rust_Discord_API::interaction::{Interaction, ModalSubmitData};
+use Rust_Discord_API::{CommandRouter, ComponentHandler, ModalHandler};
+
+type Calls = Arc<Mutex<Vec<String>>>;
+
+/// Records which handler was called for which `custom_id`.
+struct Recorder(&'static str, Calls);
+
+#[async_trait]
+impl ComponentHandler for Recorder {
+    async fn handle(&self, _client: &Client, _token: &str, interaction: &Interaction) -> Result<(), Box<dyn Error>> {
+        let custom_id = interaction.component_data().unwrap().custom_id;
+        self.1.lock().unwrap().push(format!("{} {}", self.0, custom_id));
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ModalHandler for Recorder {
+    async fn handle(&self, _client: &Client, _token: &str, _interaction: &Interaction, data: &ModalSubmitData) -> Result<(), Box<dyn Error>> {
+        let reason = data.value("reason").unwrap_or_default();
+        self.1.lock().unwrap().push(format!("{} {} {}", self.0, data.custom_id, reason));
+        Ok(())
+    }
+}
+
+fn interaction(kind: u8, data: Value) -> Interaction {
+    serde_json::from_value(json!({ "id": "1", "application_id": "2", "type": kind, "data": data, "token": "interaction-token" })).unwrap()
+}
+
+fn click(custom_id: &str) -> Interaction {
+    interaction(3, json!({ "custom_id": custom_id, "component_type": 2 }))
+}
+
+/// Dispatches a click on each `custom_id` and returns the handlers called.
+async fn dispatch(router: &CommandRouter, calls: &Calls, custom_ids: &[&str]) -> Vec<String> {
+    for custom_id in custom_ids {
+        router.dispatch_component(&Client::new(), "token", &click(custom_id)).await.unwrap();
+    }
+    calls.lock().unwrap().drain(..).collect()
+}
+
+#[tokio::test]
+async fn prefers_exact_components_over_prefixes() {
+    let calls = Calls::default();
+    let mut router = CommandRouter::new();
+    router.register_component_prefix("ban:", Arc::new(Recorder("prefix", calls.clone())));
+    router.register_component("ban:cancel", Arc::new(Recorder("exact", calls.clone())));
+
+    let called = dispatch(&router, &calls, &["ban:cancel", "ban:confirm:1234"]).await;
+    assert_eq!(called, ["exact ban:cancel", "prefix ban:confirm:1234"]);
+}
+
+#[tokio::test]
+async fn picks_the_longest_matching_prefix() {
+    let calls = Calls::default();
+    let mut router = CommandRouter::new();
+    router.register_component_prefix("ban:confirm:", Arc::new(Recorder("long", calls.clone())));
+    router.register_component_prefix("ban:", Arc::new(Recorder("short", calls.clone())));
+
+    let called = dispatch(&router, &calls, &["ban:confirm:1234", "ban:undo:1234"]).await;
+    assert_eq!(called, ["long ban:confirm:1234", "short ban:undo:1234"]);
+
+    // Registering a prefix again replaces its handler.
+    router.register_component_prefix("ban:", Arc::new(Recorder("replaced", calls.clone())));
+    assert_eq!(dispatch(&router, &calls, &["ban:undo:1234"]).await, ["replaced ban:undo:1234"]);
+}
+
+#[tokio::test]
+async fn ignores_unmatched_components() {
+    let calls = Calls::default();
+    let mut router = CommandRouter::new();
+    router.register_component("ban:cancel", Arc::new(Recorder("exact", calls.clone())));
+    router.register_component_prefix("ban:confirm:", Arc::new(Recorder("prefix", calls.clone())));
+
+    // Neither a longer ID nor a shorter prefix matches.
+    assert!(dispatch(&router, &calls, &["ban:cancel:1234", "ban:", "kick:confirm:1234"]).await.is_empty());
+}
+
+#[tokio::test]
+async fn rejects_other_interactions_as_components() {
+    let router = CommandRouter::new();
+    let modal = interaction(5, json!({ "custom_id": "report", "components": [] }));
+    assert!(router.dispatch_component(&Client::new(), "token", &modal).await.is_err());
+}
+
+fn submit(custom_id: &str) -> Interaction {
+    interaction(5, json!({
+        "custom_id": custom_id,
+        "components": [{ "type": 1, "components": [{ "type": 4, "custom_id": "reason", "value": "spam" }] }],
+    }))
+}
+
+#[tokio::test]
+async fn dispatches_modals_by_exact_id_then_prefix() {
+    let calls = Calls::default();
+    let mut router = CommandRouter::new();
+    router.register_modal_prefix("report:", Arc::new(Recorder("prefix", calls.clone())));
+    router.register_modal_prefix("report:user:", Arc::new(Recorder("user", calls.clone())));
+    router.register_modal("report:help", Arc::new(Recorder("exact", calls.clone())));
+
+    for custom_id in ["report:help", "report:user:1234", "report:message:5678", "feedback"] {
+        router.dispatch_modal(&Client::new(), "token", &submit(custom_id)).await.unwrap();
+    }
+    let called: Vec<String> = calls.lock().unwrap().drain(..).collect();
+    assert_eq!(called, ["exact report:help spam", "user report:user:1234 spam", "prefix report:message:5678 spam"]);
+
+    assert!(router.dispatch_modal(&Client::new(), "token", &click("report:help")).await.is_err());
+}
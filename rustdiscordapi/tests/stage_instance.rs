@@ -0,0 +1,125 @@
+mod common;
+
+use serde_json::{json, Value};
+
+use common::mock_api;
+use Rust_Discord_API::event::Event;
+use Rust_Discord_API::stage_instance::{
+    approve_request_to_speak, create_stage_instance, delete_stage_instance, deny_request_to_speak, get_stage_instance, invite_to_speak,
+    modify_stage_instance, request_to_speak, PrivacyLevel, StageInstanceBuilder, MAX_TOPIC_LENGTH,
+};
+
+fn stage(channel_id: &str, topic: &str) -> Value {
+    json!({
+        "id": "840647391636226060",
+        "guild_id": "197038439483310086",
+        "channel_id": channel_id,
+        "topic": topic,
+        "privacy_level": 2,
+        "discoverable_disabled": false,
+        "guild_scheduled_event_id": "947656305244532806",
+    })
+}
+
+#[tokio::test]
+async fn starts_stages_linked_to_scheduled_events() {
+    let api = mock_api();
+    api.route("/stage-instances", |request| (200, stage(request.body["channel_id"].as_str().unwrap(), "Weekly Q&A")));
+
+    let builder = StageInstanceBuilder::new("4901", "Weekly Q&A")
+        .privacy_level(PrivacyLevel::GuildOnly)
+        .send_start_notification(true)
+        .guild_scheduled_event("947656305244532806");
+    let instance = create_stage_instance(&api.client(), "token", &builder, Some("show time")).await.unwrap();
+    assert_eq!(instance.channel_id, "4901");
+    assert_eq!(instance.privacy_level, PrivacyLevel::GuildOnly);
+    assert_eq!(instance.guild_scheduled_event_id.as_deref(), Some("947656305244532806"));
+
+    let request = &api.requests("/stage-instances")[0];
+    assert_eq!((request.method.as_str(), request.path.as_str()), ("POST", "/stage-instances"));
+    assert_eq!(request.headers["x-audit-log-reason"], "show%20time");
+    assert_eq!(request.body, json!({
+        "channel_id": "4901",
+        "topic": "Weekly Q&A",
+        "privacy_level": 2,
+        "send_start_notification": true,
+        "guild_scheduled_event_id": "947656305244532806",
+    }));
+}
+
+#[tokio::test]
+async fn gets_modifies_and_ends_stages() {
+    let api = mock_api();
+    api.route("/stage-instances/4902", |request| match request.method.as_str() {
+        "DELETE" => (204, Value::Null),
+        _ => (200, stage("4902", request.body["topic"].as_str().unwrap_or("Weekly Q&A"))),
+    });
+    let client = api.client();
+
+    assert_eq!(get_stage_instance(&client, "token", "4902").await.unwrap().topic, "Weekly Q&A");
+    let changes = StageInstanceBuilder::edit().topic("Office hours");
+    assert_eq!(modify_stage_instance(&client, "token", "4902", &changes, None).await.unwrap().topic, "Office hours");
+    delete_stage_instance(&client, "token", "4902", Some("over")).await.unwrap();
+
+    let requests = api.requests("/stage-instances/4902");
+    let methods: Vec<&str> = requests.iter().map(|request| request.method.as_str()).collect();
+    assert_eq!(methods, ["GET", "PATCH", "DELETE"]);
+    assert_eq!(requests[1].body, json!({ "topic": "Office hours" }));
+    assert!(!requests[1].headers.contains_key("x-audit-log-reason"));
+    assert_eq!(requests[2].headers["x-audit-log-reason"], "over");
+}
+
+#[tokio::test]
+async fn rejects_invalid_topics_without_a_request() {
+    let api = mock_api();
+    let client = api.client();
+    let too_long = "é".repeat(MAX_TOPIC_LENGTH + 1);
+
+    assert!(create_stage_instance(&client, "token", &StageInstanceBuilder::new("4903", ""), None).await.is_err());
+    assert!(create_stage_instance(&client, "token", &StageInstanceBuilder::new("4903", &too_long), None).await.is_err());
+    assert!(modify_stage_instance(&client, "token", "4903", &StageInstanceBuilder::edit().topic(""), None).await.is_err());
+    assert!(api.requests("/stage-instances").is_empty());
+
+    // Counted in characters, not bytes.
+    let longest = "é".repeat(MAX_TOPIC_LENGTH);
+    api.route("/stage-instances/4904", |_| (200, stage("4904", "topic")));
+    modify_stage_instance(&client, "token", "4904", &StageInstanceBuilder::edit().topic(&longest), None).await.unwrap();
+}
+
+#[tokio::test]
+async fn moderates_speakers() {
+    let api = mock_api();
+    api.route("/guilds/4905/voice-states/", |_| (204, Value::Null));
+    let client = api.client();
+
+    invite_to_speak(&client, "token", "4905", "4906", "1").await.unwrap();
+    approve_request_to_speak(&client, "token", "4905", "4906", "2").await.unwrap();
+    deny_request_to_speak(&client, "token", "4905", "4906", "3").await.unwrap();
+    request_to_speak(&client, "token", "4905", "4906").await.unwrap();
+
+    let requests = api.requests("/guilds/4905/voice-states/");
+    assert!(requests.iter().all(|request| request.method == "PATCH" && request.body["channel_id"] == "4906"));
+    let suppressed: Vec<(&str, &Value)> = requests[..3].iter().map(|request| (request.path.as_str(), &request.body["suppress"])).collect();
+    assert_eq!(suppressed, [
+        ("/guilds/4905/voice-states/1", &json!(false)),
+        ("/guilds/4905/voice-states/2", &json!(false)),
+        ("/guilds/4905/voice-states/3", &json!(true)),
+    ]);
+    assert_eq!(requests[3].path, "/guilds/4905/voice-states/@me");
+    assert!(requests[3].body["request_to_speak_timestamp"].as_str().unwrap().ends_with("+00:00"));
+}
+
+#[test]
+fn parses_typed_stage_instance_events() {
+    let Event::StageInstanceUpdate(instance) = Event::from_dispatch("STAGE_INSTANCE_UPDATE", stage("4907", "Renamed")).unwrap() else {
+        panic!("expected a stage instance");
+    };
+    assert_eq!((instance.channel_id.as_str(), instance.topic.as_str()), ("4907", "Renamed"));
+
+    let mut data = stage("4907", "Ended");
+    data["guild_scheduled_event_id"] = Value::Null;
+    let Event::StageInstanceDelete(instance) = Event::from_dispatch("STAGE_INSTANCE_DELETE", data).unwrap() else {
+        panic!("expected a stage instance");
+    };
+    assert_eq!(instance.guild_scheduled_event_id, None);
+}
@@ -0,0 +1,134 @@
+mod common;
+
+use serde_json::{json, Value};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use common::{mock_api, Request};
+use Rust_Discord_API::guild::{remove_timeout, timeout_member, MAX_TIMEOUT};
+use Rust_Discord_API::model::Member;
+use Rust_Discord_API::timestamp::{format_timestamp, parse_timestamp};
+
+/// Echoes the member back with whatever timeout the request set.
+fn echo_member(request: &Request) -> (u16, Value) {
+    let user_id = request.path.rsplit('/').next().unwrap();
+    let member = json!({
+        "user": { "id": user_id, "username": "someone" },
+        "roles": [],
+        "communication_disabled_until": request.body["communication_disabled_until"],
+    });
+    (200, member)
+}
+
+fn member(communication_disabled_until: Option<&str>) -> Member {
+    serde_json::from_value(json!({ "communication_disabled_until": communication_disabled_until })).unwrap()
+}
+
+#[tokio::test]
+async fn times_out_a_member_with_an_audit_log_reason() {
+    let api = mock_api();
+    api.route("/guilds/4401/", echo_member);
+
+    let before = SystemTime::now();
+    let member = timeout_member(&api.client(), "token", "4401", "20", Duration::from_secs(600), Some("spam & links")).await.unwrap();
+    assert!(member.is_timed_out());
+
+    let requests = api.requests("/guilds/4401/");
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].method, "PATCH");
+    assert_eq!(requests[0].path, "/guilds/4401/members/20");
+    assert_eq!(requests[0].headers["x-audit-log-reason"], "spam%20%26%20links");
+    assert_eq!(requests[0].body.as_object().unwrap().len(), 1);
+
+    let until = parse_timestamp(requests[0].body["communication_disabled_until"].as_str().unwrap()).unwrap();
+    let ahead = until.duration_since(before).unwrap();
+    assert!(ahead > Duration::from_secs(599) && ahead < Duration::from_secs(610), "{:?}", ahead);
+}
+
+#[tokio::test]
+async fn accepts_exactly_the_maximum_timeout() {
+    let api = mock_api();
+    api.route("/guilds/4402/", echo_member);
+
+    let member = timeout_member(&api.client(), "token", "4402", "20", MAX_TIMEOUT, None).await.unwrap();
+    let until = member.timed_out_until().unwrap();
+    assert!(until > SystemTime::now() + MAX_TIMEOUT - Duration::from_secs(10));
+
+    let requests = api.requests("/guilds/4402/");
+    assert_eq!(requests.len(), 1);
+    assert!(!requests[0].headers.contains_key("x-audit-log-reason"));
+}
+
+#[tokio::test]
+async fn rejects_out_of_range_durations_without_a_request() {
+    let api = mock_api();
+    api.route("/guilds/4403/", echo_member);
+
+    let client = api.client();
+    let too_long = MAX_TIMEOUT + Duration::from_secs(1);
+    assert_eq!(MAX_TIMEOUT, Duration::from_secs(28 * 86_400));
+    assert!(timeout_member(&client, "token", "4403", "20", too_long, None).await.is_err());
+    assert!(timeout_member(&client, "token", "4403", "20", Duration::from_secs(365 * 86_400), None).await.is_err());
+    assert!(timeout_member(&client, "token", "4403", "20", Duration::ZERO, None).await.is_err());
+    assert!(api.requests("/guilds/4403/").is_empty());
+}
+
+#[tokio::test]
+async fn removes_a_timeout_by_sending_null() {
+    let api = mock_api();
+    api.route("/guilds/4404/", echo_member);
+
+    let member = remove_timeout(&api.client(), "token", "4404", "21", Some("appealed")).await.unwrap();
+    assert_eq!(member.communication_disabled_until, None);
+    assert!(!member.is_timed_out());
+
+    let requests = api.requests("/guilds/4404/");
+    assert_eq!(requests[0].method, "PATCH");
+    assert_eq!(requests[0].body, json!({ "communication_disabled_until": null }));
+    assert_eq!(requests[0].headers["x-audit-log-reason"], "appealed");
+}
+
+#[tokio::test]
+async fn surfaces_http_errors() {
+    let api = mock_api();
+    api.route("/guilds/4405/", |_| (403, json!({ "message": "Missing Permissions", "code": 50013 })));
+
+    let client = api.client();
+    assert!(timeout_member(&client, "token", "4405", "20", Duration::from_secs(60), None).await.is_err());
+    assert!(remove_timeout(&client, "token", "4405", "20", None).await.is_err());
+    assert_eq!(api.requests("/guilds/4405/").len(), 2);
+}
+
+#[test]
+fn computes_the_timed_out_state() {
+    let now = UNIX_EPOCH + Duration::from_secs(1_639_531_652);
+
+    assert!(!member(None).is_timed_out_at(now));
+    assert!(member(Some("2021-12-15T01:27:33.000000+00:00")).is_timed_out_at(now));
+    assert!(!member(Some("2021-12-15T01:27:32+00:00")).is_timed_out_at(now));
+    // Discord keeps the timestamp after the timeout lapses.
+    let expired = member(Some("2021-12-14T01:27:32.590000+00:00"));
+    assert!(expired.timed_out_until().is_some());
+    assert!(!expired.is_timed_out_at(now));
+    assert!(!expired.is_timed_out());
+    // A timestamp that cannot be read is treated as no timeout.
+    assert!(!member(Some("next tuesday")).is_timed_out_at(now));
+    assert_eq!(member(Some("")).timed_out_until(), None);
+}
+
+#[test]
+fn parses_and_formats_timestamps() {
+    let time = UNIX_EPOCH + Duration::from_millis(1_639_531_652_590);
+    assert_eq!(parse_timestamp("2021-12-15T01:27:32.590000+00:00"), Some(time));
+    assert_eq!(parse_timestamp("2021-12-15T01:27:32.59Z"), Some(time));
+    assert_eq!(parse_timestamp("2021-12-15T03:27:32.590+02:00"), Some(time));
+    assert_eq!(parse_timestamp("2021-12-14T23:27:32.590-02:00"), Some(time));
+    assert_eq!(parse_timestamp(&format_timestamp(time)), Some(time));
+
+    assert_eq!(format_timestamp(UNIX_EPOCH), "1970-01-01T00:00:00.000+00:00");
+    assert_eq!(format_timestamp(UNIX_EPOCH + Duration::from_secs(951_782_400)), "2000-02-29T00:00:00.000+00:00");
+    assert_eq!(format_timestamp(UNIX_EPOCH - Duration::from_millis(1)), "1969-12-31T23:59:59.999+00:00");
+
+    for malformed in ["", "2021-12-15", "2021-13-15T01:27:32Z", "2021-02-29T01:27:32Z", "2021-12-15T01:27:32", "2021-12-15T01:27:32.Z", "2021-12-15T01:27:32+0000"] {
+        assert_eq!(parse_timestamp(malformed), None, "{}", malformed);
+    }
+}
@@ -0,0 +1,215 @@
+use futures::{SinkExt, StreamExt};
+use reqwest::Client;
+use serde_json::{json, Value};
+use std::time::Duration;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::time::timeout;
+use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+use tokio_tungstenite::tungstenite::protocol::CloseFrame;
+use tokio_tungstenite::tungstenite::Message as WsMessage;
+use tokio_tungstenite::{accept_async, WebSocketStream};
+
+use Rust_Discord_API::voice::{VoiceError, VoiceGateway, VoiceServerInfo};
+use Rust_Discord_API::{GatewayClient, GatewayError, Intents};
+
+type Socket = WebSocketStream<TcpStream>;
+
+const MODES: [&str; 2] = ["aead_aes256_gcm_rtpsize", "aead_xchacha20_poly1305_rtpsize"];
+
+async fn listen() -> (TcpListener, String) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    (listener, url)
+}
+
+async fn accept(listener: &TcpListener) -> Socket {
+    let (stream, _) = listener.accept().await.unwrap();
+    accept_async(stream).await.unwrap()
+}
+
+async fn send(socket: &mut Socket, payload: Value) {
+    socket.send(WsMessage::text(payload.to_string())).await.unwrap();
+}
+
+/// Receives the next payload other than a heartbeat, which is opcode 1 on
+/// the main gateway and opcode 3 on the voice gateway.
+async fn receive(socket: &mut Socket, heartbeat: u64) -> Value {
+    loop {
+        match socket.next().await.unwrap().unwrap() {
+            WsMessage::Text(text) => {
+                let payload: Value = serde_json::from_str(&text).unwrap();
+                if payload["op"] != heartbeat {
+                    return payload;
+                }
+                if heartbeat == 3 {
+                    send(socket, json!({ "op": 6, "d": { "t": payload["d"]["t"] } })).await;
+                }
+            }
+            WsMessage::Close(frame) => panic!("client closed the connection: {:?}", frame),
+            _ => {}
+        }
+    }
+}
+
+async fn receive_gateway(socket: &mut Socket) -> Value {
+    receive(socket, 1).await
+}
+
+async fn receive_voice(socket: &mut Socket) -> Value {
+    receive(socket, 3).await
+}
+
+fn dispatch(name: &str, sequence: u64, data: Value) -> Value {
+    json!({ "op": 0, "s": sequence, "t": name, "d": data })
+}
+
+fn voice_state(user_id: &str, channel_id: Option<&str>) -> Value {
+    json!({ "guild_id": "10", "channel_id": channel_id, "user_id": user_id, "session_id": format!("voice-session-{}", user_id), "self_deaf": true })
+}
+
+/// Serves the voice gateway handshake up to the session description.
+async fn serve_voice_handshake(listener: TcpListener) -> (Value, Value, Socket) {
+    let mut socket = accept(&listener).await;
+    send(&mut socket, json!({ "op": 8, "d": { "heartbeat_interval": 41250.0 } })).await;
+    let identify = receive_voice(&mut socket).await;
+    send(&mut socket, json!({ "op": 2, "d": { "ssrc": 42, "ip": "127.0.0.1", "port": 50000, "modes": MODES }, "seq": 1 })).await;
+    let select = receive_voice(&mut socket).await;
+    let key: Vec<u8> = (0..32).collect();
+    send(&mut socket, json!({ "op": 4, "d": { "mode": select["d"]["data"]["mode"], "secret_key": key }, "seq": 2 })).await;
+    (identify, select, socket)
+}
+
+#[tokio::test]
+async fn joins_voice_and_completes_handshake() {
+    let (listener, url) = listen().await;
+    let (voice_listener, voice_url) = listen().await;
+
+    let gateway_server = tokio::spawn(async move {
+        let mut socket = accept(&listener).await;
+        send(&mut socket, json!({ "op": 10, "d": { "heartbeat_interval": 45_000 } })).await;
+        receive_gateway(&mut socket).await;
+        let ready = json!({ "v": 10, "user": { "id": "1", "username": "bot", "bot": true }, "guilds": [], "session_id": "session" });
+        send(&mut socket, dispatch("READY", 1, ready)).await;
+
+        let join = receive_gateway(&mut socket).await;
+        // Events for other users, other guilds and unallocated servers are not the answer.
+        send(&mut socket, dispatch("VOICE_STATE_UPDATE", 2, voice_state("2", Some("30")))).await;
+        send(&mut socket, dispatch("VOICE_SERVER_UPDATE", 3, json!({ "token": "wrong", "guild_id": "11", "endpoint": "elsewhere" }))).await;
+        send(&mut socket, dispatch("VOICE_SERVER_UPDATE", 4, json!({ "token": "pending", "guild_id": "10", "endpoint": null }))).await;
+        send(&mut socket, dispatch("VOICE_SERVER_UPDATE", 5, json!({ "token": "voice-token", "guild_id": "10", "endpoint": voice_url }))).await;
+        send(&mut socket, dispatch("VOICE_STATE_UPDATE", 6, voice_state("1", Some("30")))).await;
+
+        let leave = receive_gateway(&mut socket).await;
+        let frame = CloseFrame { code: CloseCode::from(4004), reason: "done".into() };
+        let _ = socket.close(Some(frame)).await;
+        (join, leave)
+    });
+    let voice_server = tokio::spawn(serve_voice_handshake(voice_listener));
+
+    let gateway = GatewayClient::new(Client::new(), "token", Intents::GUILDS | Intents::GUILD_VOICE_STATES).url(&url);
+    let handle = gateway.handle();
+    let running = tokio::spawn(async move { gateway.run().await });
+
+    let info = timeout(Duration::from_secs(5), handle.join_voice("10", "30", false, true)).await.unwrap().unwrap();
+    assert_eq!(info.user_id, "1");
+    assert_eq!(info.session_id, "voice-session-1");
+    assert_eq!(info.token, "voice-token");
+    assert_eq!(info.channel_id.as_deref(), Some("30"));
+
+    let mut voice = timeout(Duration::from_secs(5), VoiceGateway::connect(&info)).await.unwrap().unwrap();
+    assert_eq!(voice.ready().ssrc, 42);
+    assert_eq!(voice.ready().modes, MODES);
+    assert!(matches!(voice.select_protocol("203.0.113.7", 50001, "xsalsa20_poly1305").await, Err(VoiceError::Protocol(_))));
+    let session = timeout(Duration::from_secs(5), voice.select_protocol("203.0.113.7", 50001, MODES[0])).await.unwrap().unwrap();
+    assert_eq!(session.mode, MODES[0]);
+    assert_eq!(session.secret_key, (0..32).collect::<Vec<u8>>());
+
+    let (identify, select, _socket) = voice_server.await.unwrap();
+    assert_eq!(identify, json!({ "op": 0, "d": { "server_id": "10", "user_id": "1", "session_id": "voice-session-1", "token": "voice-token" } }));
+    assert_eq!(select, json!({ "op": 1, "d": { "protocol": "udp", "data": { "address": "203.0.113.7", "port": 50001, "mode": MODES[0] } } }));
+
+    handle.leave_voice("10").unwrap();
+    let (join, leave) = gateway_server.await.unwrap();
+    assert_eq!(join, json!({ "op": 4, "d": { "guild_id": "10", "channel_id": "30", "self_mute": false, "self_deaf": true } }));
+    assert_eq!(leave["d"]["channel_id"], Value::Null);
+    assert!(matches!(running.await.unwrap(), Err(GatewayError::Closed { code: 4004, .. })));
+}
+
+#[tokio::test]
+async fn join_voice_needs_voice_state_intent() {
+    let gateway = GatewayClient::new(Client::new(), "token", Intents::GUILDS);
+    let result = gateway.handle().join_voice("10", "30", false, false).await;
+    assert!(matches!(result, Err(GatewayError::InvalidRequest(_))));
+}
+
+#[tokio::test(start_paused = true)]
+async fn join_voice_gives_up_without_a_voice_server() {
+    // The gateway never runs, so the voice server is never assigned.
+    let gateway = GatewayClient::new(Client::new(), "token", Intents::GUILDS | Intents::GUILD_VOICE_STATES);
+    let result = gateway.handle().join_voice("10", "30", false, false).await;
+    assert!(matches!(result, Err(GatewayError::Timeout(_))));
+}
+
+fn info(endpoint: &str) -> VoiceServerInfo {
+    VoiceServerInfo {
+        guild_id: "10".to_string(),
+        channel_id: Some("30".to_string()),
+        user_id: "1".to_string(),
+        session_id: "session".to_string(),
+        token: "token".to_string(),
+        endpoint: endpoint.to_string(),
+    }
+}
+
+#[tokio::test]
+async fn voice_heartbeats_acknowledge_the_last_sequence() {
+    let (listener, url) = listen().await;
+
+    let server = tokio::spawn(async move {
+        let mut socket = accept(&listener).await;
+        send(&mut socket, json!({ "op": 8, "d": { "heartbeat_interval": 50.0 } })).await;
+        receive_voice(&mut socket).await;
+        send(&mut socket, json!({ "op": 2, "d": { "ssrc": 1, "ip": "127.0.0.1", "port": 1, "modes": MODES }, "seq": 7 })).await;
+        // A payload the client doesn't handle itself, such as another user connecting.
+        send(&mut socket, json!({ "op": 11, "d": { "user_ids": ["2"] }, "seq": 8 })).await;
+
+        let heartbeat = loop {
+            if let WsMessage::Text(text) = socket.next().await.unwrap().unwrap() {
+                let payload: Value = serde_json::from_str(&text).unwrap();
+                if payload["op"] == 3 {
+                    break payload;
+                }
+            }
+        };
+        send(&mut socket, json!({ "op": 6, "d": { "t": heartbeat["d"]["t"] } })).await;
+        let frame = CloseFrame { code: CloseCode::from(4006), reason: "session no longer valid".into() };
+        let _ = socket.close(Some(frame)).await;
+        heartbeat
+    });
+
+    let mut voice = timeout(Duration::from_secs(5), VoiceGateway::connect(&info(&url))).await.unwrap().unwrap();
+    let heartbeat = server.await.unwrap();
+    assert_eq!(heartbeat["d"]["seq_ack"], 8);
+    assert!(heartbeat["d"]["t"].as_u64().unwrap() > 0);
+
+    let payload = voice.next_payload().await.unwrap().unwrap();
+    assert_eq!(payload["op"], 11);
+    assert!(matches!(voice.next_payload().await, Some(Err(VoiceError::Closed { code: 4006, .. }))));
+}
+
+#[tokio::test]
+async fn voice_connection_ends_without_heartbeat_acknowledgement() {
+    let (listener, url) = listen().await;
+
+    tokio::spawn(async move {
+        let mut socket = accept(&listener).await;
+        send(&mut socket, json!({ "op": 8, "d": { "heartbeat_interval": 20.0 } })).await;
+        send(&mut socket, json!({ "op": 2, "d": { "ssrc": 1, "ip": "127.0.0.1", "port": 1, "modes": MODES } })).await;
+        // Never acknowledge heartbeats.
+        while let Some(Ok(_)) = socket.next().await {}
+    });
+
+    let mut voice = timeout(Duration::from_secs(5), VoiceGateway::connect(&info(&url))).await.unwrap().unwrap();
+    let result = timeout(Duration::from_secs(5), voice.next_payload()).await.unwrap();
+    assert!(matches!(result, Some(Err(VoiceError::Protocol(message))) if message.contains("heartbeat")));
+}
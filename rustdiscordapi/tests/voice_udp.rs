@@ -0,0 +1,230 @@
+#![cfg(feature = "voice")]
+
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::Aes256Gcm;
+use futures::{stream, SinkExt, StreamExt};
+use serde_json::{json, Value};
+use std::time::Duration;
+use tokio::net::{TcpListener, UdpSocket};
+use tokio::time::{timeout, Instant};
+use tokio_tungstenite::accept_async;
+use tokio_tungstenite::tungstenite::Message as WsMessage;
+
+use Rust_Discord_API::voice::{
+    Cipher, EncryptionMode, RtpHeader, SessionDescription, VoiceError, VoiceGateway, VoiceReady, VoiceServerInfo, VoiceUdp, SILENCE_FRAME,
+};
+
+const KEY: [u8; 32] = [7; 32];
+
+/// A loopback stand-in for the voice server's UDP side.
+async fn udp_server() -> (UdpSocket, VoiceReady) {
+    let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let port = socket.local_addr().unwrap().port();
+    let modes = vec![EncryptionMode::XChaCha20Poly1305.name().to_string(), EncryptionMode::Aes256Gcm.name().to_string()];
+    (socket, VoiceReady { ssrc: 42, ip: "127.0.0.1".to_string(), port, modes })
+}
+
+/// Answers one IP discovery request with a fixed external address, and
+/// returns the request.
+async fn serve_discovery(socket: &UdpSocket) -> Vec<u8> {
+    let mut request = [0; 128];
+    let (size, client) = socket.recv_from(&mut request).await.unwrap();
+    // Unrelated packets come first; the client must skip them.
+    socket.send_to(&[0x80, 0x78, 0, 0], client).await.unwrap();
+
+    let mut response = [0; 74];
+    response[0..2].copy_from_slice(&2u16.to_be_bytes());
+    response[2..4].copy_from_slice(&70u16.to_be_bytes());
+    response[4..8].copy_from_slice(&request[4..8]);
+    response[8..19].copy_from_slice(b"203.0.113.7");
+    response[72..74].copy_from_slice(&50001u16.to_be_bytes());
+    socket.send_to(&response, client).await.unwrap();
+    request[..size].to_vec()
+}
+
+async fn receive_packets(socket: &UdpSocket, count: usize) -> Vec<(Vec<u8>, Instant)> {
+    let mut packets = Vec::new();
+    let mut buffer = [0; 1500];
+    while packets.len() < count {
+        let size = timeout(Duration::from_secs(5), socket.recv(&mut buffer)).await.unwrap().unwrap();
+        packets.push((buffer[..size].to_vec(), Instant::now()));
+    }
+    packets
+}
+
+fn session(mode: EncryptionMode) -> SessionDescription {
+    SessionDescription { mode: mode.name().to_string(), secret_key: KEY.to_vec() }
+}
+
+#[tokio::test]
+async fn discovers_the_external_address() {
+    let (server, ready) = udp_server().await;
+    let discovery = tokio::spawn(async move { serve_discovery(&server).await });
+
+    let udp = timeout(Duration::from_secs(5), VoiceUdp::connect(&ready)).await.unwrap().unwrap();
+    assert_eq!(udp.address(), "203.0.113.7");
+    assert_eq!(udp.port(), 50001);
+
+    let request = discovery.await.unwrap();
+    assert_eq!(request.len(), 74);
+    assert_eq!(request[0..2], [0, 1]);
+    assert_eq!(request[2..4], [0, 70]);
+    assert_eq!(request[4..8], 42u32.to_be_bytes());
+    assert!(request[8..].iter().all(|&byte| byte == 0));
+}
+
+#[tokio::test]
+async fn sends_timed_aes_gcm_frames() {
+    let (server, ready) = udp_server().await;
+    let discovery = tokio::spawn(async move {
+        serve_discovery(&server).await;
+        receive_packets(&server, 5).await
+    });
+
+    let udp = VoiceUdp::connect(&ready).await.unwrap();
+    let mut sender = udp.start(&session(EncryptionMode::Aes256Gcm)).unwrap();
+    assert_eq!(sender.ssrc(), 42);
+    let started = Instant::now();
+    for frame in 0..5u8 {
+        sender.send_opus(&[frame; 40]).await.unwrap();
+    }
+    // The first frame goes out at once, each later one 20 ms after the last.
+    assert!(started.elapsed() >= Duration::from_millis(80));
+
+    // Decrypt independently of the library: the header is the associated
+    // data and the trailing counter, padded with zeroes, is the nonce.
+    let aead = Aes256Gcm::new_from_slice(&KEY).unwrap();
+    let packets = discovery.await.unwrap();
+    for (index, (packet, _)) in packets.iter().enumerate() {
+        let header = RtpHeader::parse(packet).unwrap();
+        assert_eq!(header, RtpHeader::new(index as u16, index as u32 * 960, 42));
+        assert_eq!(packet[..2], [0x80, 0x78]);
+
+        let (body, counter) = packet.split_at(packet.len() - 4);
+        assert_eq!(counter, (index as u32).to_be_bytes());
+        let mut nonce = [0; 12];
+        nonce[..4].copy_from_slice(counter);
+        let opus = aead.decrypt(&nonce.into(), Payload { msg: &body[12..], aad: &body[..12] }).unwrap();
+        assert_eq!(opus, vec![index as u8; 40]);
+    }
+    for pair in packets.windows(2) {
+        assert!(pair[1].1 - pair[0].1 >= Duration::from_millis(10));
+    }
+}
+
+#[tokio::test]
+async fn sends_xchacha20_frames() {
+    let (server, ready) = udp_server().await;
+    let discovery = tokio::spawn(async move {
+        serve_discovery(&server).await;
+        receive_packets(&server, 2).await
+    });
+
+    let mut sender = VoiceUdp::connect(&ready).await.unwrap().start(&session(EncryptionMode::XChaCha20Poly1305)).unwrap();
+    sender.send_opus(b"first").await.unwrap();
+    sender.send_opus(b"second").await.unwrap();
+
+    let cipher = Cipher::new(EncryptionMode::XChaCha20Poly1305, &KEY).unwrap();
+    let packets = discovery.await.unwrap();
+    let (header, opus) = cipher.decrypt(&packets[0].0).unwrap();
+    assert_eq!((header.sequence, header.timestamp, opus.as_slice()), (0, 0, &b"first"[..]));
+    let (header, opus) = cipher.decrypt(&packets[1].0).unwrap();
+    assert_eq!((header.sequence, header.timestamp, opus.as_slice()), (1, 960, &b"second"[..]));
+    // 12 header bytes, the payload, a 16-byte tag and the 4-byte counter.
+    assert_eq!(packets[1].0.len(), 12 + 6 + 16 + 4);
+}
+
+#[test]
+fn rejects_bad_keys_and_tampered_packets() {
+    assert!(matches!(Cipher::new(EncryptionMode::Aes256Gcm, &[0; 16]), Err(VoiceError::Protocol(_))));
+
+    let cipher = Cipher::new(EncryptionMode::Aes256Gcm, &KEY).unwrap();
+    let header = RtpHeader::new(5, 4800, 42).to_bytes();
+    let mut packet = cipher.encrypt(&header, b"opus", 9);
+    assert_eq!(cipher.decrypt(&packet).unwrap().1, b"opus");
+
+    // The header is authenticated even though it is sent in the clear.
+    packet[3] ^= 1;
+    assert!(cipher.decrypt(&packet).is_err());
+    let other = Cipher::new(EncryptionMode::Aes256Gcm, &[8; 32]).unwrap();
+    assert!(other.decrypt(&cipher.encrypt(&header, b"opus", 9)).is_err());
+    assert!(cipher.decrypt(&[0x80, 0x78]).is_err());
+}
+
+#[test]
+fn decrypts_packets_with_header_extensions() {
+    let cipher = Cipher::new(EncryptionMode::XChaCha20Poly1305, &KEY).unwrap();
+    let mut header = RtpHeader::new(1, 960, 7);
+    header.extension = true;
+    // The extension's profile and length are authenticated, its one word of
+    // data is encrypted along with the audio.
+    let mut authenticated = header.to_bytes().to_vec();
+    authenticated.extend_from_slice(&[0xBE, 0xDE, 0, 1]);
+    let packet = cipher.encrypt(&authenticated, &[1, 2, 3, 4, b'o', b'p', b'u', b's'], 0);
+
+    let (parsed, opus) = cipher.decrypt(&packet).unwrap();
+    assert!(parsed.extension);
+    assert_eq!(opus, b"opus");
+}
+
+#[tokio::test]
+async fn plays_opus_with_speaking_flags_and_trailing_silence() {
+    let (server, ready) = udp_server().await;
+    let udp_port = ready.port;
+    let udp_side = tokio::spawn(async move {
+        serve_discovery(&server).await;
+        receive_packets(&server, 8).await
+    });
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    let gateway_side = tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut socket = accept_async(stream).await.unwrap();
+        let mut payloads = Vec::new();
+        socket.send(WsMessage::text(json!({ "op": 8, "d": { "heartbeat_interval": 41250.0 } }).to_string())).await.unwrap();
+        while let Some(Ok(message)) = socket.next().await {
+            let WsMessage::Text(text) = message else { continue };
+            let payload: Value = serde_json::from_str(&text).unwrap();
+            let reply = match payload["op"].as_u64() {
+                Some(0) => json!({ "op": 2, "d": { "ssrc": 42, "ip": "127.0.0.1", "port": udp_port, "modes": [EncryptionMode::Aes256Gcm.name()] } }),
+                Some(1) => json!({ "op": 4, "d": { "mode": payload["d"]["data"]["mode"], "secret_key": KEY } }),
+                _ => {
+                    payloads.push(payload);
+                    if payloads.len() == 2 {
+                        return payloads;
+                    }
+                    continue;
+                }
+            };
+            socket.send(WsMessage::text(reply.to_string())).await.unwrap();
+        }
+        payloads
+    });
+
+    let info = VoiceServerInfo {
+        guild_id: "10".to_string(),
+        channel_id: Some("30".to_string()),
+        user_id: "1".to_string(),
+        session_id: "session".to_string(),
+        token: "token".to_string(),
+        endpoint: url,
+    };
+    let mut voice = timeout(Duration::from_secs(5), VoiceGateway::connect(&info)).await.unwrap().unwrap();
+    let udp = VoiceUdp::connect(voice.ready()).await.unwrap();
+    let mode = EncryptionMode::preferred(&voice.ready().modes).unwrap();
+    let session = voice.select_protocol(udp.address(), udp.port(), mode.name()).await.unwrap();
+    let mut sender = udp.start(&session).unwrap();
+
+    let opus = vec![b"one".to_vec(), b"two".to_vec(), b"three".to_vec()];
+    timeout(Duration::from_secs(5), sender.play(&voice, stream::iter(opus))).await.unwrap().unwrap();
+
+    let speaking = gateway_side.await.unwrap();
+    assert_eq!(speaking[0], json!({ "op": 5, "d": { "speaking": 1, "delay": 0, "ssrc": 42 } }));
+    assert_eq!(speaking[1], json!({ "op": 5, "d": { "speaking": 0, "delay": 0, "ssrc": 42 } }));
+
+    let cipher = Cipher::new(mode, &KEY).unwrap();
+    let payloads: Vec<Vec<u8>> = udp_side.await.unwrap().iter().map(|(packet, _)| cipher.decrypt(packet).unwrap().1).collect();
+    assert_eq!(payloads[..3], [b"one".to_vec(), b"two".to_vec(), b"three".to_vec()]);
+    assert!(payloads[3..].iter().all(|payload| payload == &SILENCE_FRAME));
+}